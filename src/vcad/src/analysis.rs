@@ -0,0 +1,487 @@
+//! Geometric measurements of built parts.
+
+use nalgebra::{Point3, Vector3};
+use vcad::Part;
+
+use crate::mesh::TriMesh;
+
+/// Volume-weighted centroid of the part, assuming uniform density.
+///
+/// Sums the signed tetrahedra formed by each triangle and the origin, so
+/// cavities and holes pull the result correctly.
+pub fn center_of_mass(part: &Part) -> Point3<f64> {
+    let [x, y, z] = part.center_of_mass();
+    Point3::new(x, y, z)
+}
+
+/// Enclosed volume of the part (mm³): the signed tetrahedra from the origin
+/// to each triangle, summed.
+pub fn volume(part: &Part) -> f64 {
+    TriMesh::from_part(part).volume()
+}
+
+/// Mass of the part printed solid in a material of `density_g_cm3` (g).
+pub fn estimate_mass(part: &Part, density_g_cm3: f64) -> f64 {
+    volume(part) / 1000.0 * density_g_cm3
+}
+
+/// Axis-aligned bounds of the part's mesh, as `(min, max)` corners.
+pub fn bounding_box(part: &Part) -> (Point3<f64>, Point3<f64>) {
+    TriMesh::from_part(part).bounds()
+}
+
+/// Axes along which a part `size` across overruns a `bed` build volume.
+pub fn over_bed(size: Vector3<f64>, bed: [f64; 3]) -> Vec<&'static str> {
+    ["X", "Y", "Z"].into_iter().zip(size.iter().zip(bed)).filter(|(_, (extent, limit))| **extent > *limit).map(|(axis, _)| axis).collect()
+}
+
+/// A coordinate axis, for measurements about a line through the origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub const ALL: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+
+    fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        ["X", "Y", "Z"][self.index()]
+    }
+}
+
+/// Smallest cylinder about `axis` (through the origin) that encloses the
+/// part, as `(radius, height)`.
+///
+/// Round parts are built centered on their axis, so this is their envelope:
+/// more telling than an AABB, whose corners overstate a circle's reach.
+pub fn bounding_cylinder(part: &Part, axis: Axis) -> (f64, f64) {
+    let k = axis.index();
+    let (i, j) = ((k + 1) % 3, (k + 2) % 3);
+    let mesh = TriMesh::from_part(part);
+    let radius = mesh.vertices.iter().map(|v| v[i].hypot(v[j])).fold(0.0, f64::max);
+    let (min, max) = mesh.bounds();
+    (radius, max[k] - min[k])
+}
+
+/// The narrowest of the part's bounding cylinders about X, Y and Z, as
+/// `(axis, radius, height)`. For a round part built on a coordinate axis,
+/// that axis is the one picked.
+pub fn tightest_bounding_cylinder(part: &Part) -> (Axis, f64, f64) {
+    Axis::ALL
+        .into_iter()
+        .map(|axis| {
+            let (radius, height) = bounding_cylinder(part, axis);
+            (axis, radius, height)
+        })
+        .fold((Axis::Z, f64::INFINITY, 0.0), |best, next| if next.1 < best.1 { next } else { best })
+}
+
+/// Signed volume of each connected shell of the part, largest first.
+///
+/// The kernel never shares a vertex between separate shells, so shells
+/// sharing a vertex are the same shell. A positive volume is a separate
+/// solid body; a negative one is an enclosed cavity inside a body.
+pub fn shell_volumes(part: &Part) -> Vec<f64> {
+    let mut volumes: Vec<f64> = TriMesh::from_part(part).components().iter().map(TriMesh::volume).collect();
+    volumes.sort_by(|a, b| b.total_cmp(a));
+    volumes
+}
+
+/// Area of the part's shadow on the XY plane (mm²).
+///
+/// This is the union of all projected triangles, so holes through the part
+/// are excluded and overhangs count once. Every point of the shadow lies
+/// under some upward-facing triangle, so only those are projected. The union
+/// is integrated with horizontal scanlines sampled between vertex Y values,
+/// which is exact for straight edges up to where projected edges cross.
+pub fn footprint_area(part: &Part) -> f64 {
+    const SAMPLES_PER_SLAB: usize = 4;
+
+    let mesh = TriMesh::from_part(part);
+    let triangles: Vec<[(f64, f64); 3]> = (0..mesh.triangles.len())
+        .filter(|&i| mesh.normal(i).z > 1e-9)
+        .map(|i| mesh.corners(i).map(|p| (p.x, p.y)))
+        .collect();
+
+    let mut ys: Vec<f64> = triangles.iter().flat_map(|t| t.iter().map(|p| p.1)).collect();
+    ys.sort_by(f64::total_cmp);
+    ys.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+
+    let mut area = 0.0;
+    for slab in ys.windows(2) {
+        let dy = (slab[1] - slab[0]) / SAMPLES_PER_SLAB as f64;
+        for k in 0..SAMPLES_PER_SLAB {
+            let y = slab[0] + dy * (k as f64 + 0.5);
+            area += covered_length(&triangles, y) * dy;
+        }
+    }
+    area
+}
+
+/// Total length of the union of the X intervals where the scanline at `y`
+/// crosses the given triangles.
+fn covered_length(triangles: &[[(f64, f64); 3]], y: f64) -> f64 {
+    let mut spans: Vec<(f64, f64)> = Vec::new();
+    for tri in triangles {
+        let mut xs = Vec::with_capacity(2);
+        for k in 0..3 {
+            let (a, b) = (tri[k], tri[(k + 1) % 3]);
+            if (a.1 <= y) != (b.1 <= y) {
+                xs.push(a.0 + (y - a.1) / (b.1 - a.1) * (b.0 - a.0));
+            }
+        }
+        if let [x0, x1] = xs[..] {
+            spans.push((x0.min(x1), x0.max(x1)));
+        }
+    }
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut length = 0.0;
+    let mut current: Option<(f64, f64)> = None;
+    for (lo, hi) in spans {
+        match current {
+            Some((start, end)) if lo <= end => current = Some((start, end.max(hi))),
+            Some((start, end)) => {
+                length += end - start;
+                current = Some((lo, hi));
+            }
+            None => current = Some((lo, hi)),
+        }
+    }
+    if let Some((start, end)) = current {
+        length += end - start;
+    }
+    length
+}
+
+/// Whether `point` lies inside the solid.
+///
+/// Casts rays from the point and counts surface crossings (odd = inside).
+/// A ray that grazes an edge or vertex can miscount, so three rays in
+/// pseudo-random directions are cast and the majority wins.
+#[cfg(test)]
+pub fn contains_point(part: &Part, point: Point3<f64>) -> bool {
+    mesh_contains_point(&TriMesh::from_part(part), point)
+}
+
+/// [`contains_point`] on an already extracted mesh, for repeated queries.
+pub fn mesh_contains_point(mesh: &TriMesh, point: Point3<f64>) -> bool {
+    let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next = || {
+        // xorshift64 mapped to [-1, 1)
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    };
+
+    let mut inside_votes = 0;
+    for _ in 0..3 {
+        let dir = loop {
+            let v = Vector3::new(next(), next(), next());
+            if let Some(unit) = v.try_normalize(1e-3) {
+                break unit;
+            }
+        };
+        let crossings = (0..mesh.triangles.len())
+            .filter(|&i| ray_triangle_distance(point, dir, mesh.corners(i)).is_some())
+            .count();
+        if crossings % 2 == 1 {
+            inside_votes += 1;
+        }
+    }
+    inside_votes >= 2
+}
+
+/// Distance along `dir` to the nearest surface hit in front of `origin`.
+pub fn ray_distance(mesh: &TriMesh, origin: Point3<f64>, dir: Vector3<f64>) -> Option<f64> {
+    (0..mesh.triangles.len())
+        .filter_map(|i| ray_triangle_distance(origin, dir, mesh.corners(i)))
+        .min_by(f64::total_cmp)
+}
+
+/// Smallest distance between the surfaces of two parts: 0 where they touch
+/// or cross. Triangle pairs whose bounding boxes are already further apart
+/// than the best distance so far are skipped.
+pub fn min_distance(a: &Part, b: &Part) -> f64 {
+    let (a, b) = (TriMesh::from_part(a), TriMesh::from_part(b));
+    let boxes = |mesh: &TriMesh| -> Vec<(Point3<f64>, Point3<f64>)> {
+        (0..mesh.triangles.len())
+            .map(|i| {
+                let [p, q, r] = mesh.corners(i);
+                (p.inf(&q).inf(&r), p.sup(&q).sup(&r))
+            })
+            .collect()
+    };
+    let (boxes_a, boxes_b) = (boxes(&a), boxes(&b));
+    let whole_b = b.bounds();
+    let mut best = f64::INFINITY;
+    for (i, box_a) in boxes_a.iter().enumerate() {
+        if box_gap(*box_a, whole_b) >= best {
+            continue;
+        }
+        for (j, box_b) in boxes_b.iter().enumerate() {
+            if box_gap(*box_a, *box_b) >= best {
+                continue;
+            }
+            best = best.min(triangle_distance(a.corners(i), b.corners(j)));
+            if best == 0.0 {
+                return 0.0;
+            }
+        }
+    }
+    best
+}
+
+/// Distance between two axis-aligned boxes (0 when they overlap).
+fn box_gap((min_a, max_a): (Point3<f64>, Point3<f64>), (min_b, max_b): (Point3<f64>, Point3<f64>)) -> f64 {
+    let gap = Vector3::from_fn(|k, _| (min_a[k] - max_b[k]).max(min_b[k] - max_a[k]).max(0.0));
+    gap.norm()
+}
+
+/// Distance between two triangles: 0 if an edge of either crosses the
+/// other, otherwise the closest vertex-face or edge-edge pair.
+fn triangle_distance(s: [Point3<f64>; 3], t: [Point3<f64>; 3]) -> f64 {
+    let edges = |tri: [Point3<f64>; 3]| [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])];
+    let crosses = |(p, q): (Point3<f64>, Point3<f64>), tri| ray_triangle_distance(p, q - p, tri).is_some_and(|t| t <= 1.0);
+    if edges(s).into_iter().any(|e| crosses(e, t)) || edges(t).into_iter().any(|e| crosses(e, s)) {
+        return 0.0;
+    }
+    let mut best = f64::INFINITY;
+    for p in s {
+        best = best.min((p - closest_on_triangle(p, t)).norm());
+    }
+    for p in t {
+        best = best.min((p - closest_on_triangle(p, s)).norm());
+    }
+    for e in edges(s) {
+        for f in edges(t) {
+            best = best.min(segment_distance(e, f));
+        }
+    }
+    best
+}
+
+/// Closest point to `p` on triangle `[a, b, c]` (Ericson, Real-Time
+/// Collision Detection, 5.1.5).
+fn closest_on_triangle(p: Point3<f64>, [a, b, c]: [Point3<f64>; 3]) -> Point3<f64> {
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+/// Distance between segments `p1-q1` and `p2-q2` (Ericson 5.1.9).
+fn segment_distance((p1, q1): (Point3<f64>, Point3<f64>), (p2, q2): (Point3<f64>, Point3<f64>)) -> f64 {
+    let (d1, d2, r) = (q1 - p1, q2 - p2, p1 - p2);
+    let (a, e, f) = (d1.norm_squared(), d2.norm_squared(), d2.dot(&r));
+    let (s, t) = if a <= 1e-18 && e <= 1e-18 {
+        (0.0, 0.0)
+    } else if a <= 1e-18 {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = d1.dot(&r);
+        if e <= 1e-18 {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = d1.dot(&d2);
+            let denom = a * e - b * b;
+            let mut s = if denom > 1e-18 { ((b * f - c * e) / denom).clamp(0.0, 1.0) } else { 0.0 };
+            let mut t = (b * s + f) / e;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / a).clamp(0.0, 1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((b - c) / a).clamp(0.0, 1.0);
+            }
+            (s, t)
+        }
+    };
+    ((p1 + d1 * s) - (p2 + d2 * t)).norm()
+}
+
+/// Möller–Trumbore ray/triangle test; the hit distance for hits in front
+/// of the origin.
+fn ray_triangle_distance(origin: Point3<f64>, dir: Vector3<f64>, [a, b, c]: [Point3<f64>; 3]) -> Option<f64> {
+    let (e1, e2) = (b - a, c - a);
+    let p = dir.cross(&e2);
+    let det = e1.dot(&p);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let s = origin - a;
+    let u = s.dot(&p) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(&e1);
+    let v = dir.dot(&q) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = e2.dot(&q) / det;
+    (t > 1e-9).then_some(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vcad::{centered_cube, centered_cylinder};
+
+    #[test]
+    fn bounding_cylinder_is_radius_and_length_about_the_axis() {
+        let rod = centered_cylinder("rod", 5.0, 10.0, 64).translate(0.0, 0.0, 20.0);
+        let (radius, height) = bounding_cylinder(&rod, Axis::Z);
+        assert!((radius - 5.0).abs() < 1e-5 && (height - 10.0).abs() < 1e-5);
+        // A square reaches its corners, beyond the inscribed circle.
+        let (radius, height) = bounding_cylinder(&centered_cube("cube", 10.0, 10.0, 4.0), Axis::X);
+        assert!((radius - 29f64.sqrt()).abs() < 1e-5 && (height - 10.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn tightest_bounding_cylinder_finds_a_rods_axis() {
+        let rod = centered_cylinder("rod", 5.0, 30.0, 64).rotate(90.0, 0.0, 0.0);
+        let (axis, radius, height) = tightest_bounding_cylinder(&rod);
+        assert_eq!(axis, Axis::Y);
+        assert!((radius - 5.0).abs() < 1e-5 && (height - 30.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn cylinder_bounding_box_is_its_diameter_by_its_height() {
+        let (min, max) = bounding_box(&centered_cylinder("rod", 5.0, 20.0, 64));
+        assert!((max - min - Vector3::new(10.0, 10.0, 20.0)).norm() < 1e-5, "got {}", max - min);
+        assert!((min - Point3::new(-5.0, -5.0, -10.0)).norm() < 1e-5);
+        assert_eq!(over_bed(max - min, [256.0, 256.0, 256.0]), Vec::<&str>::new());
+        assert_eq!(over_bed(max - min, [8.0, 256.0, 15.0]), vec!["X", "Z"]);
+    }
+
+    #[test]
+    fn volume_and_mass_of_a_known_cube() {
+        let cube = centered_cube("cube", 10.0, 10.0, 10.0);
+        assert!((volume(&cube) - 1000.0).abs() < 1e-6, "got {}", volume(&cube));
+        assert!((volume(&cube.translate(25.0, -3.0, 7.0)) - 1000.0).abs() < 1e-6, "independent of placement");
+        assert!((estimate_mass(&cube, 1.24) - 1.24).abs() < 1e-9);
+        // A bored cube loses the bore's volume.
+        let bored = cube - centered_cylinder("bore", 2.0, 12.0, 64);
+        assert!((volume(&bored) - bored.volume()).abs() < 1e-6 && volume(&bored) < 1000.0);
+    }
+
+    #[test]
+    fn symmetric_cube_center_of_mass_is_origin() {
+        let cube = centered_cube("cube", 10.0, 10.0, 10.0);
+        let com = center_of_mass(&cube);
+        assert!(com.coords.norm() < 1e-6, "expected origin, got {com}");
+    }
+
+    #[test]
+    fn offset_cube_center_of_mass_follows_translation() {
+        let cube = centered_cube("cube", 4.0, 6.0, 8.0).translate(3.0, -2.0, 5.0);
+        let com = center_of_mass(&cube);
+        assert!((com - Point3::new(3.0, -2.0, 5.0)).norm() < 1e-6, "got {com}");
+    }
+
+    #[test]
+    fn surface_area_matches_cube_and_faceted_cylinder() {
+        assert!((centered_cube("cube", 1.0, 1.0, 1.0).surface_area() - 6.0).abs() < 1e-6);
+        // A 64-gon prism: two polygon caps plus 64 flat sides.
+        let (r, h, n) = (3.0, 10.0, 64.0);
+        let side = 2.0 * r * (std::f64::consts::PI / n).sin();
+        let cap = 0.5 * n * r * r * (2.0 * std::f64::consts::PI / n).sin();
+        let area = centered_cylinder("rod", r, h, 64).surface_area();
+        assert!((area - (2.0 * cap + n * side * h)).abs() < 1e-3, "got {area}");
+        assert!((area - 2.0 * std::f64::consts::PI * r * (r + h)).abs() / area < 0.01, "within 1% of the true cylinder");
+    }
+
+    #[test]
+    fn shell_volumes_separate_bodies_and_cavities() {
+        let apart = centered_cube("a", 2.0, 2.0, 2.0) + centered_cube("b", 1.0, 1.0, 1.0).translate(5.0, 0.0, 0.0);
+        let volumes = shell_volumes(&apart);
+        assert_eq!(volumes.len(), 2);
+        assert!((volumes[0] - 8.0).abs() < 1e-5 && (volumes[1] - 1.0).abs() < 1e-5);
+        let hollow = centered_cube("box", 4.0, 4.0, 4.0) - centered_cube("void", 2.0, 2.0, 2.0);
+        let volumes = shell_volumes(&hollow);
+        assert!((volumes[0] - 64.0).abs() < 1e-5 && (volumes[1] + 8.0).abs() < 1e-5, "got {:?}", volumes);
+    }
+
+    #[test]
+    fn min_distance_measures_face_edge_and_corner_gaps() {
+        let cube = centered_cube("a", 2.0, 2.0, 2.0);
+        assert!((min_distance(&cube, &cube.translate(5.0, 0.0, 0.0)) - 3.0).abs() < 1e-6, "face to face");
+        let corner = min_distance(&cube, &cube.translate(3.0, 3.0, 3.0));
+        assert!((corner - 3f64.sqrt()).abs() < 1e-6, "corner to corner, got {corner}");
+        assert_eq!(min_distance(&cube, &cube.translate(1.0, 0.5, 0.0)), 0.0, "overlapping");
+        let rod = centered_cylinder("rod", 1.0, 10.0, 64).rotate(90.0, 0.0, 0.0).translate(0.0, 0.0, 3.5);
+        let gap = min_distance(&cube, &rod);
+        assert!((gap - 1.5).abs() < 1e-6, "cube top to the rod's lowest facet, got {gap}");
+    }
+
+    #[test]
+    fn box_footprint_is_length_times_width() {
+        let block = centered_cube("block", 10.0, 20.0, 5.0);
+        assert!((footprint_area(&block) - 200.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn footprint_excludes_holes_and_counts_overlaps_once() {
+        let plate = centered_cube("plate", 20.0, 20.0, 4.0);
+        let hole = centered_cylinder("hole", 3.0, 20.0, 64);
+        let stacked = centered_cube("stacked", 10.0, 10.0, 4.0).translate(0.0, 0.0, 4.0);
+        let area = footprint_area(&((plate + stacked) - hole));
+        // Hole area is the inscribed 64-gon, slightly less than πr².
+        let hole_area = 0.5 * 64.0 * 9.0 * (2.0 * std::f64::consts::PI / 64.0).sin();
+        assert!((area - (400.0 - hole_area)).abs() < 0.05, "got {area}");
+    }
+
+    #[test]
+    fn contains_point_sees_solid_and_hole() {
+        let plate = centered_cube("plate", 20.0, 20.0, 4.0) - centered_cylinder("hole", 3.0, 6.0, 32);
+        assert!(contains_point(&plate, Point3::new(6.0, 6.0, 0.0)));
+        assert!(!contains_point(&plate, Point3::new(0.0, 0.0, 0.0)));
+        assert!(!contains_point(&plate, Point3::new(0.0, 0.0, 5.0)));
+    }
+
+    #[test]
+    fn contains_point_is_robust_on_symmetry_planes() {
+        // Rays from a point on the cube's symmetry planes would graze edges
+        // if cast along an axis.
+        let cube = centered_cube("cube", 10.0, 10.0, 10.0);
+        assert!(contains_point(&cube, Point3::origin()));
+        assert!(!contains_point(&cube, Point3::new(0.0, 0.0, 5.5)));
+    }
+}
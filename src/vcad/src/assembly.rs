@@ -0,0 +1,128 @@
+//! The whole applicator in one model, every part placed on the frame.
+//!
+//! Placements come from the same `frame` functions that position the frame's
+//! own holes, post and wall, so a part can't drift away from the features
+//! it mounts to. `--exploded` instead follows `models/assembly_manifest.json`.
+
+use vcad::Part;
+
+use crate::config::Config;
+use crate::geometry::PartExt;
+use crate::interfaces::{self, Layout};
+use crate::{dancer_arm, frame, guide_roller_bracket, peel_plate, spool_holder, vial_cradle};
+
+/// Where each component's bottom center goes and its Z rotation (degrees),
+/// with the frame's bottom on Z=0, in the form `interfaces::place_part` takes.
+///
+/// Parts bolted to the base stand on its top face at their frame centers.
+/// The peel plate is turned so its rear face (-Y) lies against the mounting
+/// wall, and the dancer arm's pivot hub sits flush with the pivot post top.
+pub fn layout(cfg: &Config) -> Layout {
+    let deck = frame::base_top_z(cfg) - frame::base_bottom_z(cfg);
+    let post_top = deck + cfg.pivot_post_height;
+    let peel_x = frame::peel_wall_x(cfg) - cfg.frame_wall_thickness / 2.0 - cfg.peel_body_depth / 2.0;
+    let on_deck = |(x, y): (f64, f64)| (x, y, deck, 0.0);
+    let (pivot_x, pivot_y) = frame::pivot_center(cfg);
+    [
+        ("main_frame", (0.0, 0.0, 0.0, 0.0)),
+        ("peel_plate", (peel_x, 0.0, deck, 90.0)),
+        ("vial_cradle", on_deck(frame::cradle_center(cfg))),
+        ("spool_holder", on_deck(frame::spool_center(cfg))),
+        ("dancer_arm", (pivot_x - dancer_arm::PIVOT_XY.0, pivot_y - dancer_arm::PIVOT_XY.1, post_top - cfg.dancer_arm_thickness, 0.0)),
+        ("guide_roller_bracket", on_deck(frame::guide_center(cfg))),
+    ]
+    .into_iter()
+    .map(|(name, pose)| (name.to_string(), pose))
+    .collect()
+}
+
+/// Every component built and placed by [`layout`], keyed by component name,
+/// for formats that keep parts apart (3MF).
+pub fn placed(cfg: &Config) -> Vec<(&'static str, Part)> {
+    let layout = layout(cfg);
+    let parts = [
+        ("main_frame", frame::build(cfg), frame::base_bottom_z(cfg)),
+        ("peel_plate", peel_plate::build(cfg), peel_plate::base_bottom_z(cfg)),
+        ("vial_cradle", vial_cradle::build(cfg), vial_cradle::base_bottom_z(cfg)),
+        ("spool_holder", spool_holder::build(cfg), spool_holder::base_bottom_z(cfg)),
+        ("dancer_arm", dancer_arm::build(cfg), dancer_arm::base_bottom_z(cfg)),
+        ("guide_roller_bracket", guide_roller_bracket::build(cfg), guide_roller_bracket::base_bottom_z(cfg)),
+    ];
+    parts.into_iter().map(|(name, part, bottom_z)| (name, interfaces::place_part(&part, bottom_z, layout[name], 0.0))).collect()
+}
+
+/// The `placed` parts unioned into the single `assembly` part.
+pub fn merge(placed: &[(&str, Part)]) -> Part {
+    placed.iter().fold(Part::empty("assembly"), |assembly, (_, part)| &assembly + part).with_name("assembly")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_config;
+
+    #[test]
+    fn parts_land_on_the_frame_features_they_mount_to() {
+        let cfg = load_config(None).unwrap();
+        let layout = layout(&cfg);
+        for interface in interfaces::check(&cfg, &layout).unwrap() {
+            assert!(interface.worst < 1e-9, "{} holes off by {}", interface.component, interface.worst);
+        }
+        let place = |name: &str, part: Part, bottom_z: f64| interfaces::place_part(&part, bottom_z, layout[name], 0.0).bounding_box();
+
+        // The peel plate's rear face meets the wall's inner face.
+        let (_, plate_max) = place("peel_plate", peel_plate::build(&cfg), peel_plate::base_bottom_z(&cfg));
+        assert!((plate_max[0] - (frame::peel_wall_x(&cfg) - cfg.frame_wall_thickness / 2.0)).abs() < 1e-5);
+        // The arm's top is level with the post top.
+        let (_, arm_max) = place("dancer_arm", dancer_arm::build(&cfg), dancer_arm::base_bottom_z(&cfg));
+        let (_, frame_max) = place("main_frame", frame::build(&cfg), frame::base_bottom_z(&cfg));
+        assert!((arm_max[2] - frame_max[2]).abs() < 1e-5, "arm top {} vs frame top {}", arm_max[2], frame_max[2]);
+
+        let (min, _) = merge(&placed(&cfg)).bounding_box();
+        assert!(min[2].abs() < 1e-5, "the frame stands on Z=0");
+    }
+
+    #[test]
+    fn three_mf_keeps_each_component_a_colored_object() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.colors.remove("dancer_arm");
+        let placed = placed(&cfg);
+        let stem = std::env::temp_dir().join(format!("vialbel-assembly-{}", std::process::id()));
+        let options = crate::output::MeshOptions { merge: true, shading: crate::output::Shading::Flat, budget: None, stl: crate::output::StlEncoding::Binary };
+        let (path, triangles) = crate::output::export_3mf(&placed, stem.to_str().unwrap(), "assembly", &cfg.colors, &Default::default(), &options).unwrap();
+        let package = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Open the zip by its central directory and read the stored model.
+        let le16 = |at: usize| u16::from_le_bytes(package[at..at + 2].try_into().unwrap()) as usize;
+        let le32 = |at: usize| u32::from_le_bytes(package[at..at + 4].try_into().unwrap()) as usize;
+        let end = package.len() - 22;
+        assert!(package[end..].starts_with(b"PK\x05\x06"));
+        let mut entry = le32(end + 16);
+        let mut model = None;
+        for _ in 0..le16(end + 10) {
+            assert!(package[entry..].starts_with(b"PK\x01\x02"));
+            let name = &package[entry + 46..entry + 46 + le16(entry + 28)];
+            let local = le32(entry + 42);
+            assert!(package[local..].starts_with(b"PK\x03\x04"));
+            let data = local + 30 + le16(local + 26) + le16(local + 28);
+            if name == b"3D/3dmodel.model" {
+                model = Some(String::from_utf8(package[data..data + le32(entry + 20)].to_vec()).unwrap());
+            }
+            entry += 46 + name.len();
+        }
+        assert_eq!(entry, end);
+        let model = model.expect("3D/3dmodel.model in the package");
+
+        assert_eq!(model.matches("<object ").count(), placed.len());
+        assert_eq!(model.matches("<item ").count(), placed.len());
+        assert_eq!(model.matches("<triangle ").count(), triangles);
+        for (index, (name, _)) in placed.iter().enumerate() {
+            let color = cfg.colors.get(*name).map_or(crate::output::FALLBACK_COLORS[index], String::as_str);
+            assert!(model.contains(&format!("<base name=\"{}\" displaycolor=\"{}\"/>", name, color)), "{name} has no {color} material");
+            assert!(model.contains(&format!("name=\"{}\" type=\"model\" pid=\"1\" pindex=\"{}\"", name, index)), "{name} doesn't use its material");
+        }
+        let colors: std::collections::HashSet<&str> = model.lines().filter_map(|l| l.split("displaycolor=\"").nth(1)).collect();
+        assert_eq!(colors.len(), placed.len(), "every component gets its own color");
+    }
+}
@@ -0,0 +1,358 @@
+//! Whole-part correctness checks for `--validate-all`.
+//!
+//! Each check looks at a finished part and, for the hole checks, the cutters
+//! its builder marked as features. They are a pre-merge gate for geometry
+//! regressions, so they favour simple sampling over exact answers.
+
+use nalgebra::{Point3, Vector3};
+use vcad::Part;
+
+use crate::analysis;
+use crate::features::base_name;
+use crate::mesh::TriMesh;
+use crate::output;
+use crate::print_in_place;
+use crate::validate::MIN_WALL;
+
+/// How far past each end of a hole cutter the through-hole check looks for
+/// solid (mm).
+const END_PROBE: f64 = 0.1;
+
+/// Spacing of the samples the through-hole check takes along each hole's
+/// axis (mm): a membrane left across the hole at least this thick is found.
+const AXIS_STEP: f64 = 0.25;
+
+/// A geometry check that can be run on every built part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Check {
+    /// The boolean result has triangles at all.
+    Empty,
+    /// Every edge joins exactly two consistently wound triangles.
+    Manifold,
+    /// No disconnected island of solid floats beside the part.
+    Floating,
+    /// Holes marked as features run clear through the part.
+    ThroughHoles,
+    /// No two holes cut into each other.
+    OverlappingHoles,
+    /// No wall is thinner than the printable minimum.
+    MinWall,
+    /// The STL writer and reader agree on the part's mesh.
+    StlRoundTrip,
+}
+
+impl Check {
+    pub const ALL: [Check; 7] = [Check::Empty, Check::Manifold, Check::Floating, Check::ThroughHoles, Check::OverlappingHoles, Check::MinWall, Check::StlRoundTrip];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::Empty => "empty",
+            Check::Manifold => "manifold",
+            Check::Floating => "floating",
+            Check::ThroughHoles => "through-holes",
+            Check::OverlappingHoles => "overlapping-holes",
+            Check::MinWall => "min-wall",
+            Check::StlRoundTrip => "stl-round-trip",
+        }
+    }
+}
+
+/// A short summary when the check passes, every problem found when it fails.
+pub type Outcome = Result<String, Vec<String>>;
+
+/// Run `check` on `part`, whose builder marked `features`.
+pub fn run(check: Check, part: &Part, features: &[Part]) -> Outcome {
+    match check {
+        Check::Empty => check_empty(part),
+        Check::Manifold => check_manifold(&TriMesh::from_part(part)),
+        Check::Floating => check_floating(part),
+        Check::ThroughHoles => check_through_holes(&TriMesh::from_part(part), &holes(features)),
+        Check::OverlappingHoles => check_overlapping_holes(&holes(features)),
+        Check::MinWall => check_min_wall(&TriMesh::from_part(part)),
+        Check::StlRoundTrip => output::stl_round_trip(part).map(|n| format!("{} triangles", n)).map_err(|e| vec![e]),
+    }
+}
+
+fn check_empty(part: &Part) -> Outcome {
+    match part.num_triangles() {
+        0 => Err(vec!["boolean operations left no geometry".into()]),
+        n => Ok(format!("{} triangles", n)),
+    }
+}
+
+fn check_manifold(mesh: &TriMesh) -> Outcome {
+    let mut problems = Vec::new();
+    let mut directed = std::collections::HashSet::new();
+    for &[a, b, c] in &mesh.triangles {
+        for (u, v) in [(a, b), (b, c), (c, a)] {
+            if !directed.insert((u, v)) {
+                problems.push(format!("edge {}-{} is wound the same way by two triangles", u, v));
+            }
+        }
+    }
+    for ((a, b), faces) in mesh.edge_faces() {
+        if faces.len() != 2 {
+            problems.push(format!("edge {}-{} is shared by {} triangles", a, b, faces.len()));
+        }
+    }
+    if problems.is_empty() {
+        Ok(format!("{} edges closed", directed.len() / 2))
+    } else {
+        Err(problems)
+    }
+}
+
+/// How many separate solids a finished part is meant to print as.
+fn expected_bodies(part: &Part) -> usize {
+    if part.name == print_in_place::NAME {
+        2 // the arm and its captive pin
+    } else {
+        1
+    }
+}
+
+/// A part should be one solid (cavities inside it are fine). Extra bodies
+/// are usually waste from a cutter that missed, and would print as loose
+/// blobs.
+fn check_floating(part: &Part) -> Outcome {
+    let volumes = analysis::shell_volumes(part);
+    let bodies: Vec<f64> = volumes.iter().copied().filter(|&v| v > 0.0).collect();
+    if bodies.len() <= expected_bodies(part) {
+        return Ok(format!("{} bod{}", bodies.len(), if bodies.len() == 1 { "y" } else { "ies" }));
+    }
+    let islands: Vec<String> = bodies[expected_bodies(part)..].iter().map(|v| format!("{:.2} mm³", v)).collect();
+    Err(vec![format!(
+        "{} separate bodies where {} expected; extra islands of {}",
+        bodies.len(),
+        expected_bodies(part),
+        islands.join(", ")
+    )])
+}
+
+/// One cylindrical cutter, recovered from its bounding box.
+struct Hole {
+    name: String,
+    center: Point3<f64>,
+    /// Index of the axis the hole runs along (0 = X, 1 = Y, 2 = Z).
+    axis: usize,
+    radius: f64,
+    half_length: f64,
+}
+
+impl Hole {
+    fn describe(&self) -> String {
+        let c = self.center;
+        format!("{} at ({:.1}, {:.1}, {:.1})", self.name, c.x, c.y, c.z)
+    }
+}
+
+/// Features that are meant to cut clean through the part. Blind bores
+/// (`post_bore`) and pockets are not holes in this sense.
+fn is_through_hole(name: &str) -> bool {
+    name.ends_with("hole") || name.ends_with("holes") || name == "bolt_circle"
+}
+
+/// Split each hole feature into its individual cylinders.
+fn holes(features: &[Part]) -> Vec<Hole> {
+    let mut holes = Vec::new();
+    for feature in features.iter().filter(|f| is_through_hole(base_name(f))) {
+        for piece in TriMesh::from_part(feature).components() {
+            let (min, max) = piece.bounds();
+            let extent = max - min;
+            // The axis is the one every face is square to: caps across it,
+            // walls along it, which singles out round holes and slots. A
+            // box has three such axes and a countersink's cone none, so
+            // those take the one extent that differs from the other two
+            // (the diameter, twice).
+            let square_to = |k: usize| (0..piece.triangles.len()).all(|tri| (piece.normal(tri)[k].abs() - 0.5).abs() > 0.5 - 1e-4);
+            let squares: Vec<usize> = (0..3).filter(|&k| square_to(k)).collect();
+            let axis = match squares[..] {
+                [axis] => axis,
+                _ => (0..3)
+                    .min_by(|&k, &l| {
+                        let spread = |k: usize| (extent[(k + 1) % 3] - extent[(k + 2) % 3]).abs();
+                        spread(k).total_cmp(&spread(l))
+                    })
+                    .unwrap_or(2),
+            };
+            holes.push(Hole {
+                name: base_name(feature).to_string(),
+                center: Point3::from((min.coords + max.coords) / 2.0),
+                axis,
+                radius: (extent[(axis + 1) % 3] + extent[(axis + 2) % 3]) / 4.0,
+                half_length: extent[axis] / 2.0,
+            });
+        }
+    }
+    holes
+}
+
+/// A hole is through when the solid is open just past both ends of its
+/// cutter and all along its axis between. Cutters overshoot the faces they
+/// break by `HOLE_OVERCUT`, so a hole that stops short, or runs into other
+/// material, ends inside solid; one that a later union or a second,
+/// shorter cut left a membrane across is blocked partway.
+fn check_through_holes(mesh: &TriMesh, holes: &[Hole]) -> Outcome {
+    let mut problems = Vec::new();
+    for hole in holes {
+        let dir = Vector3::ith(hole.axis, 1.0);
+        let samples = (2.0 * hole.half_length / AXIS_STEP).ceil() as usize;
+        let blocked = (1..samples)
+            .map(|i| hole.center + dir * (-hole.half_length + 2.0 * hole.half_length * i as f64 / samples as f64))
+            .find(|&p| analysis::mesh_contains_point(mesh, p));
+        if let Some(p) = blocked {
+            problems.push(format!("{} is blocked by solid at {}={:.2}", hole.describe(), ["x", "y", "z"][hole.axis], p[hole.axis]));
+        }
+        for sign in [-1.0, 1.0] {
+            let end = hole.center + dir * (sign * (hole.half_length + END_PROBE));
+            if analysis::mesh_contains_point(mesh, end) {
+                problems.push(format!(
+                    "{} ends in solid at {}={:.2}",
+                    hole.describe(),
+                    ["x", "y", "z"][hole.axis],
+                    end[hole.axis]
+                ));
+            }
+        }
+    }
+    if problems.is_empty() {
+        Ok(format!("{} holes clear", holes.len()))
+    } else {
+        Err(problems)
+    }
+}
+
+fn check_overlapping_holes(holes: &[Hole]) -> Outcome {
+    let mut problems = Vec::new();
+    for (i, a) in holes.iter().enumerate() {
+        for b in &holes[i + 1..] {
+            let gap = b.center - a.center;
+            let overlap = if a.axis == b.axis {
+                let (u, v) = ((a.axis + 1) % 3, (a.axis + 2) % 3);
+                gap[u].hypot(gap[v]) < a.radius + b.radius && gap[a.axis].abs() < a.half_length + b.half_length
+            } else {
+                // Crossing holes: compare bounding boxes.
+                (0..3).all(|k| {
+                    let extent = |h: &Hole| if k == h.axis { h.half_length } else { h.radius };
+                    gap[k].abs() < extent(a) + extent(b)
+                })
+            };
+            if overlap {
+                problems.push(format!("{} cuts into {}", a.describe(), b.describe()));
+            }
+        }
+    }
+    if problems.is_empty() {
+        Ok(format!("{} holes apart", holes.len()))
+    } else {
+        Err(problems)
+    }
+}
+
+/// Measure the wall behind every triangle by casting a ray from its centroid
+/// straight into the solid.
+///
+/// Triangles smaller than a `MIN_WALL` square are skipped: they are the
+/// slivers faceting leaves where tangent curves meet, too small for a slicer
+/// to resolve as a wall.
+fn check_min_wall(mesh: &TriMesh) -> Outcome {
+    let mut thinnest = f64::INFINITY;
+    let mut problems = Vec::new();
+    for tri in 0..mesh.triangles.len() {
+        let normal = mesh.normal(tri);
+        let [a, b, c] = mesh.corners(tri);
+        if normal == Vector3::zeros() || (b - a).cross(&(c - a)).norm() / 2.0 < MIN_WALL * MIN_WALL {
+            continue;
+        }
+        let centroid = Point3::from((a.coords + b.coords + c.coords) / 3.0);
+        let Some(thickness) = analysis::ray_distance(mesh, centroid - normal * 1e-6, -normal) else {
+            continue;
+        };
+        thinnest = thinnest.min(thickness);
+        if thickness < MIN_WALL {
+            problems.push(format!(
+                "{:.2}mm wall behind ({:.1}, {:.1}, {:.1})",
+                thickness, centroid.x, centroid.y, centroid.z
+            ));
+        }
+    }
+    if problems.is_empty() {
+        Ok(format!("thinnest {:.2}mm", thinnest))
+    } else {
+        Err(problems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vcad::{centered_cube, centered_cylinder};
+
+    #[test]
+    fn flags_a_short_hole_and_a_thin_skin() {
+        let plate = centered_cube("plate", 20.0, 20.0, 4.0);
+        let short = centered_cylinder("hole", 4.0, 5.0, 8).translate(0.0, 0.0, 1.0);
+        let part = &plate - &short;
+        assert!(check_through_holes(&TriMesh::from_part(&part), &holes(&[short])).is_err());
+        assert!(check_min_wall(&TriMesh::from_part(&part)).is_err(), "0.5mm skin under the hole");
+
+        let through = centered_cylinder("hole", 1.5, 6.0, 32);
+        let part = &plate - &through;
+        assert!(check_through_holes(&TriMesh::from_part(&part), &holes(&[through])).is_ok());
+        assert!(check_manifold(&TriMesh::from_part(&part)).is_ok());
+    }
+
+    #[test]
+    fn a_hole_one_short_is_blind_and_an_overlength_one_is_through() {
+        let plate = centered_cube("plate", 20.0, 20.0, 4.0);
+        // An overlength cutter clears both faces; one a unit shorter, still
+        // breaking the top face, leaves a floor over the bottom one.
+        let overlength = centered_cylinder("hole", 1.5, 6.0, 32);
+        let short = centered_cylinder("hole", 1.5, 4.0, 32).translate(0.0, 0.0, 0.5);
+        for (cutter, through) in [(&overlength, true), (&short, false)] {
+            let result = check_through_holes(&TriMesh::from_part(&(&plate - cutter)), &holes(std::slice::from_ref(cutter)));
+            assert_eq!(result.is_ok(), through, "{result:?}");
+        }
+    }
+
+    #[test]
+    fn flags_a_membrane_left_between_two_cuts() {
+        let plate = centered_cube("plate", 20.0, 20.0, 4.0);
+        let declared = centered_cylinder("hole", 1.5, 6.0, 32);
+        // Cut from both faces, stopping 0.5 short of the middle on each side.
+        let top = centered_cylinder("hole", 1.5, 2.5, 32).translate(0.0, 0.0, 1.75);
+        let part = &(&plate - &top) - &top.translate(0.0, 0.0, -3.5);
+        let problems = check_through_holes(&TriMesh::from_part(&part), &holes(&[declared])).unwrap_err();
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].contains("blocked by solid"), "{problems:?}");
+    }
+
+    #[test]
+    fn slot_cutters_run_along_their_depth() {
+        let plate = centered_cube("plate", 30.0, 20.0, 4.0);
+        let slot = crate::geometry::obround("slot_hole", 12.0, 3.4, 6.0, 32);
+        let declared = holes(std::slice::from_ref(&slot));
+        assert_eq!(declared[0].axis, 2);
+        assert!(check_through_holes(&TriMesh::from_part(&(&plate - &slot)), &declared).is_ok());
+    }
+
+    #[test]
+    fn flags_a_floating_island_but_not_a_cavity() {
+        let plate = centered_cube("plate", 20.0, 20.0, 4.0);
+        assert!(check_floating(&(&plate - &centered_cube("void", 2.0, 2.0, 2.0))).is_ok());
+        let stray = &plate + &centered_cube("waste", 1.0, 1.0, 1.0).translate(0.0, 0.0, 10.0);
+        let problems = check_floating(&stray).unwrap_err();
+        assert!(problems[0].contains("2 separate bodies") && problems[0].contains("1.00 mm³"), "{:?}", problems);
+    }
+
+    #[test]
+    fn flags_holes_that_cut_into_each_other() {
+        let hole = centered_cylinder("hole", 2.0, 6.0, 32);
+        let pattern = &hole + &hole.translate(5.0, 0.0, 0.0);
+        assert_eq!(holes(&[pattern]).len(), 2, "a pattern splits into its copies");
+        assert!(check_overlapping_holes(&holes(&[hole.translate(0.0, 0.0, 0.0), hole.translate(5.0, 0.0, 0.0)])).is_ok());
+        assert!(check_overlapping_holes(&holes(&[hole.translate(0.0, 0.0, 0.0), hole.translate(3.0, 0.0, 0.0)])).is_err());
+        let cross = centered_cylinder("cross_hole", 1.0, 10.0, 32).rotate(0.0, 90.0, 0.0);
+        assert!(check_overlapping_holes(&holes(&[hole, cross])).is_err());
+    }
+}
@@ -1,10 +1,14 @@
 //! Configuration loader — reads shared parameters from the project root config.toml.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+use crate::fasteners::{self, Fastener};
+
 /// All parameters from the `[default]` section of config.toml.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[allow(dead_code)]
 pub struct Config {
     pub vial_diameter: f64,
@@ -16,39 +20,610 @@ pub struct Config {
     pub min_bend_radius: f64,
     pub wall_thickness: f64,
     pub base_thickness: f64,
+    /// Deprecated: the vcad parts size their mounting holes from
+    /// `mount_fastener` and ignore this. Kept for the build123d scripts.
+    #[serde(default)]
     pub mount_hole_diameter: f64,
+    /// Screw size for mounting holes, resolved through [`fasteners`].
+    #[serde(default = "default_mount_fastener")]
+    pub mount_fastener: String,
     pub fillet_radius: f64,
+    /// 45° chamfer on the exit rim of the bracket pin hole and frame corner holes (0 disables).
+    #[serde(default)]
+    pub hole_exit_chamfer: f64,
+    /// Countersink the peel plate, guide roller bracket, and spool holder
+    /// mounting holes for flat head screws.
+    #[serde(default)]
+    pub countersink_mount_holes: bool,
+    /// Heat-set insert bore diameter and depth, for parts that take
+    /// threaded brass inserts instead of self-tapping screws.
+    #[serde(default = "default_insert_od")]
+    pub insert_od: f64,
+    #[serde(default = "default_insert_depth")]
+    pub insert_depth: f64,
     pub frame_length: f64,
     pub frame_width: f64,
     pub frame_wall_height: f64,
     pub frame_wall_thickness: f64,
+    /// Cut a grid of lightening pockets into the underside of the frame base.
+    #[serde(default)]
+    pub frame_pockets: bool,
+    #[serde(default = "default_frame_pocket_size")]
+    pub frame_pocket_size: f64,
+    #[serde(default = "default_frame_pocket_pitch")]
+    pub frame_pocket_pitch: f64,
+    /// Solid skin left above each pocket.
+    #[serde(default = "default_frame_pocket_floor")]
+    pub frame_pocket_floor: f64,
+    /// Add standoff bosses under the frame base.
+    #[serde(default)]
+    pub frame_standoffs: bool,
+    #[serde(default = "default_frame_standoff_od")]
+    pub frame_standoff_od: f64,
+    #[serde(default = "default_frame_standoff_height")]
+    pub frame_standoff_height: f64,
+    /// Standoff XY centers; defaults to the corner mounting holes.
+    #[serde(default)]
+    pub frame_standoff_positions: Option<Vec<(f64, f64)>>,
+    /// Clearance generated cutters keep from holes and mounting features.
+    #[serde(default = "default_keep_out_margin")]
+    pub keep_out_margin: f64,
     pub peel_channel_width_clearance: f64,
     pub peel_body_depth: f64,
     pub peel_body_height_rear: f64,
+    /// Height of the peel body at its front (label exit) face; the top
+    /// slopes down to it from the rear. Unset keeps the rear height, a
+    /// plain block.
+    #[serde(default)]
+    pub peel_body_height_front: Option<f64>,
+    /// Spacing of the two mounting holes through the rear face, for bolting
+    /// the plate to the frame wall.
     pub peel_mount_hole_spacing: f64,
+    /// Drill the rear face mounting holes.
+    #[serde(default = "default_true")]
+    pub peel_rear_holes: bool,
+    /// Add two blind mounting holes up into the bottom face, for bolting the
+    /// plate to the base.
+    #[serde(default)]
+    pub peel_bottom_holes: bool,
+    /// Spacing of the bottom face mounting holes along X.
+    #[serde(default = "default_peel_bottom_hole_spacing")]
+    pub peel_bottom_hole_spacing: f64,
+    /// Depth of the bottom face mounting holes from the bottom face.
+    #[serde(default = "default_peel_bottom_hole_depth")]
+    pub peel_bottom_hole_depth: f64,
+    /// Stiffening ribs hanging below the body under the label channel, each
+    /// spanning the channel width, spread evenly front to back (0 = none).
+    #[serde(default)]
+    pub peel_rib_count: usize,
+    #[serde(default = "default_peel_rib_thickness")]
+    pub peel_rib_thickness: f64,
+    /// How far the ribs reach below the bottom face.
+    #[serde(default = "default_peel_rib_height")]
+    pub peel_rib_height: f64,
+    /// Radius of the rounded leading (front top) edge the label bends over; 0 leaves it sharp.
+    #[serde(default)]
+    pub peel_edge_radius: f64,
+    /// 45° chamfer on the leading edge instead of a round, so the label
+    /// doesn't catch on a sharp corner, measured down the front face; 0
+    /// leaves it sharp.
+    #[serde(default)]
+    pub peel_edge_chamfer: f64,
+    /// Side wall thickness on the -X side of the label channel; `wall_thickness` when unset.
+    #[serde(default)]
+    pub peel_wall_left: Option<f64>,
+    /// Side wall thickness on the +X side of the label channel; `wall_thickness` when unset.
+    #[serde(default)]
+    pub peel_wall_right: Option<f64>,
     pub cradle_base_height: f64,
     pub cradle_v_block_height: f64,
     pub cradle_mount_slot_spacing_x: f64,
     pub cradle_mount_slot_spacing_y: f64,
+    /// Extra diameter the V-groove is cut for, leaving the vial a radial gap
+    /// of half this at the contact lines.
+    #[serde(default)]
+    pub vial_cradle_clearance: f64,
+    /// Shape of the vial seat: a 90° V-groove or a cylindrical saddle.
+    #[serde(default)]
+    pub cradle_style: CradleStyle,
+    /// How far the top of the seated vial stands above the V-block
+    /// (negative when it sits below the block's top); sets how deep the
+    /// groove is cut.
+    #[serde(default = "default_cradle_vial_rise")]
+    pub cradle_vial_rise: f64,
+    /// How much shorter the V-block is than the vial, so the vial's ends
+    /// overhang it slightly.
+    #[serde(default = "default_cradle_end_clearance")]
+    pub cradle_end_clearance: f64,
+    /// Base plate length beyond each end of the V-block, carrying the mounting holes.
+    #[serde(default = "default_cradle_base_margin")]
+    pub cradle_base_margin: f64,
+    /// Least material between a cradle mounting hole and the base edge; the
+    /// base grows past its nominal size to keep it.
+    #[serde(default = "default_cradle_hole_edge_margin")]
+    pub cradle_hole_edge_margin: f64,
+    /// Adjustment travel of the cradle mounting slots in the frame and the
+    /// cradle base: how much longer each slot is than the bolt clearance.
+    /// 0 leaves round holes.
+    #[serde(default = "default_cradle_slot_travel")]
+    pub cradle_slot_travel: f64,
+    /// Axis the cradle mounting slots run along.
+    #[serde(default)]
+    pub cradle_slot_axis: SlotAxis,
+    /// Cut a label inspection window through the +Y wall of the V-block.
+    #[serde(default)]
+    pub cradle_window: bool,
+    #[serde(default = "default_cradle_window_width")]
+    pub cradle_window_width: f64,
+    #[serde(default = "default_cradle_window_height")]
+    pub cradle_window_height: f64,
+    /// Height of the window's bottom edge above the base plate.
+    #[serde(default = "default_cradle_window_offset")]
+    pub cradle_window_offset: f64,
     pub spool_spindle_od: f64,
     pub spool_flange_diameter: f64,
     pub spool_flange_thickness: f64,
     pub spool_height: f64,
+    /// Lead-in chamfer on the top edge of the spool spindle (0 disables).
+    #[serde(default)]
+    pub spool_spindle_chamfer: f64,
+    /// Draft on the spindle sides in degrees, narrowing it towards the top.
+    #[serde(default)]
+    pub spool_spindle_draft: f64,
+    /// Mount the flange with a three-hole bolt circle, whatever
+    /// `spool_bolt_circle_count` says.
+    #[serde(default)]
+    pub spool_flange_bolt_circle: bool,
+    /// Number of flange mounting holes on a bolt circle (0 = single center hole).
+    #[serde(default)]
+    pub spool_bolt_circle_count: usize,
+    #[serde(default)]
+    pub spool_bolt_circle_diameter: f64,
     pub dancer_arm_length: f64,
     pub dancer_arm_width: f64,
     pub dancer_arm_thickness: f64,
+    /// Cross-section of the dancer arm bar: solid, or ribbed with pockets in
+    /// both faces leaving an I-beam.
+    #[serde(default)]
+    pub dancer_arm_profile: ArmProfile,
+    /// Thickness of the ribbed bar's central web.
+    #[serde(default = "default_dancer_arm_rib_web")]
+    pub dancer_arm_rib_web: f64,
+    /// Width of the solid rim the ribbed bar keeps along its edges and
+    /// around the hubs and spring holes.
+    #[serde(default = "default_dancer_arm_rib_rim")]
+    pub dancer_arm_rib_rim: f64,
+    /// Spring anchor hole position along the arm, measured from the pivot.
+    #[serde(default = "default_spring_hole_x")]
+    pub dancer_spring_hole_x: f64,
+    /// Spring anchor hole offset from the arm centerline.
+    #[serde(default = "default_spring_hole_offset")]
+    pub dancer_spring_hole_offset: f64,
+    #[serde(default = "default_spring_hole_diameter")]
+    pub dancer_spring_hole_diameter: f64,
+    /// Optional second anchor (same offset and diameter) for a two-stage spring.
+    #[serde(default)]
+    pub dancer_spring_hole_2_x: Option<f64>,
+    /// Replace the spring holes with bosses for heat-set inserts, so the
+    /// spring hooks onto a screw instead.
+    #[serde(default = "default_true")]
+    pub dancer_spring_inserts: bool,
+    /// Counterbore on the outer (+Z) face of the roller hub for a shoulder
+    /// screw head; 0 leaves the plain bearing bore.
+    #[serde(default)]
+    pub dancer_roller_counterbore_diameter: f64,
+    #[serde(default = "default_roller_counterbore_depth")]
+    pub dancer_roller_counterbore_depth: f64,
     pub pivot_bore: f64,
     pub bearing_od: f64,
     pub bearing_id: f64,
     pub bracket_base_width: f64,
     pub bracket_base_depth: f64,
     pub bracket_height: f64,
+    /// Gap kept between the roller bearing and the bracket base plate.
+    #[serde(default = "default_bracket_bearing_clearance")]
+    pub bracket_bearing_clearance: f64,
+    /// Raise `bracket_height` to the smallest that fits the bearing instead
+    /// of rejecting the config.
+    #[serde(default)]
+    pub bracket_auto_height: bool,
+    /// Mounting holes in a row along X, centered on the bracket base.
+    #[serde(default = "default_bracket_mount_hole_count")]
+    pub bracket_mount_hole_count: usize,
+    #[serde(default = "default_bracket_mount_hole_spacing")]
+    pub bracket_mount_hole_spacing: f64,
+    /// Press-fit tuning for the bracket's roller pin hole: grows (positive)
+    /// or shrinks (negative) its surface by this much.
+    #[serde(default)]
+    pub bracket_pin_hole_offset: f64,
     pub pivot_post_height: f64,
+    /// Diameter of the hole bored down the pivot post axis (0 = solid post).
+    #[serde(default)]
+    pub pivot_post_bore: f64,
+    /// Depth of the post bore from its top; 0 bores through post and base.
+    #[serde(default)]
+    pub pivot_post_bore_depth: f64,
+    /// Draft on the pivot post sides in degrees, narrowing it towards the top.
+    #[serde(default)]
+    pub pivot_post_draft: f64,
+    /// How far the conical reinforcement at the foot of the pivot post
+    /// extends beyond the post radius.
+    #[serde(default = "default_pivot_reinforce_radius")]
+    pub pivot_reinforce_radius: f64,
+    /// Height over which the reinforcement tapers into the post.
+    #[serde(default = "default_pivot_reinforce_height")]
+    pub pivot_reinforce_height: f64,
+    /// Also build the dancer arm captive on a pivot pin, printed as one piece.
+    #[serde(default)]
+    pub print_in_place: bool,
+    /// Clearance between the pin and the arm on every mating face.
+    #[serde(default = "default_print_in_place_gap")]
+    pub print_in_place_gap: f64,
+    /// Output file name template (see `output::file_stem` for tokens).
+    #[serde(default = "default_filename_template")]
+    pub filename_template: String,
+    /// Low-poly preview: cap circle segment counts (also `--quick`).
+    #[serde(default)]
+    pub quick: bool,
+    /// Circle segments for holes, and for pins and posts that fit in them.
+    #[serde(default = "default_segments_hole")]
+    pub segments_hole: u32,
+    /// Circle segments for visible round bodies: hubs, spindle, edge rounds.
+    #[serde(default = "default_segments_cylinder")]
+    pub segments_cylinder: u32,
+    /// Circle segments for the spool holder's flange.
+    #[serde(default = "default_segments_flange")]
+    pub segments_flange: u32,
+    /// Most triangles an exported part may have after the coplanar merge
+    /// (0 = no limit). Parts over it are decimated, or fail with `--strict`.
+    #[serde(default)]
+    pub max_triangles: usize,
+    /// Factor every length is multiplied by at load, e.g. 0.5 for a
+    /// half-size prototype. Angles, counts, segment numbers and
+    /// print tolerances are kept.
+    #[serde(default = "default_model_scale")]
+    pub model_scale: f64,
+    /// Filament density (g/cm³) for the estimated mass printed with each
+    /// export; PLA by default.
+    #[serde(default = "default_material_density")]
+    pub material_density: f64,
+    /// Reference point placed at the origin after each part is built.
+    #[serde(default)]
+    pub origin_mode: OriginMode,
+    /// Per-component enable flags from `[components]`; unlisted components
+    /// are built. Last, since TOML writes tables after plain values.
+    #[serde(default)]
+    pub components: BTreeMap<String, bool>,
+    /// Per-component display colors from `[colors]`, as `#RRGGBB` (or
+    /// `#RRGGBBAA`) hex, given to each part's material in the assembly 3MF.
+    #[serde(default)]
+    pub colors: BTreeMap<String, String>,
+}
+
+/// Where each exported part's origin sits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OriginMode {
+    /// Parts stay centered on the origin as built.
+    #[default]
+    Center,
+    /// Parts are shifted so the bottom face of their base sits at Z=0.
+    Bottom,
+}
+
+/// Cross-section of the dancer arm bar.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArmProfile {
+    /// A plain rectangle.
+    #[default]
+    Solid,
+    /// Pockets in the top and bottom faces, leaving an I-beam.
+    Ribbed,
+}
+
+/// Shape of the groove the vial cradle seats the vial in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CradleStyle {
+    /// A 90° V-groove touching the vial along two lines.
+    #[default]
+    V,
+    /// A half-cylinder seat wrapping the vial, open at the top.
+    Saddle,
+}
+
+/// Axis an adjustment slot runs along.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlotAxis {
+    /// Along the vial axis, moving the cradle towards or away from the peel plate.
+    #[default]
+    X,
+    /// Across the vial axis.
+    Y,
+}
+
+impl Config {
+    /// Dimensions of the configured mounting fastener, at `model_scale`.
+    ///
+    /// `validate` rejects unknown designations before any part is built.
+    pub fn fastener(&self) -> Fastener {
+        fasteners::lookup(&self.mount_fastener)
+            .unwrap_or_else(|| panic!("Unknown mount_fastener '{}'", self.mount_fastener))
+            .scaled(self.model_scale)
+    }
+
+    /// A length a builder fixes in code (mm), at `model_scale` like the
+    /// config's own lengths.
+    pub fn mm(&self, length: f64) -> f64 {
+        length * self.model_scale
+    }
+
+    /// Short fingerprint of the effective config (after profile merge and
+    /// defaults): the first 12 hex digits of the SHA-256 of its TOML form.
+    pub fn hash(&self) -> String {
+        let toml = toml::to_string(self).expect("Config always serializes");
+        let digest = Sha256::digest(toml.as_bytes());
+        digest[..6].iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// One copy of the config per scalar field with just that field nudged:
+    /// numbers by 5% (or 0.5 from zero), integers by one, booleans flipped.
+    /// Building each and comparing shows which fields a part depends on.
+    /// Unset optional fields and text fields are left out. Nudging
+    /// `model_scale` rescales every length with it, as loading would.
+    pub fn nudged(&self) -> Vec<(String, Config)> {
+        let toml::Value::Table(table) = toml::Value::try_from(self).expect("Config always serializes") else {
+            unreachable!("Config serializes as a table");
+        };
+        let mut nudged = Vec::new();
+        for (key, value) in &table {
+            let changed = match value {
+                toml::Value::Float(v) => toml::Value::Float(if *v == 0.0 { 0.5 } else { v * 1.05 }),
+                toml::Value::Integer(v) => toml::Value::Integer(v + 1),
+                toml::Value::Boolean(v) => toml::Value::Boolean(!v),
+                _ => continue,
+            };
+            let mut table = table.clone();
+            if key == "model_scale" {
+                scale_lengths(&mut table, 1.05);
+            }
+            table.insert(key.clone(), changed);
+            let cfg = toml::Value::Table(table).try_into().unwrap_or_else(|e| panic!("Nudged {} no longer parses: {}", key, e));
+            nudged.push((key.clone(), cfg));
+        }
+        nudged
+    }
+}
+
+/// Float fields left alone by `model_scale`: those that are not lengths,
+/// and print tolerances, which the printer needs at any model size.
+const UNSCALED_FIELDS: &[&str] = &[
+    "model_scale",
+    "material_density",
+    "spool_spindle_draft",
+    "pivot_post_draft",
+    "print_in_place_gap",
+    "vial_cradle_clearance",
+    "peel_channel_width_clearance",
+    "bracket_bearing_clearance",
+    "bracket_pin_hole_offset",
+];
+
+/// Multiply every length in a serialized config by `factor`: float fields
+/// and point lists, except [`UNSCALED_FIELDS`].
+fn scale_lengths(table: &mut toml::Table, factor: f64) {
+    fn scale(value: &mut toml::Value, factor: f64) {
+        match value {
+            toml::Value::Float(v) => *v *= factor,
+            toml::Value::Array(items) => items.iter_mut().for_each(|item| scale(item, factor)),
+            _ => {}
+        }
+    }
+    for (key, value) in table.iter_mut() {
+        if !UNSCALED_FIELDS.contains(&key.as_str()) {
+            scale(value, factor);
+        }
+    }
+}
+
+/// Prefix of the environment variables that override single config fields.
+const ENV_PREFIX: &str = "VIAL_LAYBELL_";
+
+/// Set each numeric field named by a `VIAL_LAYBELL_<FIELD>` variable in
+/// `env` (the field name upper-snake-cased) to its value, adding it to
+/// `table` if it was unset. Fields are looked up in the config schema, so
+/// optional ones like `peel_wall_left` can be overridden too. Any such
+/// variable but `VIAL_LAYBELL_CONFIG` that names no numeric field is an
+/// error. Returns whether anything was overridden.
+fn apply_env_overrides(table: &mut toml::Table, env: impl IntoIterator<Item = (String, String)>) -> Result<bool, ConfigError> {
+    let mut overridden = false;
+    for (var, value) in env {
+        let Some(field) = var.strip_prefix(ENV_PREFIX).filter(|field| *field != "CONFIG").map(str::to_lowercase) else {
+            continue;
+        };
+        let integer = crate::schema::numeric_field(&field).ok_or_else(|| ConfigError::UnknownOverride { var: var.clone() })?;
+        let bad = || ConfigError::BadOverride { var: var.clone(), value: value.clone() };
+        let number = value.trim().parse::<f64>().ok().filter(|n| n.is_finite()).ok_or_else(bad)?;
+        if integer && (number.fract() != 0.0 || number < 0.0) {
+            return Err(bad());
+        }
+        table.insert(field, if integer { toml::Value::Integer(number as i64) } else { toml::Value::Float(number) });
+        overridden = true;
+    }
+    Ok(overridden)
+}
+
+fn default_model_scale() -> f64 {
+    1.0
+}
+
+fn default_material_density() -> f64 {
+    1.24
+}
+
+fn default_filename_template() -> String {
+    "{component}".into()
+}
+
+fn default_dancer_arm_rib_web() -> f64 {
+    1.5
+}
+
+fn default_dancer_arm_rib_rim() -> f64 {
+    1.5
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_peel_bottom_hole_spacing() -> f64 {
+    30.0
+}
+
+fn default_peel_bottom_hole_depth() -> f64 {
+    6.0
+}
+
+fn default_peel_rib_thickness() -> f64 {
+    2.0
+}
+
+fn default_peel_rib_height() -> f64 {
+    3.0
+}
+
+fn default_mount_fastener() -> String {
+    "M3".into()
+}
+
+fn default_frame_pocket_size() -> f64 {
+    15.0
+}
+
+fn default_frame_pocket_pitch() -> f64 {
+    20.0
+}
+
+fn default_frame_pocket_floor() -> f64 {
+    1.5
+}
+
+fn default_frame_standoff_od() -> f64 {
+    8.0
+}
+
+fn default_frame_standoff_height() -> f64 {
+    6.0
+}
+
+fn default_keep_out_margin() -> f64 {
+    3.0
+}
+
+fn default_cradle_end_clearance() -> f64 {
+    3.5
+}
+
+fn default_cradle_base_margin() -> f64 {
+    9.0
+}
+
+fn default_cradle_hole_edge_margin() -> f64 {
+    3.0
+}
+
+fn default_cradle_window_width() -> f64 {
+    20.0
+}
+
+fn default_cradle_window_height() -> f64 {
+    8.0
+}
+
+fn default_cradle_slot_travel() -> f64 {
+    6.0
+}
+
+fn default_cradle_vial_rise() -> f64 {
+    -3.56
+}
+
+fn default_cradle_window_offset() -> f64 {
+    6.0
+}
+
+fn default_segments_hole() -> u32 {
+    32
+}
+
+fn default_segments_cylinder() -> u32 {
+    64
+}
+
+fn default_segments_flange() -> u32 {
+    64
+}
+
+fn default_bracket_bearing_clearance() -> f64 {
+    1.0
+}
+
+fn default_bracket_mount_hole_count() -> usize {
+    2
+}
+
+fn default_bracket_mount_hole_spacing() -> f64 {
+    15.0
+}
+
+fn default_pivot_reinforce_radius() -> f64 {
+    3.0
+}
+
+fn default_pivot_reinforce_height() -> f64 {
+    6.0
+}
+
+fn default_insert_od() -> f64 {
+    4.0
+}
+
+fn default_insert_depth() -> f64 {
+    4.0
+}
+
+fn default_roller_counterbore_depth() -> f64 {
+    2.0
+}
+
+fn default_print_in_place_gap() -> f64 {
+    0.3
+}
+
+fn default_spring_hole_x() -> f64 {
+    10.0
+}
+
+fn default_spring_hole_offset() -> f64 {
+    4.5
+}
+
+fn default_spring_hole_diameter() -> f64 {
+    3.0
 }
 
 #[derive(Deserialize)]
 struct ConfigFile {
-    default: Config,
+    default: toml::Table,
+    #[serde(default)]
+    components: toml::Table,
+    #[serde(default)]
+    colors: toml::Table,
+    #[serde(default)]
+    profiles: toml::Table,
 }
 
 /// Resolve the path to config.toml at the project root.
@@ -57,7 +632,7 @@ struct ConfigFile {
 /// 1. `VIAL_LAYBELL_CONFIG` environment variable
 /// 2. `../../config.toml` relative to the vcad crate manifest directory (compile-time)
 /// 3. `../../config.toml` relative to the current executable
-fn resolve_config_path() -> PathBuf {
+pub fn resolve_config_path() -> PathBuf {
     if let Ok(p) = std::env::var("VIAL_LAYBELL_CONFIG") {
         return PathBuf::from(p);
     }
@@ -82,12 +657,223 @@ fn resolve_config_path() -> PathBuf {
     PathBuf::from("config.toml")
 }
 
+/// Why a config file couldn't be loaded.
+#[derive(Debug)]
+pub enum ConfigError {
+    NotFound(PathBuf),
+    ReadFailed(PathBuf, std::io::Error),
+    /// Not valid TOML, or a value of the wrong type or a missing field.
+    ParseFailed(PathBuf, String),
+    UnknownProfile { name: String, available: Vec<String> },
+    /// A `VIAL_LAYBELL_<FIELD>` override that isn't a number.
+    BadOverride { var: String, value: String },
+    /// A `VIAL_LAYBELL_*` variable that names no numeric field.
+    UnknownOverride { var: String },
+}
+
+impl ConfigError {
+    /// Process exit code for the failure, from BSD `sysexits.h`.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ConfigError::NotFound(_) => 66,
+            ConfigError::ReadFailed(..) => 74,
+            ConfigError::ParseFailed(..) => 65,
+            ConfigError::UnknownProfile { .. } => 64,
+            ConfigError::BadOverride { .. } | ConfigError::UnknownOverride { .. } => 78,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ConfigError::NotFound(path) => write!(f, "No config at {} (set VIAL_LAYBELL_CONFIG to point at one)", path.display()),
+            ConfigError::ReadFailed(path, e) => write!(f, "Failed to read config at {}: {}", path.display(), e),
+            ConfigError::ParseFailed(path, e) => write!(f, "Failed to parse {}: {}", path.display(), e),
+            ConfigError::UnknownProfile { name, available } => write!(f, "Unknown profile '{}'. Available: {}", name, available.join(", ")),
+            ConfigError::BadOverride { var, value } => write!(f, "{} = '{}' is not a number", var, value),
+            ConfigError::UnknownOverride { var } => write!(f, "{} names no numeric config field", var),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
 /// Load and parse the project configuration.
-pub fn load_config() -> Config {
-    let path = resolve_config_path();
-    let content = std::fs::read_to_string(&path)
-        .unwrap_or_else(|e| panic!("Failed to read config at {}: {}", path.display(), e));
-    let file: ConfigFile = toml::from_str(&content)
-        .unwrap_or_else(|e| panic!("Failed to parse config.toml: {}", e));
-    file.default
+///
+/// A named `[profiles.<name>]` table is merged over `[default]`, matching
+/// `load_config(profile)` in `src/config.py`. The `[components]` enable
+/// flags become `Config::components` and `[colors]` `Config::colors`; a
+/// profile may replace either with its own `[profiles.<name>.components]`
+/// or `[profiles.<name>.colors]`. A numeric field is then overridden by a
+/// `VIAL_LAYBELL_<FIELD>` environment variable, e.g.
+/// `VIAL_LAYBELL_VIAL_DIAMETER=18`, and lengths are multiplied by
+/// `model_scale`, defaults and overrides included.
+pub fn load_config(profile: Option<&str>) -> Result<Config, ConfigError> {
+    load_config_from(&resolve_config_path(), profile)
+}
+
+/// Load the configuration from an explicit config file.
+pub fn load_config_from(path: &Path, profile: Option<&str>) -> Result<Config, ConfigError> {
+    load_config_with_env(path, profile, std::env::vars())
+}
+
+/// [`load_config_from`] with `env` standing in for the process environment.
+fn load_config_with_env(path: &Path, profile: Option<&str>, env: impl IntoIterator<Item = (String, String)>) -> Result<Config, ConfigError> {
+    let content = std::fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => ConfigError::NotFound(path.to_path_buf()),
+        _ => ConfigError::ReadFailed(path.to_path_buf(), e),
+    })?;
+    let parse_failed = |e: toml::de::Error| ConfigError::ParseFailed(path.to_path_buf(), e.to_string());
+    let file: ConfigFile = toml::from_str(&content).map_err(parse_failed)?;
+
+    let mut table = file.default;
+    table.insert("components".into(), toml::Value::Table(file.components));
+    table.insert("colors".into(), toml::Value::Table(file.colors));
+    if let Some(name) = profile {
+        let overrides = file.profiles.get(name).and_then(|p| p.as_table()).ok_or_else(|| ConfigError::UnknownProfile {
+            name: name.to_string(),
+            available: file.profiles.keys().cloned().collect(),
+        })?;
+        table.extend(overrides.clone());
+    }
+    let cfg: Config = table.try_into().map_err(parse_failed)?;
+    let toml::Value::Table(mut table) = toml::Value::try_from(&cfg).expect("Config always serializes") else {
+        unreachable!("Config serializes as a table");
+    };
+    if !apply_env_overrides(&mut table, env)? && cfg.model_scale == 1.0 {
+        return Ok(cfg);
+    }
+    let cfg: Config = table.clone().try_into().map_err(parse_failed)?;
+    scale_lengths(&mut table, cfg.model_scale);
+    Ok(table.try_into().expect("scaled config parses"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_is_stable_and_tracks_values() {
+        let cfg = load_config(None).unwrap();
+        assert_eq!(cfg.hash(), load_config(None).unwrap().hash());
+        assert_eq!(cfg.hash().len(), 12);
+        let mut changed = load_config(None).unwrap();
+        changed.wall_thickness += 0.1;
+        assert_ne!(cfg.hash(), changed.hash());
+    }
+
+    #[test]
+    fn model_scale_scales_lengths_and_holes_alike() {
+        let content = std::fs::read_to_string(resolve_config_path()).unwrap();
+        let path = std::env::temp_dir().join(format!("vialbel-half-scale-{}.toml", std::process::id()));
+        std::fs::write(&path, content.replace("model_scale = 1.0", "model_scale = 0.5").replace("pivot_post_draft = 0.0", "pivot_post_draft = 2.0")).unwrap();
+        let half = load_config_from(&path, None).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // Tolerances stay put, so compare with a full-size model whose
+        // tolerances are doubled to match.
+        let mut full = load_config(None).unwrap();
+        full.print_in_place_gap *= 2.0;
+        full.vial_cradle_clearance *= 2.0;
+        full.peel_channel_width_clearance *= 2.0;
+        full.bracket_bearing_clearance *= 2.0;
+        full.bracket_pin_hole_offset *= 2.0;
+
+        assert_eq!(half.wall_thickness, full.wall_thickness / 2.0);
+        assert_eq!(half.frame_pocket_size, full.frame_pocket_size / 2.0, "defaults scale too");
+        assert_eq!(half.fastener().clearance, full.fastener().clearance / 2.0);
+        assert_eq!(half.pivot_post_draft, 2.0, "angles are kept");
+        assert_eq!(half.segments_hole, full.segments_hole);
+
+        for (name, build_fn, _) in crate::COMPONENTS {
+            let (full_min, full_max) = crate::mesh::TriMesh::from_part(&build_fn(&full)).bounds();
+            let (half_min, half_max) = crate::mesh::TriMesh::from_part(&build_fn(&half)).bounds();
+            assert!(((half_max - half_min) * 2.0 - (full_max - full_min)).norm() < 1e-6, "{name} is not half size");
+            let holes = |cfg: &Config| crate::features::count_tagged(crate::features::Role::MountingHole, || build_fn(cfg));
+            assert_eq!(holes(&half), holes(&full), "{name} keeps its holes");
+        }
+    }
+
+    #[test]
+    fn half_scale_keeps_print_tolerances_and_validates() {
+        let content = std::fs::read_to_string(resolve_config_path()).unwrap();
+        let path = std::env::temp_dir().join(format!("vialbel-half-scale-gap-{}.toml", std::process::id()));
+        // Halved, the default 2.5mm walls less half the unscaled channel
+        // clearance fall under MIN_WALL beside the peel channel.
+        let content = content.replace("model_scale = 1.0", "model_scale = 0.5").replace("print_in_place = false", "print_in_place = true").replace("wall_thickness = 2.5", "wall_thickness = 3.0");
+        std::fs::write(&path, content).unwrap();
+        let half = load_config_from(&path, None).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let full = load_config(None).unwrap();
+
+        assert_eq!(half.print_in_place_gap, full.print_in_place_gap);
+        assert_eq!(half.peel_channel_width_clearance, full.peel_channel_width_clearance);
+        assert_eq!(half.pivot_bore, full.pivot_bore / 2.0);
+        let errors = crate::validate::validate(&half);
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn load_errors_name_the_file_and_the_cause() {
+        let path = std::env::temp_dir().join(format!("vialbel-malformed-{}.toml", std::process::id()));
+        std::fs::write(&path, "[default]\nvial_diameter = = 16.0\n").unwrap();
+        let malformed = load_config_from(&path, None);
+        std::fs::write(&path, "[default]\nvial_diameter = 16.0\n").unwrap();
+        let incomplete = load_config_from(&path, None);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(malformed, Err(ConfigError::ParseFailed(ref p, _)) if *p == path), "{:?}", malformed);
+        assert!(matches!(incomplete, Err(ConfigError::ParseFailed(_, ref e)) if e.contains("missing field")), "{:?}", incomplete);
+        assert!(matches!(load_config_from(&path, None), Err(ConfigError::NotFound(_))));
+        let unknown = load_config(Some("no_such_profile")).unwrap_err();
+        assert!(matches!(unknown, ConfigError::UnknownProfile { ref available, .. } if available.iter().any(|p| p == "22mm")));
+        assert_eq!(unknown.exit_code(), 64);
+    }
+
+    #[test]
+    fn env_overrides_change_only_their_field() {
+        // The process environment is shared by parallel tests, so the
+        // variables are passed in rather than set.
+        let env = |pairs: &[(&str, &str)]| pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<_>>();
+        let path = resolve_config_path();
+        let base = toml::Table::try_from(load_config_with_env(&path, None, env(&[])).unwrap()).unwrap();
+        let cfg = load_config_with_env(&path, None, env(&[("VIAL_LAYBELL_VIAL_DIAMETER", "18"), ("VIAL_LAYBELL_CONFIG", "elsewhere.toml")])).unwrap();
+        assert_eq!(cfg.vial_diameter, 18.0);
+        let changed: Vec<String> = toml::Table::try_from(cfg).unwrap().into_iter().filter(|(k, v)| base[k] != *v).map(|(k, _)| k).collect();
+        assert_eq!(changed, ["vial_diameter"]);
+
+        let segments = load_config_with_env(&path, None, env(&[("VIAL_LAYBELL_SEGMENTS_HOLE", "24")])).unwrap();
+        assert_eq!(segments.segments_hole, 24);
+        for (var, value) in [("VIAL_LAYBELL_VIAL_DIAMETER", "wide"), ("VIAL_LAYBELL_VIAL_DIAMETER", "NaN"), ("VIAL_LAYBELL_SEGMENTS_HOLE", "2.5")] {
+            let err = load_config_with_env(&path, None, env(&[(var, value)])).unwrap_err();
+            assert!(matches!(err, ConfigError::BadOverride { .. }), "{var}={value}: {err:?}");
+            assert!(err.to_string().contains(var) && err.to_string().contains(value));
+        }
+
+        // Optional fields are absent from the parsed table but still resolve.
+        let walls = load_config_with_env(&path, None, env(&[("VIAL_LAYBELL_PEEL_WALL_LEFT", "8"), ("VIAL_LAYBELL_PEEL_BODY_HEIGHT_FRONT", "4.5")])).unwrap();
+        assert_eq!(walls.peel_wall_left, Some(8.0));
+        assert_eq!(walls.peel_body_height_front, Some(4.5));
+        for var in ["VIAL_LAYBELL_VIAL_DIAMITER", "VIAL_LAYBELL_QUICK", "VIAL_LAYBELL_MOUNT_FASTENER"] {
+            let err = load_config_with_env(&path, None, env(&[(var, "1")])).unwrap_err();
+            assert!(matches!(err, ConfigError::UnknownOverride { .. }), "{var}: {err:?}");
+            assert!(err.to_string().contains(var));
+        }
+    }
+
+    #[test]
+    fn nudged_changes_one_field_each() {
+        let cfg = load_config(None).unwrap();
+        let nudged = cfg.nudged();
+        let wall = nudged.iter().find(|(key, _)| key == "wall_thickness").map(|(_, c)| c).unwrap();
+        assert!((wall.wall_thickness - cfg.wall_thickness * 1.05).abs() < 1e-9);
+        assert_eq!(wall.base_thickness, cfg.base_thickness);
+        let quick = nudged.iter().find(|(key, _)| key == "quick").map(|(_, c)| c).unwrap();
+        assert_eq!(quick.quick, !cfg.quick);
+        assert!(!nudged.iter().any(|(key, _)| key == "mount_fastener"), "text fields are skipped");
+        let mut hashes: Vec<String> = nudged.iter().map(|(_, c)| c.hash()).collect();
+        hashes.push(cfg.hash());
+        hashes.sort();
+        hashes.dedup();
+        assert_eq!(hashes.len(), nudged.len() + 1, "every copy differs from the rest");
+    }
 }
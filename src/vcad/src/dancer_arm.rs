@@ -1,37 +1,202 @@
 //! Dancer arm — simplified CSG version.
 //!
 //! The Build123d version uses a 2D sketch with hub circles and extrusion.
-//! This vcad version approximates the shape with box and cylinder primitives.
+//! This vcad version does the same: the hubs, bar and hub-to-bar fillets are
+//! traced as one outline and extruded, then the bores are cut.
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
 
 use vcad::*;
 
-use crate::config::Config;
+use crate::config::{ArmProfile, Config};
+use crate::features::feature;
+use crate::geometry::{self, PartExt};
 
-pub fn build(cfg: &Config) -> Part {
-    let pivot_hub_radius = cfg.pivot_bore / 2.0 + cfg.wall_thickness + 2.0;
-    let roller_hub_radius = cfg.bearing_od / 2.0 + cfg.wall_thickness;
+/// The arm pivots about the Z axis through the pivot bore at the origin.
+pub const PIVOT_XY: (f64, f64) = (0.0, 0.0);
+
+/// Z of the bottom face of the arm in build coordinates.
+pub fn base_bottom_z(cfg: &Config) -> f64 {
+    -cfg.dancer_arm_thickness / 2.0
+}
+
+pub fn pivot_hub_radius(cfg: &Config) -> f64 {
+    cfg.pivot_bore / 2.0 + cfg.wall_thickness + cfg.mm(2.0)
+}
+
+pub fn roller_hub_radius(cfg: &Config) -> f64 {
+    cfg.bearing_od / 2.0 + cfg.wall_thickness
+}
+
+/// Plan-view outline of the arm, counter-clockwise: pivot hub at the origin,
+/// roller hub at `dancer_arm_length` on +X, joined by the bar with a concave
+/// `fillet_radius` blend at each of the four corners. `validate` keeps the
+/// bar narrower than both hubs, so every corner is concave.
+fn outline(cfg: &Config) -> Vec<(f64, f64)> {
+    let (h, f, len) = (cfg.dancer_arm_width / 2.0, cfg.fillet_radius.max(0.0), cfg.dancer_arm_length);
+    let (hub_segments, fillet_segments) = (geometry::segments(cfg, cfg.segments_cylinder), geometry::segments(cfg, 32));
+    // Distance along the bar from a hub's center to its fillet centers, and
+    // the angle (from the hub center) of the point where the fillet meets it.
+    let blend = |r: f64| {
+        let along = ((r + f).powi(2) - (h + f).powi(2)).sqrt();
+        (along, (h + f).atan2(along))
+    };
+    let (pivot_r, roller_r) = (pivot_hub_radius(cfg), roller_hub_radius(cfg));
+    let (pa, pt) = blend(pivot_r);
+    let (ra, rt) = blend(roller_r);
 
-    // Pivot hub cylinder
-    let pivot_hub = centered_cylinder("pivot_hub", pivot_hub_radius, cfg.dancer_arm_thickness, 64);
+    let mut points = geometry::arc_points((0.0, 0.0), pivot_r, pt, TAU - pt, hub_segments);
+    points.extend(geometry::arc_points((pa, -h - f), f, PI - pt, FRAC_PI_2, fillet_segments));
+    points.extend(geometry::arc_points((len - ra, -h - f), f, FRAC_PI_2, rt, fillet_segments));
+    points.extend(geometry::arc_points((len, 0.0), roller_r, PI + rt, 3.0 * PI - rt, hub_segments));
+    points.extend(geometry::arc_points((len - ra, h + f), f, -rt, -FRAC_PI_2, fillet_segments));
+    points.extend(geometry::arc_points((pa, h + f), f, -FRAC_PI_2, pt - PI, fillet_segments));
 
-    // Roller hub cylinder at far end
-    let roller_hub = centered_cylinder("roller_hub", roller_hub_radius, cfg.dancer_arm_thickness, 64)
-        .translate(cfg.dancer_arm_length, 0.0, 0.0);
+    // Neighbouring arcs share their end points (and a zero fillet collapses
+    // to one), so drop the repeats.
+    let same = |a: &(f64, f64), b: &(f64, f64)| (a.0 - b.0).hypot(a.1 - b.1) < 1e-6;
+    points.dedup_by(|a, b| same(a, b));
+    if points.len() > 1 && same(&points[0], &points[points.len() - 1]) {
+        points.pop();
+    }
+    points
+}
 
-    // Connecting bar
-    let bar = centered_cube("bar", cfg.dancer_arm_length, cfg.dancer_arm_width, cfg.dancer_arm_thickness)
-        .translate(cfg.dancer_arm_length / 2.0, 0.0, 0.0);
+/// Spring anchor X positions along the arm.
+fn spring_hole_xs(cfg: &Config) -> Vec<f64> {
+    std::iter::once(cfg.dancer_spring_hole_x).chain(cfg.dancer_spring_hole_2_x).collect()
+}
+
+/// Radius of each spring anchor: its insert boss, or the plain hole.
+pub fn spring_anchor_radius(cfg: &Config) -> f64 {
+    if cfg.dancer_spring_inserts { geometry::insert_boss_od(cfg) / 2.0 } else { cfg.dancer_spring_hole_diameter / 2.0 }
+}
+
+/// X range of the ribbed bar's pockets: the bar between the hubs, less
+/// `dancer_arm_rib_rim` at each end. Empty when the hubs leave no room.
+pub fn rib_pocket_span(cfg: &Config) -> (f64, f64) {
+    (pivot_hub_radius(cfg) + cfg.dancer_arm_rib_rim, cfg.dancer_arm_length - roller_hub_radius(cfg) - cfg.dancer_arm_rib_rim)
+}
+
+/// Pockets cut into the top and bottom faces of a ribbed bar, leaving the
+/// rim and web, with solid bosses kept around the spring holes.
+fn rib_pockets(cfg: &Config) -> Part {
+    let (x0, x1) = rib_pocket_span(cfg);
+    let width = cfg.dancer_arm_width - 2.0 * cfg.dancer_arm_rib_rim;
+    let depth = (cfg.dancer_arm_thickness - cfg.dancer_arm_rib_web) / 2.0 + geometry::HOLE_OVERCUT;
+    let face_z = (cfg.dancer_arm_thickness - depth) / 2.0 + geometry::HOLE_OVERCUT;
+    let pocket = centered_cube("rib_pocket", x1 - x0, width, depth).translate((x0 + x1) / 2.0, 0.0, face_z);
+    let mut pockets = pocket.mirror_copy(0.0, 0.0, 1.0);
+    let boss = 2.0 * (spring_anchor_radius(cfg) + cfg.dancer_arm_rib_rim);
+    for x in spring_hole_xs(cfg) {
+        pockets = pockets - centered_cube("spring_boss", boss, cfg.dancer_arm_width, cfg.dancer_arm_thickness + 2.0 * depth).translate(x, 0.0, 0.0);
+    }
+    pockets.with_name("rib_pockets")
+}
+
+pub fn build(cfg: &Config) -> Part {
+    let mut body = feature(geometry::extrude("outline", &outline(cfg), cfg.dancer_arm_thickness));
+    if cfg.dancer_arm_profile == ArmProfile::Ribbed {
+        body = body - feature(rib_pockets(cfg));
+    }
+
+    // Spring anchors — a second gives a two-stage spring. Heat-set insert
+    // bosses stand on the bottom face, so they print from the bed even
+    // where they overhang the bar. They go on before the bores are cut, so
+    // a boss reaching a hub can't fill its bore.
+    if cfg.dancer_spring_inserts {
+        body = spring_hole_xs(cfg).into_iter().fold(body, |body, x| geometry::add_insert_boss(cfg, &body, x, cfg.dancer_spring_hole_offset, base_bottom_z(cfg)));
+    }
 
     // Pivot bore
-    let pivot_hole = centered_cylinder("pivot_hole", cfg.pivot_bore / 2.0, cfg.dancer_arm_thickness + 2.0, 32);
+    let pivot_hole = feature(centered_cylinder("pivot_hole", cfg.pivot_bore / 2.0, geometry::through_length(cfg.dancer_arm_thickness), geometry::segments(cfg, cfg.segments_hole)));
 
     // Bearing bore at roller end
-    let bearing_hole = centered_cylinder("bearing_hole", cfg.bearing_id / 2.0, cfg.dancer_arm_thickness + 2.0, 32)
-        .translate(cfg.dancer_arm_length, 0.0, 0.0);
+    let bearing_hole = feature(centered_cylinder("bearing_hole", cfg.bearing_id / 2.0, geometry::through_length(cfg.dancer_arm_thickness), geometry::segments(cfg, cfg.segments_hole))
+        .translate(cfg.dancer_arm_length, 0.0, 0.0));
+
+    // Optional counterbore on the outer face for a shoulder screw head; the
+    // bearing bore below it stays at bearing_id.
+    let counterbore = if cfg.dancer_roller_counterbore_diameter > 0.0 {
+        let depth = cfg.dancer_roller_counterbore_depth + geometry::HOLE_OVERCUT;
+        feature(Part::cylinder("roller_counterbore", cfg.dancer_roller_counterbore_diameter / 2.0, depth, geometry::segments(cfg, cfg.segments_hole))
+            .translate(cfg.dancer_arm_length, 0.0, cfg.dancer_arm_thickness / 2.0 - cfg.dancer_roller_counterbore_depth))
+    } else {
+        Part::empty("roller_counterbore")
+    };
+
+    let arm = body - pivot_hole - bearing_hole - counterbore;
+    if cfg.dancer_spring_inserts {
+        return arm.with_name("dancer_arm");
+    }
+
+    // Without inserts, plain spring holes for the hook.
+    let spring_hole = centered_cylinder("spring_hole", cfg.dancer_spring_hole_diameter / 2.0, geometry::through_length(cfg.dancer_arm_thickness), geometry::segments(cfg, cfg.segments_hole))
+        .translate(0.0, cfg.dancer_spring_hole_offset, 0.0);
+    let spring_holes = feature(spring_hole_xs(cfg).into_iter().map(|x| spring_hole.translate(x, 0.0, 0.0)).reduce(|a, b| a + b).expect("there is always a first spring hole"));
+
+    (arm - spring_holes).with_name("dancer_arm")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::contains_point;
+    use nalgebra::Point3;
+
+    #[test]
+    fn ribbed_bar_is_pocketed_but_keeps_hubs_and_spring_bosses_solid() {
+        let mut cfg = crate::config::load_config(None).unwrap();
+        cfg.dancer_spring_hole_x = 30.0;
+        cfg.dancer_spring_inserts = false;
+        let solid = build(&cfg);
+        cfg.dancer_arm_profile = ArmProfile::Ribbed;
+        let arm = build(&cfg);
+        assert!(arm.volume() < solid.volume());
+        let near_face = cfg.dancer_arm_thickness / 2.0 - 0.1;
+        let (x0, _) = rib_pocket_span(&cfg);
+        for z in [near_face, -near_face] {
+            assert!(!contains_point(&arm, Point3::new((x0 + 25.0) / 2.0, 0.0, z)), "pocketed at z={z}");
+            // Hubs: just inside each hub's rim, on the bar's centerline.
+            assert!(contains_point(&arm, Point3::new(pivot_hub_radius(&cfg) - 0.1, 0.0, z)));
+            assert!(contains_point(&arm, Point3::new(cfg.dancer_arm_length - roller_hub_radius(&cfg) + 0.1, 0.0, z)));
+            // The boss around the spring hole, between the hole and the centerline.
+            assert!(contains_point(&arm, Point3::new(30.0, cfg.dancer_spring_hole_offset - cfg.dancer_spring_hole_diameter / 2.0 - 0.1, z)));
+        }
+        assert!(contains_point(&arm, Point3::new((x0 + 25.0) / 2.0, 0.0, 0.0)), "the web stays");
+        assert!(!contains_point(&arm, Point3::new(30.0, cfg.dancer_spring_hole_offset, 0.0)), "the spring hole stays open");
+    }
 
-    // Spring attachment hole
-    let spring_hole = centered_cylinder("spring_hole", 1.5, cfg.dancer_arm_thickness + 2.0, 32)
-        .translate(10.0, cfg.dancer_arm_width / 2.0 - 1.5, 0.0);
+    #[test]
+    fn spring_inserts_get_a_blind_bore_in_a_boss_on_the_bed() {
+        let mut cfg = crate::config::load_config(None).unwrap();
+        cfg.dancer_spring_inserts = true;
+        let arm = build(&cfg);
+        let (x, y) = (cfg.dancer_spring_hole_x, cfg.dancer_spring_hole_offset);
+        let top = base_bottom_z(&cfg) + geometry::insert_boss_height(&cfg);
+        let in_bore = top - cfg.insert_depth / 2.0;
+        let r = cfg.insert_od / 2.0;
+        assert!(!contains_point(&arm, Point3::new(x, y + r - 0.05, in_bore)), "bore is insert_od across");
+        assert!(contains_point(&arm, Point3::new(x, y + r + 0.05, in_bore)), "boss wall around the bore");
+        assert!(contains_point(&arm, Point3::new(x, y, top - cfg.insert_depth - 0.1)), "floor under the insert");
+        // The boss overhangs the bar but still reaches down to the bed.
+        let outside_bar = y + geometry::insert_boss_od(&cfg) / 2.0 - 0.1;
+        assert!(outside_bar > cfg.dancer_arm_width / 2.0);
+        assert!(contains_point(&arm, Point3::new(x, outside_bar, base_bottom_z(&cfg) + 0.05)));
+        assert_eq!(arm.bounding_box().0[2], base_bottom_z(&cfg));
+    }
 
-    (pivot_hub + roller_hub + bar) - pivot_hole - bearing_hole - spring_hole
+    #[test]
+    fn roller_counterbore_is_blind_and_keeps_the_bearing_bore() {
+        let mut cfg = crate::config::load_config(None).unwrap();
+        cfg.dancer_roller_counterbore_diameter = cfg.bearing_id + 4.0;
+        cfg.dancer_roller_counterbore_depth = 2.0;
+        let arm = build(&cfg);
+        let r = cfg.bearing_id / 2.0 + 1.0;
+        let top = cfg.dancer_arm_thickness / 2.0;
+        let x = cfg.dancer_arm_length + r;
+        assert!(!contains_point(&arm, Point3::new(x, 0.0, top - 1.0)), "recessed at the outer face");
+        assert!(contains_point(&arm, Point3::new(x, 0.0, top - 3.0)), "solid below the counterbore");
+        assert!(!contains_point(&arm, Point3::new(cfg.dancer_arm_length, 0.0, -top + 0.5)), "bearing bore runs through");
+    }
 }
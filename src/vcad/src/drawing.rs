@@ -0,0 +1,331 @@
+//! Orthographic line drawings for documentation.
+//!
+//! Projects a part's feature edges onto a view plane and writes them as SVG.
+//! Only creases sharper than a threshold, open boundaries, and silhouettes
+//! are drawn, so faceted cylinders read as clean outlines rather than
+//! a fan of segment lines.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use nalgebra::{Point3, Vector3};
+use vcad::Part;
+
+use crate::mesh::TriMesh;
+
+/// Dihedral angle (degrees) above which a shared edge is drawn.
+pub const FEATURE_ANGLE_DEG: f64 = 30.0;
+
+/// Margin around the drawing in the SVG viewport (mm).
+const SVG_MARGIN: f64 = 5.0;
+
+/// Orthographic view direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Projection {
+    /// Looking down -Z onto the XY plane.
+    Top,
+    /// Looking along +Y onto the XZ plane.
+    Front,
+    /// Looking along -X onto the YZ plane.
+    Side,
+}
+
+impl Projection {
+    pub fn name(self) -> &'static str {
+        match self {
+            Projection::Top => "top",
+            Projection::Front => "front",
+            Projection::Side => "side",
+        }
+    }
+
+    /// Direction the viewer looks along.
+    fn view_dir(self) -> Vector3<f64> {
+        match self {
+            Projection::Top => Vector3::new(0.0, 0.0, -1.0),
+            Projection::Front => Vector3::new(0.0, 1.0, 0.0),
+            Projection::Side => Vector3::new(-1.0, 0.0, 0.0),
+        }
+    }
+
+    /// Project a point onto the view plane as `(u, v)` with `v` pointing up.
+    pub fn project(self, p: &Point3<f64>) -> (f64, f64) {
+        match self {
+            Projection::Top => (p.x, p.y),
+            Projection::Front => (p.x, p.z),
+            Projection::Side => (p.y, p.z),
+        }
+    }
+}
+
+/// Edges to draw for `projection`: creases sharper than `angle_deg`, edges
+/// with only one adjacent triangle, and silhouette edges where one adjacent
+/// face points toward the viewer and the other away.
+pub fn feature_edges(mesh: &TriMesh, projection: Projection, angle_deg: f64) -> Vec<(Point3<f64>, Point3<f64>)> {
+    let cos_limit = angle_deg.to_radians().cos();
+    let view = projection.view_dir();
+
+    let mut edges = Vec::new();
+    for ((a, b), faces) in mesh.edge_faces() {
+        let keep = match faces.as_slice() {
+            [f1, f2] => {
+                let (n1, n2) = (mesh.normal(*f1), mesh.normal(*f2));
+                let crease = n1.dot(&n2) < cos_limit;
+                let silhouette = (n1.dot(&view) < 0.0) != (n2.dot(&view) < 0.0);
+                crease || silhouette
+            }
+            _ => true,
+        };
+        if keep {
+            edges.push((mesh.vertices[a], mesh.vertices[b]));
+        }
+    }
+    edges
+}
+
+/// Feature edges of a part projected onto one view, with their 2D bounds.
+struct View {
+    lines: Vec<((f64, f64), (f64, f64))>,
+    min: (f64, f64),
+    max: (f64, f64),
+}
+
+impl View {
+    fn new(mesh: &TriMesh, projection: Projection) -> View {
+        let lines: Vec<((f64, f64), (f64, f64))> = feature_edges(mesh, projection, FEATURE_ANGLE_DEG)
+            .iter()
+            .map(|(a, b)| (projection.project(a), projection.project(b)))
+            .collect();
+
+        let (mut min_u, mut min_v, mut max_u, mut max_v) = (0.0_f64, 0.0_f64, 0.0_f64, 0.0_f64);
+        if let Some(&((u, v), _)) = lines.first() {
+            (min_u, min_v, max_u, max_v) = (u, v, u, v);
+        }
+        for &((u1, v1), (u2, v2)) in &lines {
+            min_u = min_u.min(u1).min(u2);
+            max_u = max_u.max(u1).max(u2);
+            min_v = min_v.min(v1).min(v2);
+            max_v = max_v.max(v1).max(v2);
+        }
+        View { lines, min: (min_u, min_v), max: (max_u, max_v) }
+    }
+
+    fn width(&self) -> f64 {
+        self.max.0 - self.min.0
+    }
+
+    fn height(&self) -> f64 {
+        self.max.1 - self.min.1
+    }
+
+    /// Append the view's lines with its top-left corner at `(x, y)` on the
+    /// sheet. SVG y grows downward, so v is flipped about the top of the view.
+    fn write_lines(&self, svg: &mut String, x: f64, y: f64) {
+        let to_svg = |(u, v): (f64, f64)| (u - self.min.0 + x, self.max.1 - v + y);
+        for &(a, b) in &self.lines {
+            let ((x1, y1), (x2, y2)) = (to_svg(a), to_svg(b));
+            let _ = writeln!(svg, r#"<line x1="{x1:.3}" y1="{y1:.3}" x2="{x2:.3}" y2="{y2:.3}"/>"#);
+        }
+    }
+}
+
+fn svg_header(svg: &mut String, width: f64, height: f64) {
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.2}mm" height="{height:.2}mm" viewBox="0 0 {width:.2} {height:.2}">"#
+    );
+}
+
+/// Write the projected feature edges of `part` as an SVG line drawing in mm.
+pub fn write_edges_svg(part: &Part, path: impl AsRef<Path>, projection: Projection) -> std::io::Result<()> {
+    let view = View::new(&TriMesh::from_part(part), projection);
+
+    let mut svg = String::new();
+    svg_header(&mut svg, view.width() + 2.0 * SVG_MARGIN, view.height() + 2.0 * SVG_MARGIN);
+    let _ = writeln!(svg, r#"<g stroke="black" stroke-width="0.25" stroke-linecap="round" fill="none">"#);
+    view.write_lines(&mut svg, SVG_MARGIN, SVG_MARGIN);
+    svg.push_str("</g>\n</svg>\n");
+
+    std::fs::write(path, svg)
+}
+
+/// Characters for `ascii_preview`, from the surface furthest from the viewer
+/// to the nearest.
+const ASCII_RAMP: &[u8] = b".:-=+*#%@";
+
+/// Rough terminal rendering of `part` seen in `projection`, `columns`
+/// characters wide: blank where the view sees through the part, otherwise a
+/// character from [`ASCII_RAMP`] for how near the surface is. Rows are
+/// twice as tall as columns are wide, as terminal cells roughly are.
+pub fn ascii_preview(part: &Part, projection: Projection, columns: usize) -> String {
+    let mesh = TriMesh::from_part(part);
+    if mesh.triangles.is_empty() || columns == 0 {
+        return String::new();
+    }
+    let toward = -projection.view_dir();
+    let projected: Vec<(f64, f64, f64)> = mesh
+        .vertices
+        .iter()
+        .map(|p| {
+            let (u, v) = projection.project(p);
+            (u, v, p.coords.dot(&toward))
+        })
+        .collect();
+    let (mut min_u, mut max_u, mut min_v, mut max_v) = (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+    for &(u, v, _) in &projected {
+        (min_u, max_u, min_v, max_v) = (min_u.min(u), max_u.max(u), min_v.min(v), max_v.max(v));
+    }
+    let cell = ((max_u - min_u) / columns as f64).max(1e-9);
+    let rows = (((max_v - min_v) / (2.0 * cell)).ceil() as usize).max(1);
+
+    // Nearest surface depth at each cell center, row 0 at the top.
+    let mut depth: Vec<Option<f64>> = vec![None; rows * columns];
+    for &[a, b, c] in &mesh.triangles {
+        let [(ua, va, da), (ub, vb, db), (uc, vc, dc)] = [projected[a], projected[b], projected[c]];
+        let area = (ub - ua) * (vc - va) - (uc - ua) * (vb - va);
+        if area.abs() < 1e-12 {
+            continue; // edge-on to the viewer
+        }
+        let col_range = |lo: f64, hi: f64| {
+            let first = ((lo - min_u) / cell - 0.5).ceil().max(0.0) as usize;
+            let last = (((hi - min_u) / cell - 0.5).floor().max(-1.0) + 1.0) as usize;
+            first..last.min(columns)
+        };
+        let row_range = |lo: f64, hi: f64| {
+            let first = ((max_v - hi) / (2.0 * cell) - 0.5).ceil().max(0.0) as usize;
+            let last = (((max_v - lo) / (2.0 * cell) - 0.5).floor().max(-1.0) + 1.0) as usize;
+            first..last.min(rows)
+        };
+        for row in row_range(va.min(vb).min(vc), va.max(vb).max(vc)) {
+            let v = max_v - (row as f64 + 0.5) * 2.0 * cell;
+            for col in col_range(ua.min(ub).min(uc), ua.max(ub).max(uc)) {
+                let u = min_u + (col as f64 + 0.5) * cell;
+                let wb = ((u - ua) * (vc - va) - (uc - ua) * (v - va)) / area;
+                let wc = ((ub - ua) * (v - va) - (u - ua) * (vb - va)) / area;
+                let wa = 1.0 - wb - wc;
+                if wa < -1e-9 || wb < -1e-9 || wc < -1e-9 {
+                    continue;
+                }
+                let d = wa * da + wb * db + wc * dc;
+                let slot = &mut depth[row * columns + col];
+                *slot = Some(slot.map_or(d, |old: f64| old.max(d)));
+            }
+        }
+    }
+
+    let (near, far) = depth.iter().flatten().fold((f64::NEG_INFINITY, f64::INFINITY), |(n, f), &d| (n.max(d), f.min(d)));
+    let mut text = String::new();
+    for row in depth.chunks(columns) {
+        let line: String = row
+            .iter()
+            .map(|d| match d {
+                None => ' ',
+                Some(d) => {
+                    let t = if near > far { (d - far) / (near - far) } else { 1.0 };
+                    ASCII_RAMP[(t * (ASCII_RAMP.len() - 1) as f64).round() as usize] as char
+                }
+            })
+            .collect();
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// Space reserved beside a view for its dimensions, and between views (mm).
+const DIM_SPACE: f64 = 20.0;
+
+/// Distance from a view's edge to its dimension line (mm).
+const DIM_OFFSET: f64 = 8.0;
+
+/// Dimension text height (mm).
+const TEXT_SIZE: f64 = 3.5;
+
+/// Write a third-angle reference sheet for `part`: top view above the front
+/// view, side view to its right, with overall X, Y and Z extents dimensioned.
+pub fn write_drawing_svg(part: &Part, path: impl AsRef<Path>, title: &str) -> std::io::Result<()> {
+    let mesh = TriMesh::from_part(part);
+    let (top, front, side) = (View::new(&mesh, Projection::Top), View::new(&mesh, Projection::Front), View::new(&mesh, Projection::Side));
+
+    // Sheet layout: dimensions left of the top/front column and below the front view.
+    let left = SVG_MARGIN + DIM_SPACE;
+    let top_y = SVG_MARGIN;
+    let front_y = top_y + top.height() + DIM_SPACE;
+    let side_x = left + front.width() + DIM_SPACE;
+    let title_y = front_y + front.height() + DIM_SPACE + TEXT_SIZE;
+    let width = side_x + side.width() + SVG_MARGIN;
+    let height = title_y + SVG_MARGIN;
+
+    let mut svg = String::new();
+    svg_header(&mut svg, width, height);
+    let _ = writeln!(svg, r#"<g stroke="black" stroke-width="0.25" stroke-linecap="round" fill="none">"#);
+    top.write_lines(&mut svg, left, top_y);
+    front.write_lines(&mut svg, left, front_y);
+    side.write_lines(&mut svg, side_x, front_y);
+    svg.push_str("</g>\n");
+
+    let _ = writeln!(svg, r#"<g stroke="black" stroke-width="0.18" fill="none">"#);
+    let front_bottom = front_y + front.height();
+    horizontal_dimension(&mut svg, left, left + front.width(), front_bottom, front_bottom + DIM_OFFSET);
+    vertical_dimension(&mut svg, front_y, front_bottom, left, left - DIM_OFFSET);
+    vertical_dimension(&mut svg, top_y, top_y + top.height(), left, left - DIM_OFFSET);
+    svg.push_str("</g>\n");
+
+    let _ = writeln!(svg, r#"<g font-family="sans-serif" font-size="{TEXT_SIZE}" fill="black" text-anchor="middle">"#);
+    let x_mid = left + front.width() / 2.0;
+    let _ = writeln!(svg, r#"<text x="{x_mid:.3}" y="{:.3}">{:.1}</text>"#, front_bottom + DIM_OFFSET - 1.0, front.width());
+    for (y0, y1, value) in [(front_y, front_bottom, front.height()), (top_y, top_y + top.height(), top.height())] {
+        let (tx, ty) = (left - DIM_OFFSET - 1.0, (y0 + y1) / 2.0);
+        let _ = writeln!(svg, r#"<text x="{tx:.3}" y="{ty:.3}" transform="rotate(-90 {tx:.3} {ty:.3})">{value:.1}</text>"#);
+    }
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.3}" y="{title_y:.3}" text-anchor="start">{title}: {:.1} × {:.1} × {:.1} mm</text>"#,
+        left,
+        front.width(),
+        top.height(),
+        front.height()
+    );
+    svg.push_str("</g>\n</svg>\n");
+
+    std::fs::write(path, svg)
+}
+
+/// Dimension between `x0` and `x1` for an edge at `edge_y`, drawn at `dim_y`.
+fn horizontal_dimension(svg: &mut String, x0: f64, x1: f64, edge_y: f64, dim_y: f64) {
+    let ext = dim_y + 1.5 * (dim_y - edge_y).signum();
+    for x in [x0, x1] {
+        let _ = writeln!(svg, r#"<line x1="{x:.3}" y1="{:.3}" x2="{x:.3}" y2="{ext:.3}"/>"#, edge_y + (dim_y - edge_y).signum());
+        let _ = writeln!(svg, r#"<line x1="{:.3}" y1="{:.3}" x2="{:.3}" y2="{:.3}"/>"#, x - 1.0, dim_y + 1.0, x + 1.0, dim_y - 1.0);
+    }
+    let _ = writeln!(svg, r#"<line x1="{x0:.3}" y1="{dim_y:.3}" x2="{x1:.3}" y2="{dim_y:.3}"/>"#);
+}
+
+/// Dimension between `y0` and `y1` for an edge at `edge_x`, drawn at `dim_x`.
+fn vertical_dimension(svg: &mut String, y0: f64, y1: f64, edge_x: f64, dim_x: f64) {
+    let ext = dim_x + 1.5 * (dim_x - edge_x).signum();
+    for y in [y0, y1] {
+        let _ = writeln!(svg, r#"<line x1="{:.3}" y1="{y:.3}" x2="{ext:.3}" y2="{y:.3}"/>"#, edge_x + (dim_x - edge_x).signum());
+        let _ = writeln!(svg, r#"<line x1="{:.3}" y1="{:.3}" x2="{:.3}" y2="{:.3}"/>"#, dim_x - 1.0, y + 1.0, dim_x + 1.0, y - 1.0);
+    }
+    let _ = writeln!(svg, r#"<line x1="{dim_x:.3}" y1="{y0:.3}" x2="{dim_x:.3}" y2="{y1:.3}"/>"#);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vcad::{centered_cube, centered_cylinder};
+
+    #[test]
+    fn ascii_preview_shows_a_through_hole_and_a_raised_boss() {
+        let plate = centered_cube("plate", 40.0, 20.0, 4.0) - centered_cylinder("hole", 4.0, 6.0, 32).translate(-10.0, 0.0, 0.0);
+        let part = plate + centered_cylinder("boss", 4.0, 4.0, 32).translate(10.0, 0.0, 4.0);
+        let text = ascii_preview(&part, Projection::Top, 40);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 10, "20mm deep at 1mm columns and 2mm rows:\n{text}");
+        let middle = lines[5].as_bytes();
+        assert_eq!(middle[10], b' ', "the hole is see-through:\n{text}");
+        assert_eq!(middle[30], b'@', "the boss top is nearest:\n{text}");
+        assert_eq!(middle[2], b'.', "the plate top is furthest:\n{text}");
+    }
+}
@@ -0,0 +1,72 @@
+//! Metric fastener presets.
+//!
+//! Hole sizes are looked up by designation ("M3", "M4", ...) so retargeting
+//! the whole applicator to a different screw size is a one-line config
+//! change instead of editing every hole diameter.
+
+/// Hole and head dimensions for one screw size (mm).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fastener {
+    pub designation: &'static str,
+    /// Close-fit clearance hole diameter.
+    pub clearance: f64,
+    /// Pilot diameter for tapping or self-threading into plastic.
+    pub tap: f64,
+    /// Socket head cap screw head diameter.
+    pub head: f64,
+    /// Flat head (countersunk) screw head diameter.
+    pub countersunk_head: f64,
+}
+
+impl Fastener {
+    /// The same fastener with every dimension multiplied by `factor`, for
+    /// models built at `model_scale`.
+    pub fn scaled(&self, factor: f64) -> Fastener {
+        Fastener {
+            designation: self.designation,
+            clearance: self.clearance * factor,
+            tap: self.tap * factor,
+            head: self.head * factor,
+            countersunk_head: self.countersunk_head * factor,
+        }
+    }
+}
+
+/// Included angle of a metric flat head screw's countersink (degrees).
+pub const COUNTERSINK_ANGLE: f64 = 90.0;
+
+/// Supported sizes, smallest first.
+pub const FASTENERS: &[Fastener] = &[
+    Fastener { designation: "M2.5", clearance: 2.7, tap: 2.1, head: 4.5, countersunk_head: 5.0 },
+    Fastener { designation: "M3", clearance: 3.2, tap: 2.5, head: 5.5, countersunk_head: 6.0 },
+    Fastener { designation: "M4", clearance: 4.3, tap: 3.3, head: 7.0, countersunk_head: 8.0 },
+];
+
+/// Look up a fastener by designation (case-insensitive).
+pub fn lookup(designation: &str) -> Option<&'static Fastener> {
+    FASTENERS.iter().find(|f| f.designation.eq_ignore_ascii_case(designation))
+}
+
+/// Comma-separated list of supported designations, for error messages.
+pub fn designations() -> String {
+    FASTENERS.iter().map(|f| f.designation).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_is_case_insensitive() {
+        assert_eq!(lookup("m3").map(|f| f.clearance), Some(3.2));
+        assert!(lookup("M5").is_none());
+    }
+
+    #[test]
+    fn sizes_are_internally_consistent() {
+        for f in FASTENERS {
+            assert!(f.tap < f.clearance && f.clearance < f.head, "{}", f.designation);
+            assert!(f.clearance < f.countersunk_head, "{}", f.designation);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Named-feature capture for debugging boolean operations.
+//!
+//! Builders pass each placed primitive through [`feature`] before combining
+//! them. Normally that is a pass-through; while [`capture`] is running, a
+//! copy of every feature is kept so it can be exported on its own and
+//! inspected next to the finished part.
+//!
+//! Cutters that stand for hardware are marked with [`tagged`] instead, so
+//! tests can count them per part and pin down the fastener count.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+use vcad::{CadError, Part};
+
+use crate::mesh::TriMesh;
+
+thread_local! {
+    static CAPTURED: RefCell<Option<Vec<Part>>> = const { RefCell::new(None) };
+    static TAGGED: RefCell<Option<Vec<(Role, Part)>>> = const { RefCell::new(None) };
+}
+
+/// What a tagged cutter is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// A clearance hole for one mounting fastener.
+    MountingHole,
+}
+
+/// Mark a fully placed primitive as a named feature of the part being built.
+pub fn feature(part: Part) -> Part {
+    CAPTURED.with(|captured| {
+        if let Some(list) = captured.borrow_mut().as_mut() {
+            list.push(part.translate(0.0, 0.0, 0.0));
+        }
+    });
+    part
+}
+
+/// Mark a fully placed cutter as a feature, like [`feature`], that serves
+/// `role`.
+pub fn tagged(role: Role, part: Part) -> Part {
+    TAGGED.with(|tagged| {
+        if let Some(list) = tagged.borrow_mut().as_mut() {
+            list.push((role, part.translate(0.0, 0.0, 0.0)));
+        }
+    });
+    feature(part)
+}
+
+/// Run `build` and count the cutters it tagged with `role`. A patterned
+/// cutter counts once per separate copy.
+pub fn count_tagged(role: Role, build: impl FnOnce() -> Part) -> usize {
+    let outer = TAGGED.with(|tagged| tagged.borrow_mut().replace(Vec::new()));
+    build();
+    let cutters = TAGGED.with(|tagged| std::mem::replace(&mut *tagged.borrow_mut(), outer)).unwrap_or_default();
+    cutters.iter().filter(|(r, _)| *r == role).map(|(_, cutter)| TriMesh::from_part(cutter).components().len()).sum()
+}
+
+/// Run `build`, returning the part and every feature it marked.
+///
+/// Captures nest: features marked inside an inner capture are returned by
+/// it and not seen by the outer one.
+pub fn capture(build: impl FnOnce() -> Part) -> (Part, Vec<Part>) {
+    let outer = CAPTURED.with(|captured| captured.borrow_mut().replace(Vec::new()));
+    let part = build();
+    let features = CAPTURED.with(|captured| std::mem::replace(&mut *captured.borrow_mut(), outer)).unwrap_or_default();
+    (part, features)
+}
+
+/// The name a primitive was created with, without the `-union`/`-diff`
+/// suffixes vcad appends when patterns and booleans are applied.
+pub fn base_name(part: &Part) -> &str {
+    part.name.split('-').next().unwrap_or(&part.name)
+}
+
+/// Whether `feature` adds material to `part` rather than cutting it away:
+/// most of an added primitive survives in the finished part, while a cutter
+/// is mostly outside it.
+pub fn is_additive(feature: &Part, part: &Part) -> bool {
+    (feature & part).volume() > feature.volume() / 2.0
+}
+
+/// Write each feature to `<dir>/<name>.stl`, numbering repeated names
+/// (`corner_hole.stl`, `corner_hole_2.stl`, ...). Returns the paths written.
+pub fn write_features(features: &[Part], dir: &Path) -> Result<Vec<PathBuf>, CadError> {
+    std::fs::create_dir_all(dir)?;
+    let mut seen: Vec<&str> = Vec::new();
+    let mut paths = Vec::new();
+    for part in features {
+        let name = base_name(part);
+        seen.push(name);
+        let count = seen.iter().filter(|n| **n == name).count();
+        let file = if count == 1 { format!("{name}.stl") } else { format!("{name}_{count}.stl") };
+        let path = dir.join(file);
+        part.write_stl(&path)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
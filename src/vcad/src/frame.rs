@@ -1,72 +1,239 @@
 //! Main frame — simplified CSG version.
 //!
-//! Base plate with mounting wall, adjustment slots (approximated as holes),
-//! pivot post, and mounting holes.
+//! Base plate with mounting wall, vial cradle adjustment slots, pivot post,
+//! and mounting holes.
 
 use vcad::*;
 
 use crate::config::Config;
+use crate::features::{feature, tagged, Role};
+use crate::guide_roller_bracket;
+use crate::geometry::{self, KeepOuts, PartExt};
+use crate::vial_cradle;
+
+/// Z of the bottom face of the base plate (or standoffs, when enabled) in
+/// build coordinates.
+pub fn base_bottom_z(cfg: &Config) -> f64 {
+    let standoffs = if cfg.frame_standoffs { cfg.frame_standoff_height } else { 0.0 };
+    -cfg.base_thickness / 2.0 - standoffs
+}
+
+/// Z of the top face of the base plate, where the parts bolted to the
+/// frame stand, in build coordinates.
+pub fn base_top_z(cfg: &Config) -> f64 {
+    cfg.base_thickness / 2.0
+}
+
+/// Distance of the corner mounting holes from the base plate edges.
+pub const CORNER_HOLE_INSET: f64 = 8.0;
+
+/// XY centers of the four corner mounting holes.
+pub fn corner_hole_positions(cfg: &Config) -> [(f64, f64); 4] {
+    let x = cfg.frame_length / 2.0 - cfg.mm(CORNER_HOLE_INSET);
+    let y = cfg.frame_width / 2.0 - cfg.mm(CORNER_HOLE_INSET);
+    [(-x, -y), (x, -y), (-x, y), (x, y)]
+}
+
+/// X of the peel plate mounting wall's center plane.
+pub fn peel_wall_x(cfg: &Config) -> f64 {
+    cfg.frame_length / 2.0 - cfg.frame_wall_thickness / 2.0 - cfg.mm(5.0)
+}
+
+/// Radius of the through hole the spool holder sits over.
+pub const SPOOL_HOLE_RADIUS: f64 = 12.5;
+
+/// XY center of the spool holder on the base plate.
+pub fn spool_center(cfg: &Config) -> (f64, f64) {
+    (-cfg.frame_length / 2.0 + cfg.mm(30.0), -cfg.frame_width / 2.0 + cfg.mm(30.0))
+}
+
+/// XY center of the dancer arm pivot post.
+pub fn pivot_center(cfg: &Config) -> (f64, f64) {
+    (-cfg.frame_length / 2.0 + cfg.mm(80.0), -cfg.frame_width / 2.0 + cfg.mm(35.0))
+}
+
+/// XY center of the vial cradle on the base plate.
+pub fn cradle_center(cfg: &Config) -> (f64, f64) {
+    (peel_wall_x(cfg) - cfg.mm(35.0), cfg.mm(25.0))
+}
+
+/// XY center of the guide roller bracket on the base plate.
+pub fn guide_center(cfg: &Config) -> (f64, f64) {
+    (peel_wall_x(cfg) - cfg.mm(70.0), -cfg.frame_width / 2.0 + cfg.mm(25.0))
+}
+
+/// XY centers of the four vial cradle mounting slots.
+pub fn cradle_hole_positions(cfg: &Config) -> [(f64, f64); 4] {
+    let (cx, cy) = cradle_center(cfg);
+    let sx = cfg.cradle_mount_slot_spacing_x / 2.0;
+    let sy = cfg.cradle_mount_slot_spacing_y / 2.0;
+    [(cx - sx, cy - sy), (cx + sx, cy - sy), (cx - sx, cy + sy), (cx + sx, cy + sy)]
+}
+
+/// XY centers of the guide roller bracket mounting holes.
+pub fn guide_hole_positions(cfg: &Config) -> Vec<(f64, f64)> {
+    let (gx, gy) = guide_center(cfg);
+    guide_roller_bracket::mount_hole_positions(cfg).into_iter().map(|(x, y)| (gx + x, gy + y)).collect()
+}
 
 pub fn build(cfg: &Config) -> Part {
     let pivot_post_od = cfg.pivot_bore;
-    let m3_hole = cfg.mount_hole_diameter;
+    let m3_hole = cfg.fastener().clearance;
 
     // Component positions (origin at base plate center)
-    let peel_wall_x = cfg.frame_length / 2.0 - cfg.frame_wall_thickness / 2.0 - 5.0;
-    let cradle_center_x = peel_wall_x - 35.0;
-    let cradle_center_y = 25.0;
-    let spool_x = -cfg.frame_length / 2.0 + 30.0;
-    let spool_y = -cfg.frame_width / 2.0 + 30.0;
-    let dancer_x = -cfg.frame_length / 2.0 + 80.0;
-    let dancer_y = -cfg.frame_width / 2.0 + 35.0;
-    let guide_x = peel_wall_x - 70.0;
-    let guide_y = -cfg.frame_width / 2.0 + 25.0;
+    let peel_wall_x = peel_wall_x(cfg);
+    let (spool_x, spool_y) = spool_center(cfg);
+    let (dancer_x, dancer_y) = pivot_center(cfg);
+
+    // Everything that must stay solid around it for generated pockets.
+    let mut keep_outs = KeepOuts::default();
+    let margin = cfg.keep_out_margin;
 
     // Base plate
-    let base = centered_cube("base", cfg.frame_length, cfg.frame_width, cfg.base_thickness);
+    let base = feature(centered_cube("base", cfg.frame_length, cfg.frame_width, cfg.base_thickness));
 
     // Peel plate mounting wall
-    let wall = centered_cube("wall", cfg.frame_wall_thickness, cfg.frame_width * 0.5, cfg.frame_wall_height)
-        .translate(peel_wall_x, 0.0, cfg.base_thickness / 2.0 + cfg.frame_wall_height / 2.0);
+    let wall = feature(centered_cube("wall", cfg.frame_wall_thickness, cfg.frame_width * 0.5, cfg.frame_wall_height)
+        .translate(peel_wall_x, 0.0, base_top_z(cfg) + cfg.frame_wall_height / 2.0));
+    keep_outs.add_rect(peel_wall_x, 0.0, cfg.frame_wall_thickness + 2.0 * margin, cfg.frame_width * 0.5 + 2.0 * margin);
 
     // Dancer arm pivot post
-    let post = centered_cylinder("post", pivot_post_od / 2.0, cfg.pivot_post_height, 32)
-        .translate(dancer_x, dancer_y, cfg.base_thickness / 2.0 + cfg.pivot_post_height / 2.0);
+    let post_top_r = geometry::drafted_radius(pivot_post_od / 2.0, cfg.pivot_post_height, cfg.pivot_post_draft);
+    let post = feature(geometry::centered_cone("post", pivot_post_od / 2.0, post_top_r, cfg.pivot_post_height, geometry::segments(cfg, cfg.segments_hole))
+        .translate(dancer_x, dancer_y, base_top_z(cfg) + cfg.pivot_post_height / 2.0));
 
-    // Reinforcement at post base
-    let reinforce = centered_cylinder("reinforce", pivot_post_od / 2.0 + 3.0, 6.0, 32)
-        .translate(dancer_x, dancer_y, cfg.base_thickness / 2.0 + 3.0);
+    // Reinforcement at post base — a cone flaring from the post out to
+    // pivot_reinforce_radius beyond it at the base, concentric with the post
+    // and meeting its drafted side.
+    let reinforce = feature(geometry::centered_cone(
+        "reinforce",
+        pivot_post_od / 2.0 + cfg.pivot_reinforce_radius,
+        geometry::drafted_radius(pivot_post_od / 2.0, cfg.pivot_reinforce_height, cfg.pivot_post_draft),
+        cfg.pivot_reinforce_height,
+        geometry::segments(cfg, cfg.segments_hole),
+    )
+    .translate(dancer_x, dancer_y, base_top_z(cfg) + cfg.pivot_reinforce_height / 2.0));
+    keep_outs.add_circle(dancer_x, dancer_y, pivot_post_od / 2.0 + cfg.pivot_reinforce_radius + margin);
 
     // Spool spindle hole
-    let spool_hole = centered_cylinder("spool_hole", 12.5, cfg.base_thickness + 2.0, 32)
-        .translate(spool_x, spool_y, 0.0);
+    let spool_hole = feature(centered_cylinder("spool_hole", cfg.mm(SPOOL_HOLE_RADIUS), geometry::through_length(cfg.base_thickness), geometry::segments(cfg, cfg.segments_hole))
+        .translate(spool_x, spool_y, 0.0));
+    keep_outs.add_circle(spool_x, spool_y, cfg.mm(SPOOL_HOLE_RADIUS) + margin);
 
     // Guide roller bracket mounting holes
-    let guide_hole = centered_cylinder("guide_hole", m3_hole / 2.0, cfg.base_thickness + 2.0, 32);
-    let guide_holes = guide_hole
-        .linear_pattern(15.0, 0.0, 0.0, 2)
-        .translate(guide_x - 7.5, guide_y, 0.0);
-
-    // Corner mounting holes
-    let corner_hole = centered_cylinder("corner", m3_hole / 2.0, cfg.base_thickness + 2.0, 32);
-    let inset = 8.0;
-    let c1 = corner_hole.translate(-cfg.frame_length / 2.0 + inset, -cfg.frame_width / 2.0 + inset, 0.0);
-    let c2 = corner_hole.translate(cfg.frame_length / 2.0 - inset, -cfg.frame_width / 2.0 + inset, 0.0);
-    let c3 = corner_hole.translate(-cfg.frame_length / 2.0 + inset, cfg.frame_width / 2.0 - inset, 0.0);
-    let c4 = corner_hole.translate(cfg.frame_length / 2.0 - inset, cfg.frame_width / 2.0 - inset, 0.0);
-
-    // Cradle mounting holes (simplified from slots to round holes)
-    let cradle_hole = centered_cylinder("cradle_hole", m3_hole / 2.0, cfg.base_thickness + 2.0, 32);
-    let sx = cfg.cradle_mount_slot_spacing_x / 2.0;
-    let sy = cfg.cradle_mount_slot_spacing_y / 2.0;
-    let ch1 = cradle_hole.translate(cradle_center_x - sx, cradle_center_y - sy, 0.0);
-    let ch2 = cradle_hole.translate(cradle_center_x + sx, cradle_center_y - sy, 0.0);
-    let ch3 = cradle_hole.translate(cradle_center_x - sx, cradle_center_y + sy, 0.0);
-    let ch4 = cradle_hole.translate(cradle_center_x + sx, cradle_center_y + sy, 0.0);
+    let guide_hole = centered_cylinder("guide_hole", m3_hole / 2.0, geometry::through_length(cfg.base_thickness), geometry::segments(cfg, cfg.segments_hole));
+    let (gx, gy) = guide_center(cfg);
+    let guide_holes = tagged(Role::MountingHole, guide_hole
+        .grid_pattern(cfg.bracket_mount_hole_count, 1, cfg.bracket_mount_hole_spacing, 0.0, true)
+        .translate(gx, gy, 0.0));
+    for (x, y) in guide_hole_positions(cfg) {
+        keep_outs.add_circle(x, y, m3_hole / 2.0 + margin);
+    }
+
+    // Corner mounting holes, chamfered on the bottom face unless standoffs
+    // cover it.
+    let corners = corner_hole_positions(cfg);
+    let exit_chamfer = if cfg.frame_standoffs { 0.0 } else { cfg.hole_exit_chamfer };
+    let corner_hole = centered_cylinder("corner_hole", m3_hole / 2.0, geometry::through_length(cfg.base_thickness), geometry::segments(cfg, cfg.segments_hole))
+        .chamfer_hole_exit(exit_chamfer, geometry::segments(cfg, cfg.segments_hole));
+    let corner_holes = tagged(Role::MountingHole, corner_hole
+        .translate(corners[3].0, corners[3].1, 0.0)
+        .mirror_copy(1.0, 0.0, 0.0)
+        .mirror_copy(0.0, 1.0, 0.0));
+    for (x, y) in corners {
+        keep_outs.add_circle(x, y, m3_hole / 2.0 + margin);
+    }
+
+    // Cradle mounting slots, matching the cradle's own so its position can
+    // be adjusted along cradle_slot_axis
+    let cradle_hole = vial_cradle::mount_slot(cfg, "cradle_hole", cfg.base_thickness);
+    let cradle_holes = cradle_hole_positions(cfg);
+    let (reach_x, reach_y) = vial_cradle::slot_reach(cfg);
+    let ch1 = tagged(Role::MountingHole, cradle_hole.translate(cradle_holes[0].0, cradle_holes[0].1, 0.0));
+    let ch2 = tagged(Role::MountingHole, cradle_hole.translate(cradle_holes[1].0, cradle_holes[1].1, 0.0));
+    let ch3 = tagged(Role::MountingHole, cradle_hole.translate(cradle_holes[2].0, cradle_holes[2].1, 0.0));
+    let ch4 = tagged(Role::MountingHole, cradle_hole.translate(cradle_holes[3].0, cradle_holes[3].1, 0.0));
+    let band = m3_hole + 2.0 * margin;
+    for (x, y) in cradle_holes {
+        keep_outs.add_circle(x - reach_x, y - reach_y, band / 2.0);
+        keep_outs.add_circle(x + reach_x, y + reach_y, band / 2.0);
+        // The straight run between the round ends.
+        let (width, depth) = if reach_x > 0.0 { (2.0 * reach_x, band) } else { (band, 2.0 * reach_y) };
+        keep_outs.add_rect(x, y, width, depth);
+    }
+
+    // Optional standoffs under the base, each bored through so it doubles
+    // as a mounting point. They default to the corner hole positions.
+    // Pivot post bore — a blind hole down from the post top for the pin or
+    // retaining screw, or all the way through when the depth is 0. Shares
+    // the post's axis so post, reinforcement and bore stay concentric.
+    let post_top = base_top_z(cfg) + cfg.pivot_post_height;
+    let bore_bottom = if cfg.pivot_post_bore_depth > 0.0 {
+        post_top - cfg.pivot_post_bore_depth
+    } else {
+        -cfg.base_thickness / 2.0 - geometry::HOLE_OVERCUT
+    };
+    let bore_length = post_top + geometry::HOLE_OVERCUT - bore_bottom;
+    let post_bore = if cfg.pivot_post_bore > 0.0 {
+        feature(centered_cylinder("post_bore", cfg.pivot_post_bore / 2.0, bore_length, geometry::segments(cfg, cfg.segments_hole))
+            .translate(dancer_x, dancer_y, bore_bottom + bore_length / 2.0))
+    } else {
+        Part::empty("post_bore")
+    };
 
-    (base + wall + post + reinforce)
+    let mut frame = (base + wall + post + reinforce)
+        - post_bore
         - spool_hole
         - guide_holes
-        - c1 - c2 - c3 - c4
-        - ch1 - ch2 - ch3 - ch4
+        - corner_holes
+        - ch1 - ch2 - ch3 - ch4;
+    if cfg.frame_standoffs {
+        let positions = cfg.frame_standoff_positions.clone().unwrap_or_else(|| corners.to_vec());
+        let boss = geometry::standoff("standoff", cfg.frame_standoff_od, m3_hole, cfg.frame_standoff_height, geometry::segments(cfg, cfg.segments_hole))
+            .translate(0.0, 0.0, -cfg.base_thickness / 2.0 - cfg.frame_standoff_height / 2.0);
+        let bore = centered_cylinder("standoff_bore", m3_hole / 2.0, geometry::through_length(cfg.base_thickness), geometry::segments(cfg, cfg.segments_hole));
+        for (x, y) in positions {
+            frame = frame + feature(boss.translate(x, y, 0.0)) - bore.translate(x, y, 0.0);
+            keep_outs.add_circle(x, y, cfg.frame_standoff_od / 2.0 + margin);
+        }
+    }
+
+    // Optional lightening pockets cut up from the underside, leaving a
+    // solid skin of frame_pocket_floor on top.
+    if !cfg.frame_pockets {
+        return frame.with_name("main_frame");
+    }
+    let pocket_depth = cfg.base_thickness - cfg.frame_pocket_floor;
+    let pockets = feature(geometry::pocket_grid(
+        "pocket",
+        &keep_outs,
+        cfg.frame_length - 2.0 * cfg.mm(CORNER_HOLE_INSET),
+        cfg.frame_width - 2.0 * cfg.mm(CORNER_HOLE_INSET),
+        cfg.frame_pocket_size,
+        cfg.frame_pocket_pitch,
+        pocket_depth + geometry::HOLE_OVERCUT,
+    )
+    .translate(0.0, 0.0, -cfg.base_thickness / 2.0 + (pocket_depth - geometry::HOLE_OVERCUT) / 2.0));
+    (frame - pockets).with_name("main_frame")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::contains_point;
+    use crate::config::load_config;
+    use nalgebra::Point3;
+
+    #[test]
+    fn pivot_post_is_bored_on_axis_without_breaking_through_the_base() {
+        let cfg = load_config(None).unwrap();
+        assert!(cfg.pivot_post_bore > 0.0 && cfg.pivot_post_bore_depth > 0.0);
+        let frame = build(&cfg);
+        let (x, y) = pivot_center(&cfg);
+        let post_top = cfg.base_thickness / 2.0 + cfg.pivot_post_height;
+        let wall = (cfg.pivot_bore + cfg.pivot_post_bore) / 4.0;
+        assert!(!contains_point(&frame, Point3::new(x, y, post_top - 1.0)), "bore should be open on the post axis");
+        assert!(contains_point(&frame, Point3::new(x + wall, y, post_top - 1.0)), "post wall should be solid");
+        assert!(contains_point(&frame, Point3::new(x, y, post_top - cfg.pivot_post_bore_depth - 1.0)), "bore should be blind");
+    }
 }
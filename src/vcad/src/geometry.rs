@@ -0,0 +1,1155 @@
+//! Reusable geometry helpers built on top of vcad primitives.
+//!
+//! vcad has no native chamfer or fillet support, so edge treatments are
+//! built here from boolean cuts against cones and cylinders.
+
+use std::cell::Cell;
+use std::path::Path;
+
+use vcad::*;
+
+use crate::config::Config;
+use crate::mesh;
+
+/// How far every through-cut extends past each face it breaks through (mm).
+///
+/// Cutters that end exactly on a face leave coplanar geometry that the
+/// boolean kernel may resolve as a paper-thin membrane.
+pub const HOLE_OVERCUT: f64 = 1.0;
+
+/// Length of a cutter that clears both faces of a solid `extent` thick.
+pub fn through_length(extent: f64) -> f64 {
+    extent + 2.0 * HOLE_OVERCUT
+}
+
+/// One of the twelve edges of a box, named by the two faces meeting at it:
+/// top and bottom are +Z and -Z, front and back +Y and -Y, right and left
+/// +X and -X.
+// The peel plate chamfers one edge; the rest complete the set.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeEdge {
+    TopFront,
+    TopBack,
+    BottomFront,
+    BottomBack,
+    TopRight,
+    TopLeft,
+    BottomRight,
+    BottomLeft,
+    FrontRight,
+    FrontLeft,
+    BackRight,
+    BackLeft,
+}
+
+impl CubeEdge {
+    #[cfg(test)]
+    pub const ALL: [CubeEdge; 12] = [
+        CubeEdge::TopFront,
+        CubeEdge::TopBack,
+        CubeEdge::BottomFront,
+        CubeEdge::BottomBack,
+        CubeEdge::TopRight,
+        CubeEdge::TopLeft,
+        CubeEdge::BottomRight,
+        CubeEdge::BottomLeft,
+        CubeEdge::FrontRight,
+        CubeEdge::FrontLeft,
+        CubeEdge::BackRight,
+        CubeEdge::BackLeft,
+    ];
+
+    /// Which side of the box the edge lies on along each axis: +1 or -1,
+    /// and 0 along the axis it runs.
+    fn sides(self) -> [f64; 3] {
+        match self {
+            CubeEdge::TopFront => [0.0, 1.0, 1.0],
+            CubeEdge::TopBack => [0.0, -1.0, 1.0],
+            CubeEdge::BottomFront => [0.0, 1.0, -1.0],
+            CubeEdge::BottomBack => [0.0, -1.0, -1.0],
+            CubeEdge::TopRight => [1.0, 0.0, 1.0],
+            CubeEdge::TopLeft => [-1.0, 0.0, 1.0],
+            CubeEdge::BottomRight => [1.0, 0.0, -1.0],
+            CubeEdge::BottomLeft => [-1.0, 0.0, -1.0],
+            CubeEdge::FrontRight => [1.0, 1.0, 0.0],
+            CubeEdge::FrontLeft => [-1.0, 1.0, 0.0],
+            CubeEdge::BackRight => [1.0, -1.0, 0.0],
+            CubeEdge::BackLeft => [-1.0, -1.0, 0.0],
+        }
+    }
+}
+
+/// Helpers vcad's `Part` lacks.
+pub trait PartExt {
+    /// Rename a finished part. vcad names a boolean result after its first
+    /// operand (`"base-union"`), so builders rename what they return to the
+    /// component it is.
+    fn with_name(self, name: &str) -> Part;
+
+    /// `count_x` × `count_y` copies at `pitch_x` × `pitch_y` spacing. The
+    /// first copy stays in place unless `centered`, which shifts the grid so
+    /// its middle sits where the part was.
+    fn grid_pattern(&self, count_x: usize, count_y: usize, pitch_x: f64, pitch_y: f64, centered: bool) -> Part;
+
+    /// `count` copies evenly spaced around the Z axis at `radius`, the first
+    /// `start_angle_deg` counterclockwise from +X, at [`polar_positions`].
+    /// Unlike vcad's `circular_pattern`, the start angle is free, so a bolt
+    /// circle can be turned clear of other features.
+    fn polar_pattern(&self, count: usize, radius: f64, start_angle_deg: f64) -> Part;
+
+    /// The part plus its reflection across the plane through the origin
+    /// normal to `(nx, ny, nz)`. Unlike vcad's `mirror_*`, which replace the
+    /// part, this keeps it: place one hole and get the symmetric pair.
+    fn mirror_copy(&self, nx: f64, ny: f64, nz: f64) -> Part;
+
+    /// The part trimmed to the axis-aligned box from `min` to `max`. The
+    /// kernel caps every cut, so the result is still a closed solid.
+    fn clip_to_box(&self, min: [f64; 3], max: [f64; 3]) -> Part;
+
+    /// A Z-aligned through-hole cutter (centered, `through_length` long)
+    /// with a 45° chamfer of `size` added where it leaves the bottom face,
+    /// so the exit rim prints without a burr. Orient the cutter after
+    /// adding it; `size` of 0 leaves the hole plain.
+    fn chamfer_hole_exit(&self, size: f64, segments: u32) -> Part;
+
+    /// The part with a 45° chamfer `size` deep on each face cut along
+    /// `edge` of its bounding box, by subtracting a square prism turned 45°
+    /// about the edge. Meant for box-like parts; `size` of 0 leaves it as is.
+    fn chamfer_edge(&self, edge: CubeEdge, size: f64) -> Part;
+
+    /// The part moved along Z so its bottom sits `gap` above the top of
+    /// `base` (0 to touch), by their bounding boxes. X and Y are kept, so
+    /// coaxial parts stay on their shared axis.
+    fn stack_on(&self, base: &Part, gap: f64) -> Part;
+
+    /// The translation that sets the part on the print bed: its lowest
+    /// mesh point to Z=0 and its bounding-box footprint centered on the
+    /// XY origin.
+    fn bed_offset(&self) -> [f64; 3];
+
+    /// Outward normal and area (mm²) of the largest flat face the part can
+    /// rest on: all the triangles lying in one plane with nothing beyond
+    /// it, even as separate patches such as the feet of several standoffs.
+    /// Of equal faces the one facing most nearly down wins, so a part
+    /// already lying flat stays as it is.
+    fn largest_flat_face(&self) -> ([f64; 3], f64);
+
+    /// The part rotated so the face with outward `normal` points down
+    /// (-Z), then moved along Z so that face sits at Z=0.
+    fn lay_flat(&self, normal: [f64; 3]) -> Part;
+
+    /// Move every face of the part `amount` along its outward normal:
+    /// positive grows the solid, negative shrinks it. Each vertex moves so
+    /// that all of its faces' planes shift by `amount`, keeping the mesh
+    /// connected. Meant for fit tuning of a few hundredths on simple
+    /// features; large offsets on complex geometry can fold the mesh
+    /// through itself and should be avoided.
+    fn offset_surface(&self, amount: f64) -> Part;
+
+    /// One-line summary for logs: name, bounding box size, triangle count,
+    /// volume and whether the mesh is closed, e.g.
+    /// `vial_cradle: 48×36×23 mm, 1,284 tris, 18.2 cm³, manifold`.
+    fn describe(&self) -> String;
+
+    /// Save the part's name and exact mesh (f32 vertices, unlike STL's
+    /// rounded text or merged output) as JSON, for caching and debugging.
+    fn write_json(&self, path: &Path) -> Result<(), String>;
+
+    /// Load a part saved by `write_json`. Vertex positions come back bit
+    /// for bit, but the kernel re-indexes the mesh and drops degenerate
+    /// slivers, so triangle order and count may differ from the original.
+    fn read_json(path: &Path) -> Result<Part, String>;
+}
+
+/// On-disk form of `PartExt::write_json`. vcad parts carry nothing but a
+/// name and a mesh, so this is the whole part.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JsonPart {
+    name: String,
+    vertices: Vec<[f32; 3]>,
+    triangles: Vec<[u32; 3]>,
+}
+
+impl PartExt for Part {
+    fn with_name(mut self, name: &str) -> Part {
+        self.name = name.into();
+        self
+    }
+
+    fn grid_pattern(&self, count_x: usize, count_y: usize, pitch_x: f64, pitch_y: f64, centered: bool) -> Part {
+        let grid = self.linear_pattern(pitch_x, 0.0, 0.0, count_x).linear_pattern(0.0, pitch_y, 0.0, count_y);
+        if !centered {
+            return grid;
+        }
+        let span = |count: usize, pitch: f64| count.saturating_sub(1) as f64 * pitch;
+        grid.translate(-span(count_x, pitch_x) / 2.0, -span(count_y, pitch_y) / 2.0, 0.0)
+    }
+
+    fn polar_pattern(&self, count: usize, radius: f64, start_angle_deg: f64) -> Part {
+        (0..count).fold(Part::empty("polar_pattern"), |pattern, i| {
+            let angle = start_angle_deg + 360.0 * i as f64 / count as f64;
+            pattern + self.translate(radius, 0.0, 0.0).rotate(0.0, 0.0, angle)
+        })
+    }
+
+    fn mirror_copy(&self, nx: f64, ny: f64, nz: f64) -> Part {
+        let n = nalgebra::Vector3::new(nx, ny, nz).normalize();
+        let mesh = mesh::TriMesh::from_part(self);
+        let vertices: Vec<f32> = mesh
+            .vertices
+            .iter()
+            .flat_map(|p| {
+                let r = p - n * (2.0 * p.coords.dot(&n));
+                [r.x as f32, r.y as f32, r.z as f32]
+            })
+            .collect();
+        // A reflection turns every triangle inside out; swap two corners to
+        // keep the normals pointing outward.
+        let indices: Vec<u32> = mesh.triangles.iter().flat_map(|&[a, b, c]| [a, c, b].map(|i| i as u32)).collect();
+        let mirrored = Part::new(self.name.clone(), manifold_rs::Manifold::from_mesh(manifold_rs::Mesh::new(&vertices, &indices)));
+        self + &mirrored
+    }
+
+    fn clip_to_box(&self, min: [f64; 3], max: [f64; 3]) -> Part {
+        let size = [0, 1, 2].map(|k| max[k] - min[k]);
+        let window = Part::cube("clip", size[0], size[1], size[2]).translate(min[0], min[1], min[2]);
+        (self & &window).with_name(&self.name)
+    }
+
+    fn chamfer_hole_exit(&self, size: f64, segments: u32) -> Part {
+        if size <= 0.0 {
+            return self.translate(0.0, 0.0, 0.0);
+        }
+        let (min, max) = self.bounding_box();
+        let radius = (max[0] - min[0]) / 2.0;
+        // Widest past the face so the cone's base doesn't sit on it.
+        let height = size + HOLE_OVERCUT;
+        let cone = centered_cone("exit_chamfer", radius + height, radius, height, segments)
+            .translate((min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0, min[2] + height / 2.0);
+        (self + &cone).with_name(&self.name)
+    }
+
+    fn chamfer_edge(&self, edge: CubeEdge, size: f64) -> Part {
+        if size <= 0.0 {
+            return self.translate(0.0, 0.0, 0.0);
+        }
+        let (min, max) = self.bounding_box();
+        // A square of side size·√2 centered on the edge and turned 45°
+        // reaches `size` into both faces.
+        let mut dims = [size * std::f64::consts::SQRT_2; 3];
+        let (mut at, mut turn) = ([0.0; 3], [0.0; 3]);
+        for (axis, side) in edge.sides().into_iter().enumerate() {
+            if side == 0.0 {
+                dims[axis] = through_length(max[axis] - min[axis]);
+                at[axis] = (min[axis] + max[axis]) / 2.0;
+                turn[axis] = 45.0;
+            } else {
+                at[axis] = if side > 0.0 { max[axis] } else { min[axis] };
+            }
+        }
+        let cut = centered_cube("edge_chamfer", dims[0], dims[1], dims[2]).rotate(turn[0], turn[1], turn[2]).translate(at[0], at[1], at[2]);
+        (self - &cut).with_name(&self.name)
+    }
+
+    fn stack_on(&self, base: &Part, gap: f64) -> Part {
+        let ((bottom, _), (_, top)) = (self.bounding_box(), base.bounding_box());
+        self.translate(0.0, 0.0, top[2] + gap - bottom[2])
+    }
+
+    fn bed_offset(&self) -> [f64; 3] {
+        let (min, max) = self.bounding_box();
+        // Subtracting from 0.0 gives 0.0, not -0.0, for a part already in place.
+        [0.0 - (min[0] + max[0]) / 2.0, 0.0 - (min[1] + max[1]) / 2.0, 0.0 - min[2]]
+    }
+
+    fn largest_flat_face(&self) -> ([f64; 3], f64) {
+        let mesh = mesh::TriMesh::from_part(self);
+        // (normal, plane offset, area) per plane found so far.
+        let mut planes: Vec<(nalgebra::Vector3<f64>, f64, f64)> = Vec::new();
+        for tri in 0..mesh.triangles.len() {
+            let [a, b, c] = mesh.corners(tri);
+            let area = (b - a).cross(&(c - a)).norm() / 2.0;
+            let normal = mesh.normal(tri);
+            if area == 0.0 || normal == nalgebra::Vector3::zeros() {
+                continue;
+            }
+            let offset = normal.dot(&a.coords);
+            match planes.iter_mut().find(|(n, d, _)| n.dot(&normal) > 1.0 - 1e-9 && (d - offset).abs() < 1e-4) {
+                Some(plane) => plane.2 += area,
+                None => planes.push((normal, offset, area)),
+            }
+        }
+        planes
+            .into_iter()
+            .filter(|(n, d, _)| mesh.vertices.iter().all(|p| n.dot(&p.coords) <= d + 1e-4))
+            .max_by(|(n1, _, a1), (n2, _, a2)| if (a1 - a2).abs() > 1e-6 { a1.total_cmp(a2) } else { n2.z.total_cmp(&n1.z) })
+            .map_or(([0.0, 0.0, -1.0], 0.0), |(n, _, area)| ([n.x, n.y, n.z], area))
+    }
+
+    fn lay_flat(&self, normal: [f64; 3]) -> Part {
+        let down = -nalgebra::Vector3::z();
+        let normal = nalgebra::Vector3::from(normal).normalize();
+        // rotation_between has no unique answer for opposite vectors; any
+        // half turn about a horizontal axis will do.
+        let rotation = nalgebra::Rotation3::rotation_between(&normal, &down)
+            .unwrap_or_else(|| nalgebra::Rotation3::from_axis_angle(&nalgebra::Vector3::x_axis(), std::f64::consts::PI));
+        let mesh = mesh::TriMesh::from_part(self);
+        let vertices: Vec<f32> = mesh
+            .vertices
+            .iter()
+            .flat_map(|p| {
+                let r = rotation * p;
+                [r.x as f32, r.y as f32, r.z as f32]
+            })
+            .collect();
+        let indices: Vec<u32> = mesh.triangles.iter().flatten().map(|&i| i as u32).collect();
+        let rotated = Part::new(self.name.clone(), manifold_rs::Manifold::from_mesh(manifold_rs::Mesh::new(&vertices, &indices)));
+        let (min, _) = rotated.bounding_box();
+        rotated.translate(0.0, 0.0, 0.0 - min[2])
+    }
+
+    fn offset_surface(&self, amount: f64) -> Part {
+        if amount == 0.0 {
+            return self.translate(0.0, 0.0, 0.0);
+        }
+        let mesh = mesh::TriMesh::from_part(self);
+        let mut normals: Vec<Vec<nalgebra::Vector3<f64>>> = vec![Vec::new(); mesh.vertices.len()];
+        for (tri, corners) in mesh.triangles.iter().enumerate() {
+            let normal = mesh.normal(tri);
+            for &v in corners {
+                if normal != nalgebra::Vector3::zeros() && !normals[v].iter().any(|n| (n - normal).norm() < 1e-6) {
+                    normals[v].push(normal);
+                }
+            }
+        }
+        // Least squares for n·d = amount over the distinct face normals;
+        // the SVD's pseudo-inverse covers vertices on a flat or a single
+        // crease, where fewer than three normals pin the move down.
+        let vertices: Vec<f32> = mesh
+            .vertices
+            .iter()
+            .zip(&normals)
+            .flat_map(|(p, normals)| {
+                let (mut ata, mut atb) = (nalgebra::Matrix3::zeros(), nalgebra::Vector3::zeros());
+                for n in normals {
+                    ata += n * n.transpose();
+                    atb += n * amount;
+                }
+                let d = ata.svd(true, true).solve(&atb, 1e-9).unwrap_or_else(|_| nalgebra::Vector3::zeros());
+                let q = p + d;
+                [q.x as f32, q.y as f32, q.z as f32]
+            })
+            .collect();
+        let indices: Vec<u32> = mesh.triangles.iter().flatten().map(|&i| i as u32).collect();
+        Part::new(self.name.clone(), manifold_rs::Manifold::from_mesh(manifold_rs::Mesh::new(&vertices, &indices)))
+    }
+
+    fn describe(&self) -> String {
+        let (min, max) = self.bounding_box();
+        let manifold = crate::checks::run(crate::checks::Check::Manifold, self, &[]).is_ok();
+        format!(
+            "{}: {:.0}×{:.0}×{:.0} mm, {} tris, {:.1} cm³, {}",
+            self.name,
+            max[0] - min[0],
+            max[1] - min[1],
+            max[2] - min[2],
+            thousands(self.num_triangles()),
+            self.volume() / 1000.0,
+            if manifold { "manifold" } else { "not manifold" }
+        )
+    }
+
+    fn write_json(&self, path: &Path) -> Result<(), String> {
+        let mesh = self.to_mesh();
+        let props = (mesh.num_props() as usize).max(3);
+        let json = JsonPart {
+            name: self.name.clone(),
+            vertices: mesh.vertices().chunks(props).map(|v| [v[0], v[1], v[2]]).collect(),
+            triangles: mesh.indices().chunks(3).map(|t| [t[0], t[1], t[2]]).collect(),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let text = serde_json::to_string(&json).map_err(|e| format!("Failed to serialize {}: {}", self.name, e))?;
+        std::fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    fn read_json(path: &Path) -> Result<Part, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let json: JsonPart = serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        if json.triangles.iter().flatten().any(|&i| i as usize >= json.vertices.len()) {
+            return Err(format!("{}: triangle refers to a missing vertex", path.display()));
+        }
+        let vertices: Vec<f32> = json.vertices.iter().flatten().copied().collect();
+        let indices: Vec<u32> = json.triangles.iter().flatten().copied().collect();
+        let part = Part::new(json.name, manifold_rs::Manifold::from_mesh(manifold_rs::Mesh::new(&vertices, &indices)));
+        if part.num_triangles() == 0 && !json.triangles.is_empty() {
+            return Err(format!("{}: mesh is not a closed solid", path.display()));
+        }
+        Ok(part)
+    }
+}
+
+/// `n` with a comma between each group of three digits.
+fn thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+/// Segment count for every circle in `--quick` mode.
+pub const QUICK_SEGMENTS: u32 = 16;
+
+thread_local! {
+    static SAVED_TRIANGLES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Segment count for a circular primitive whose full-quality count is `full`.
+///
+/// Quick mode caps it at [`QUICK_SEGMENTS`] and tallies the triangles that
+/// saves (about four per dropped segment: two on the side, one per cap).
+pub fn segments(cfg: &Config, full: u32) -> u32 {
+    if !cfg.quick || full <= QUICK_SEGMENTS {
+        return full;
+    }
+    SAVED_TRIANGLES.with(|saved| saved.set(saved.get() + 4 * (full - QUICK_SEGMENTS) as usize));
+    QUICK_SEGMENTS
+}
+
+/// Estimated triangles saved by quick mode since the last call.
+pub fn take_saved_triangles() -> usize {
+    SAVED_TRIANGLES.with(|saved| saved.replace(0))
+}
+
+/// A Z-aligned frustum `height` tall, centered on the origin like
+/// `centered_cylinder` with the same `segments` around, triangulated
+/// directly: a ring at `bottom_radius` and one at `top_radius`, fanned
+/// into caps and joined by quads. Equal radii give the plain cylinder; a
+/// `top_radius` of 0 closes the side onto a single apex vertex for a true
+/// cone.
+pub fn centered_cone(name: &str, bottom_radius: f64, top_radius: f64, height: f64, segments: u32) -> Part {
+    let n = segments.max(3) as usize;
+    let ring = |r: f64, z: f64| {
+        (0..n).flat_map(move |i| {
+            let angle = std::f64::consts::TAU * i as f64 / n as f64;
+            [(r * angle.cos()) as f32, (r * angle.sin()) as f32, z as f32]
+        })
+    };
+    let apex = top_radius <= 0.0;
+    let mut vertices: Vec<f32> = ring(bottom_radius, -height / 2.0).collect();
+    if apex {
+        vertices.extend([0.0, 0.0, (height / 2.0) as f32]);
+    } else {
+        vertices.extend(ring(top_radius, height / 2.0));
+    }
+
+    let mut indices = Vec::new();
+    // Bottom cap faces -Z, so its fan is wound against the ring.
+    for i in 1..n - 1 {
+        indices.extend([0, i + 1, i]);
+    }
+    for i in 0..n {
+        let j = (i + 1) % n;
+        if apex {
+            indices.extend([i, j, n]);
+        } else {
+            indices.extend([i, j, j + n, i, j + n, i + n]);
+        }
+    }
+    if !apex {
+        for i in 1..n - 1 {
+            indices.extend([n, n + i, n + i + 1]);
+        }
+    }
+    let indices: Vec<u32> = indices.into_iter().map(|i| i as u32).collect();
+    Part::new(name, manifold_rs::Manifold::from_mesh(manifold_rs::Mesh::new(&vertices, &indices)))
+}
+
+/// Radius `rise` above the base of a post that starts at `radius` and
+/// narrows with `draft_deg` degrees of draft.
+pub fn drafted_radius(radius: f64, rise: f64, draft_deg: f64) -> f64 {
+    radius - rise * draft_deg.to_radians().tan()
+}
+
+/// Chamfer the top circular edge of a Z-aligned cylindrical feature.
+///
+/// The feature has outer `radius` and its top face sits at `top_z`; it is
+/// assumed to be centered on the Z axis. A 45° chamfer of `size` is cut by
+/// subtracting everything outside a cone that runs from `radius` at
+/// `top_z - size` to `radius - size` at `top_z`. Holes and other features
+/// subtracted afterwards are unaffected.
+pub fn bevel_top_edge(part: &Part, radius: f64, top_z: f64, size: f64, segments: u32) -> Part {
+    if size <= 0.0 {
+        return part.translate(0.0, 0.0, 0.0);
+    }
+
+    // Extend the cone past both ends of the chamfer so the cut faces don't
+    // coincide with the existing top and side faces.
+    let height = through_length(size);
+    let keep = centered_cone("bevel_keep", radius + HOLE_OVERCUT, radius - size - HOLE_OVERCUT, height, segments)
+        .translate(0.0, 0.0, top_z - size - HOLE_OVERCUT + height / 2.0);
+    let ring = Part::cylinder("bevel_ring", radius + 2.0 * HOLE_OVERCUT, height, segments)
+        .translate(0.0, 0.0, top_z - size - HOLE_OVERCUT);
+
+    part - &(ring - keep)
+}
+
+/// Extrude a simple 2D polygon along Z into a prism `height` tall,
+/// centered on Z=0 like `centered_cube`.
+///
+/// The outline may be given in either winding; it is ear-clipped into the
+/// caps and each edge becomes a quad of side wall. Panics if the outline
+/// is not a simple polygon, since the result would not be a closed solid.
+pub fn extrude(name: &str, points: &[(f64, f64)], height: f64) -> Part {
+    let area2: f64 = (0..points.len())
+        .map(|i| {
+            let ((x0, y0), (x1, y1)) = (points[i], points[(i + 1) % points.len()]);
+            x0 * y1 - x1 * y0
+        })
+        .sum();
+    let mut outline = points.to_vec();
+    if area2 < 0.0 {
+        outline.reverse();
+    }
+    let caps = mesh::ear_clip(&outline).unwrap_or_else(|| panic!("extrude: '{}' outline is not a simple polygon", name));
+
+    let n = outline.len();
+    let vertices: Vec<f32> = [-height / 2.0, height / 2.0]
+        .iter()
+        .flat_map(|&z| outline.iter().flat_map(move |&(x, y)| [x as f32, y as f32, z as f32]))
+        .collect();
+    let mut indices = Vec::with_capacity(3 * (2 * caps.len() + 2 * n));
+    for [a, b, c] in caps {
+        // Bottom cap faces -Z, so it is wound against the outline.
+        indices.extend([a, c, b].map(|i| i as u32));
+        indices.extend([a + n, b + n, c + n].map(|i| i as u32));
+    }
+    for i in 0..n {
+        let j = (i + 1) % n;
+        indices.extend([i, j, j + n, i, j + n, i + n].map(|i| i as u32));
+    }
+    Part::new(name, manifold_rs::Manifold::from_mesh(manifold_rs::Mesh::new(&vertices, &indices)))
+}
+
+/// XY centers of the copies `PartExt::polar_pattern` places.
+pub fn polar_positions(count: usize, radius: f64, start_angle_deg: f64) -> Vec<(f64, f64)> {
+    (0..count)
+        .map(|i| {
+            let angle = (start_angle_deg + 360.0 * i as f64 / count as f64).to_radians();
+            (radius * angle.cos(), radius * angle.sin())
+        })
+        .collect()
+}
+
+/// Points along a circular arc from angle `from` to `to` (radians, either
+/// direction), both ends included, with `segments` facets per full turn.
+pub fn arc_points(center: (f64, f64), radius: f64, from: f64, to: f64, segments: u32) -> Vec<(f64, f64)> {
+    let steps = ((to - from).abs() / std::f64::consts::TAU * segments as f64).ceil().max(1.0) as usize;
+    (0..=steps)
+        .map(|k| {
+            let angle = from + (to - from) * k as f64 / steps as f64;
+            (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+        })
+        .collect()
+}
+
+/// Block `width` × `depth` whose top slopes from `height_rear` at its rear
+/// (-Y) face down to `height_front` at its front (+Y) face: a box with an
+/// angled box cut off its top. Centered like `centered_cube` of the rear
+/// height, so the bottom sits at `-height_rear / 2`. A front no lower than
+/// the rear leaves the plain box.
+pub fn wedge(name: &str, width: f64, depth: f64, height_rear: f64, height_front: f64) -> Part {
+    let block = centered_cube(name, width, depth, height_rear);
+    if height_front >= height_rear {
+        return block;
+    }
+    let slope = ((height_rear - height_front) / depth).atan().to_degrees();
+    let cut = slope_cutter("wedge_cut", through_length(width), depth + height_rear, depth / 2.0, height_front - height_rear / 2.0, slope);
+    (block - cut).with_name(name)
+}
+
+/// Cutter for everything above the plane through `(y, z)` that rises
+/// `slope_deg` towards -Y, `width` wide centered on X=0 and big enough to
+/// clear a part about `reach` across in Y and Z.
+pub fn slope_cutter(name: &str, width: f64, reach: f64, y: f64, z: f64, slope_deg: f64) -> Part {
+    let size = 4.0 * reach;
+    centered_cube(name, width, size, size).translate(0.0, 0.0, size / 2.0).rotate(-slope_deg, 0.0, 0.0).translate(0.0, y, z)
+}
+
+/// Clearance hole `diameter` wide through material `depth` thick, centered
+/// on the origin like `centered_cylinder`, countersunk on its top (+Z) face
+/// to `head_diameter` for a flat head screw with a `cone_angle` included
+/// angle. The shaft runs `HOLE_OVERCUT` past both faces.
+pub fn countersunk_hole(name: &str, diameter: f64, depth: f64, head_diameter: f64, cone_angle: f64, segments: u32) -> Part {
+    let shaft = centered_cylinder(name, diameter / 2.0, through_length(depth), segments);
+    if head_diameter <= diameter {
+        return shaft;
+    }
+    let sink = countersink_depth(diameter, head_diameter, cone_angle);
+    // Run the cone on past the top face so its base doesn't sit on it.
+    let flare = HOLE_OVERCUT * (cone_angle / 2.0).to_radians().tan();
+    let height = sink + HOLE_OVERCUT;
+    let cone = centered_cone(name, diameter / 2.0, head_diameter / 2.0 + flare, height, segments).translate(0.0, 0.0, depth / 2.0 - sink + height / 2.0);
+    (shaft + cone).with_name(name)
+}
+
+/// How deep a `cone_angle` countersink opening to `head_diameter` cuts
+/// before it narrows to the `diameter` shaft.
+pub fn countersink_depth(diameter: f64, head_diameter: f64, cone_angle: f64) -> f64 {
+    ((head_diameter - diameter) / 2.0).max(0.0) / (cone_angle / 2.0).to_radians().tan()
+}
+
+/// Clearance hole for the configured fastener through material `thickness`
+/// thick, centered like `centered_cylinder`; countersunk on its top face
+/// when `countersink_mount_holes` is set.
+pub fn mount_hole(cfg: &Config, name: &str, thickness: f64) -> Part {
+    let fastener = cfg.fastener();
+    let segments = segments(cfg, cfg.segments_hole);
+    if cfg.countersink_mount_holes {
+        countersunk_hole(name, fastener.clearance, thickness, fastener.countersunk_head, crate::fasteners::COUNTERSINK_ANGLE, segments)
+    } else {
+        centered_cylinder(name, fastener.clearance / 2.0, through_length(thickness), segments)
+    }
+}
+
+/// Obround (stadium) prism, `length` end to end along X and `width` across,
+/// centered on the origin like `centered_cube`: a box between two
+/// half-round ends. A slot no longer than it is wide is a plain cylinder.
+pub fn obround(name: &str, length: f64, width: f64, height: f64, segments: u32) -> Part {
+    let r = width / 2.0;
+    let end = centered_cylinder(name, r, height, segments);
+    let straight = length - width;
+    if straight <= 0.0 {
+        return end;
+    }
+    (end.translate(-straight / 2.0, 0.0, 0.0) + end.translate(straight / 2.0, 0.0, 0.0) + centered_cube(name, straight, width, height)).with_name(name)
+}
+
+/// Turn `part` by `angle_deg` counterclockwise (right-hand rule) about
+/// `axis` through the origin.
+///
+/// vcad's `rotate` applies X, Y and Z Euler angles in a fixed order, which
+/// makes a turn about anything but a principal axis awkward to write. This
+/// builds the rotation by Rodrigues' formula and hands vcad the equivalent
+/// Euler angles. A zero axis leaves the part as is.
+pub fn rotate_axis(part: &Part, axis: (f64, f64, f64), angle_deg: f64) -> Part {
+    let Some(axis) = nalgebra::Unit::try_new(nalgebra::Vector3::new(axis.0, axis.1, axis.2), 1e-12) else {
+        return part.translate(0.0, 0.0, 0.0);
+    };
+    // `euler_angles` decomposes as Rz * Ry * Rx: X first, then Y, then Z,
+    // the order vcad applies them in.
+    let (rx, ry, rz) = nalgebra::Rotation3::from_axis_angle(&axis, angle_deg.to_radians()).euler_angles();
+    part.rotate(rx.to_degrees(), ry.to_degrees(), rz.to_degrees())
+}
+
+/// Standoff boss: a solid cylinder with a through hole down its axis.
+///
+/// Centered on the origin like `centered_cylinder`, ready to be translated
+/// and unioned onto a base. The hole is cut before the union, so it must
+/// also be cut through whatever the boss is mounted on.
+pub fn standoff(name: &str, outer_d: f64, hole_d: f64, height: f64, segments: u32) -> Part {
+    let boss = centered_cylinder(name, outer_d / 2.0, height, segments);
+    let hole = centered_cylinder("standoff_hole", hole_d / 2.0, through_length(height), segments);
+    boss - hole
+}
+
+/// Solid left under the bore of an insert boss (mm), for the plastic the
+/// insert displaces as it is pressed in.
+pub const INSERT_FLOOR: f64 = 1.0;
+
+/// Cutter for a heat-set insert bore `id` wide and `depth` deep below Z=0,
+/// the face the insert is pressed in from, running on past that face.
+pub fn insert_bore(name: &str, id: f64, depth: f64, segments: u32) -> Part {
+    let length = depth + HOLE_OVERCUT;
+    centered_cylinder(name, id / 2.0, length, segments).translate(0.0, 0.0, -depth + length / 2.0)
+}
+
+/// Boss for a heat-set insert: a cylinder `od` across and `floor` taller
+/// than the insert, centered on the origin like `centered_cylinder`, with
+/// the `id` × `depth` insert bore cut down from its top.
+pub fn insert_boss(name: &str, od: f64, id: f64, depth: f64, floor: f64, segments: u32) -> Part {
+    let height = depth + floor;
+    let boss = centered_cylinder(name, od / 2.0, height, segments);
+    (boss - insert_bore("insert_bore", id, depth, segments).translate(0.0, 0.0, height / 2.0)).with_name(name)
+}
+
+/// Outer diameter of a boss around the configured heat-set insert.
+pub fn insert_boss_od(cfg: &Config) -> f64 {
+    cfg.insert_od + 2.0 * cfg.wall_thickness
+}
+
+/// Height of a boss for the configured insert, `INSERT_FLOOR` under it.
+pub fn insert_boss_height(cfg: &Config) -> f64 {
+    cfg.insert_depth + cfg.mm(INSERT_FLOOR)
+}
+
+/// `part` with a boss for the configured heat-set insert standing on the
+/// plane `base_z` at `(x, y)`. The boss is unioned on, then the insert bore
+/// cut again from its top so it also clears whatever of `part` it lands in.
+pub fn add_insert_boss(cfg: &Config, part: &Part, x: f64, y: f64, base_z: f64) -> Part {
+    let segments = segments(cfg, cfg.segments_hole);
+    let height = insert_boss_height(cfg);
+    let top = base_z + height;
+    let boss = insert_boss("insert_boss", insert_boss_od(cfg), cfg.insert_od, cfg.insert_depth, cfg.mm(INSERT_FLOOR), segments).translate(x, y, base_z + height / 2.0);
+    let bore = insert_bore("insert_bore", cfg.insert_od, cfg.insert_depth, segments).translate(x, y, top);
+    ((part + &boss) - bore).with_name(&part.name)
+}
+
+/// Cutter for a uniform `clearance` gap where `a` and `b` overlap: the
+/// overlap itself, grown by `clearance` on every side.
+///
+/// Model mating parts at their nominal, interfering size (a peg plunged into
+/// a solid block) and subtract the result from the host to leave the peg a
+/// pocket with the gap all round. The overlap is grown as the hull of copies
+/// offset to the vertices of a `segments`-sided sphere, so each separate
+/// piece of it must be convex (pegs, pins, tabs); a concave piece would have
+/// its recesses filled.
+pub fn gap_between(a: &Part, b: &Part, clearance: f64, segments: u32) -> Part {
+    let offsets = mesh::TriMesh::from_part(&Part::sphere("clearance", clearance, segments)).vertices;
+    let mut cutter = Part::empty("gap");
+    for piece in mesh::TriMesh::from_part(&(a & b)).components() {
+        let (mut vertices, mut indices) = (Vec::new(), Vec::new());
+        for offset in &offsets {
+            let base = (vertices.len() / 3) as u32;
+            vertices.extend(piece.vertices.iter().flat_map(|v| [(v.x + offset.x) as f32, (v.y + offset.y) as f32, (v.z + offset.z) as f32]));
+            indices.extend(piece.triangles.iter().flatten().map(|&i| base + i as u32));
+        }
+        let copies = manifold_rs::Manifold::from_mesh(manifold_rs::Mesh::new(&vertices, &indices));
+        cutter = cutter + Part::new("gap", copies.hull());
+    }
+    cutter
+}
+
+/// Regions of a face that generated cutters (pockets, vents) must avoid.
+///
+/// Mounting features register a keep-out as they are placed; generated
+/// cutters then skip any cell that would intersect one, so enabling them
+/// never eats into a screw hole.
+#[derive(Debug, Default)]
+pub struct KeepOuts {
+    /// `(x, y, radius)`
+    circles: Vec<(f64, f64, f64)>,
+    /// `(min_x, min_y, max_x, max_y)`
+    rects: Vec<(f64, f64, f64, f64)>,
+}
+
+impl KeepOuts {
+    /// Keep clear of a circle, e.g. a hole's radius plus a margin.
+    pub fn add_circle(&mut self, x: f64, y: f64, radius: f64) {
+        self.circles.push((x, y, radius));
+    }
+
+    /// Keep clear of an axis-aligned rectangle centered at `(x, y)`.
+    pub fn add_rect(&mut self, x: f64, y: f64, width: f64, depth: f64) {
+        self.rects.push((x - width / 2.0, y - depth / 2.0, x + width / 2.0, y + depth / 2.0));
+    }
+
+    /// Whether an axis-aligned rectangle centered at `(x, y)` touches any keep-out.
+    pub fn blocks_rect(&self, x: f64, y: f64, width: f64, depth: f64) -> bool {
+        let (min_x, min_y) = (x - width / 2.0, y - depth / 2.0);
+        let (max_x, max_y) = (x + width / 2.0, y + depth / 2.0);
+        let hits_circle = self.circles.iter().any(|&(cx, cy, r)| {
+            let dx = cx - cx.clamp(min_x, max_x);
+            let dy = cy - cy.clamp(min_y, max_y);
+            dx * dx + dy * dy < r * r
+        });
+        let hits_rect = self
+            .rects
+            .iter()
+            .any(|&(x0, y0, x1, y1)| min_x < x1 && max_x > x0 && min_y < y1 && max_y > y0);
+        hits_circle || hits_rect
+    }
+}
+
+/// Grid of square pocket cutters filling a `region_x` × `region_y` area
+/// centered on the origin, skipping any cell that touches a keep-out.
+///
+/// Cutters are `height` tall and centered on Z=0; the caller positions them.
+pub fn pocket_grid(name: &str, keep_outs: &KeepOuts, region_x: f64, region_y: f64, size: f64, pitch: f64, height: f64) -> Part {
+    let count = |extent: f64| {
+        if extent < size || pitch <= 0.0 {
+            0
+        } else {
+            ((extent - size) / pitch).floor() as usize + 1
+        }
+    };
+    let (nx, ny) = (count(region_x), count(region_y));
+    let start_x = -(nx.saturating_sub(1) as f64) * pitch / 2.0;
+    let start_y = -(ny.saturating_sub(1) as f64) * pitch / 2.0;
+
+    let cell = centered_cube(name, size, size, height);
+    let mut pockets = Part::empty(name);
+    for i in 0..nx {
+        for j in 0..ny {
+            let (x, y) = (start_x + i as f64 * pitch, start_y + j as f64 * pitch);
+            if !keep_outs.blocks_rect(x, y, size, size) {
+                pockets = pockets + cell.translate(x, y, 0.0);
+            }
+        }
+    }
+    pockets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chamfer_edge_keeps_the_box_and_cuts_a_45_degree_prism() {
+        let block = centered_cube("block", 10.0, 8.0, 6.0).translate(3.0, -2.0, 1.0);
+        let size = 1.5;
+        for edge in CubeEdge::ALL {
+            let chamfered = block.chamfer_edge(edge, size);
+            let ((min, max), (bmin, bmax)) = (chamfered.bounding_box(), block.bounding_box());
+            assert!(min.iter().chain(&max).zip(bmin.iter().chain(&bmax)).all(|(a, b)| (a - b).abs() < 1e-9), "{edge:?} changed the bounding box");
+            let length = [10.0, 8.0, 6.0][edge.sides().iter().position(|s| *s == 0.0).unwrap()];
+            let removed = block.volume() - chamfered.volume();
+            assert!((removed - size * size / 2.0 * length).abs() < 1e-6, "{edge:?} removed {removed}mm³");
+        }
+        assert_eq!(block.chamfer_edge(CubeEdge::TopFront, 0.0).volume(), block.volume());
+    }
+
+    #[test]
+    fn obround_flat_sides_are_parallel_and_width_apart() {
+        let (length, width) = (10.0, 3.4);
+        let mesh = mesh::TriMesh::from_part(&obround("slot", length, width, 2.0, 32));
+        let mut sides = Vec::new();
+        for tri in 0..mesh.triangles.len() {
+            let n = mesh.normal(tri);
+            if n.y.abs() > 1.0 - 1e-9 {
+                let ys: Vec<f64> = mesh.corners(tri).iter().map(|p| p.y).collect();
+                assert!(ys.iter().all(|y| (y - ys[0]).abs() < 1e-9), "flat side triangle isn't level in Y");
+                sides.push((n.y.signum(), ys[0]));
+            }
+        }
+        let plane = |sign: f64| sides.iter().filter(|(s, _)| *s == sign).map(|(_, y)| *y).collect::<Vec<_>>();
+        let (low, high) = (plane(-1.0), plane(1.0));
+        assert!(!low.is_empty() && !high.is_empty(), "both flat sides");
+        assert!(low.iter().chain(&high).all(|y| (y.abs() - width / 2.0).abs() < 1e-6));
+        assert!((high[0] - low[0] - width).abs() < 1e-6);
+        let (min, max) = mesh.bounds();
+        assert!((max.x - min.x - length).abs() < 1e-6, "overall length end to end");
+    }
+
+    #[test]
+    fn wedge_top_slopes_from_rear_to_front_height() {
+        let (width, depth, rear, front) = (20.0, 15.0, 20.0, 12.0);
+        let part = wedge("wedge", width, depth, rear, front);
+        let (min, max) = part.bounding_box();
+        assert!((max[2] - min[2] - rear).abs() < 1e-6 && (max[1] - min[1] - depth).abs() < 1e-6);
+        // Trapezoid side profile: mean height times the footprint.
+        assert!((part.volume() - width * depth * (rear + front) / 2.0).abs() < 1e-6);
+        let inside = |y: f64, z: f64| crate::analysis::contains_point(&part, nalgebra::Point3::new(0.0, y, z));
+        assert!(inside(depth / 2.0 - 0.01, -rear / 2.0 + front - 0.05));
+        assert!(!inside(depth / 2.0 - 0.01, -rear / 2.0 + front + 0.05), "front face no taller than height_front");
+        assert!(inside(-depth / 2.0 + 0.01, rear / 2.0 - 0.05));
+    }
+
+    #[test]
+    fn countersink_opens_to_the_head_diameter_on_the_top_face() {
+        let (diameter, depth, head) = (3.2, 5.0, 6.0);
+        let hole = countersunk_hole("hole", diameter, depth, head, 90.0, 128);
+        let inside = |r: f64, z: f64| crate::analysis::contains_point(&hole, nalgebra::Point3::new(r, 0.0, z));
+        let top = depth / 2.0 - 1e-3;
+        assert!(inside(head / 2.0 - 0.02, top) && !inside(head / 2.0 + 0.02, top), "top opening is head_diameter across");
+        // Plain shaft below the 90° cone, which is half the flare deep.
+        let below = depth / 2.0 - countersink_depth(diameter, head, 90.0) - 0.05;
+        assert!((countersink_depth(diameter, head, 90.0) - (head - diameter) / 2.0).abs() < 1e-9);
+        assert!(inside(diameter / 2.0 - 0.02, below) && !inside(diameter / 2.0 + 0.02, below));
+        let (min, max) = hole.bounding_box();
+        assert!((min[2] + depth / 2.0 + HOLE_OVERCUT).abs() < 1e-9 && max[2] > depth / 2.0);
+    }
+
+    #[test]
+    fn pocket_grid_skips_cells_touching_a_keep_out() {
+        let mut keep_outs = KeepOuts::default();
+        let open = pocket_grid("pocket", &keep_outs, 50.0, 10.0, 10.0, 20.0, 2.0);
+        keep_outs.add_circle(0.0, 0.0, 2.0);
+        let masked = pocket_grid("pocket", &keep_outs, 50.0, 10.0, 10.0, 20.0, 2.0);
+        // Three cells at x = -20, 0, 20; the keep-out removes the middle one.
+        assert!((open.volume() - 3.0 * 200.0).abs() < 1e-6);
+        assert!((masked.volume() - 2.0 * 200.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn exit_chamfer_widens_only_the_bottom_face() {
+        let plate = centered_cube("plate", 20.0, 20.0, 4.0);
+        let hole = centered_cylinder("hole", 1.5, through_length(4.0), 64);
+        let part = &plate - &hole.chamfer_hole_exit(1.0, 64);
+        let mesh = mesh::TriMesh::from_part(&part);
+        let inside = |x: f64, z: f64| crate::analysis::mesh_contains_point(&mesh, nalgebra::Point3::new(x, 0.0, z));
+        assert!(!inside(2.3, -1.95), "chamfer opens the bottom rim out to 2.5mm");
+        assert!(inside(2.3, 1.95) && inside(1.7, -0.5), "top rim and bore above the chamfer untouched");
+        let removed = plate.volume() - part.volume();
+        let cone = std::f64::consts::PI / 3.0 * (2.5f64.powi(2) + 2.5 * 1.5 + 1.5f64.powi(2)) - std::f64::consts::PI * 1.5f64.powi(2);
+        assert!((removed - (std::f64::consts::PI * 1.5f64.powi(2) * 4.0 + cone)).abs() < 0.1, "removed {removed}");
+        assert_eq!(hole.chamfer_hole_exit(0.0, 64).volume(), hole.volume());
+    }
+
+    #[test]
+    fn stack_on_sets_the_gap_and_keeps_the_axis() {
+        let base = centered_cube("base", 10.0, 10.0, 4.0).translate(3.0, 0.0, -1.0);
+        let post = centered_cylinder("post", 1.0, 6.0, 32).translate(3.0, 0.0, 20.0);
+        let (min, max) = post.stack_on(&base, 0.5).bounding_box();
+        assert!((min[2] - 1.5).abs() < 1e-6 && (max[2] - 7.5).abs() < 1e-6);
+        assert!((min[0] - 2.0).abs() < 1e-6, "X is unchanged");
+    }
+
+    #[test]
+    fn lay_flat_puts_the_largest_face_on_the_bed() {
+        // A plate standing on its long edge, on a foot that sticks out past
+        // its +Y side, so only the -Y side can lie on the bed.
+        let plate = centered_cube("plate", 40.0, 3.0, 20.0) + centered_cube("foot", 6.0, 5.0, 2.0).translate(0.0, 1.0, -11.0);
+        let (normal, area) = plate.largest_flat_face();
+        assert!((normal[1] + 1.0).abs() < 1e-9, "the flush broad side, got {normal:?}");
+        assert!((area - 812.0).abs() < 1e-6, "plate side and the flush foot side");
+        let (min, max) = plate.lay_flat(normal).bounding_box();
+        assert!(min[2].abs() < 1e-5 && (max[2] - 5.0).abs() < 1e-5, "lying down, {min:?}..{max:?}");
+
+        let block = centered_cube("block", 10.0, 10.0, 10.0);
+        assert_eq!(block.largest_flat_face().0, [0.0, 0.0, -1.0], "a tie keeps the bottom face down");
+    }
+
+    #[test]
+    fn bed_offset_drops_the_part_centered_onto_the_bed() {
+        let part = centered_cube("block", 10.0, 6.0, 4.0).translate(7.0, -2.0, 5.0);
+        let [x, y, z] = part.bed_offset();
+        assert_eq!([x, y, z], [-7.0, 2.0, -3.0]);
+        let (min, max) = part.translate(x, y, z).bounding_box();
+        assert!(min[2].abs() < 1e-9 && (min[0] + max[0]).abs() < 1e-9 && (min[1] + max[1]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn describe_sums_up_size_triangles_volume_and_closure() {
+        let block = centered_cube("block", 48.0, 36.0, 23.0);
+        assert_eq!(block.describe(), "block: 48×36×23 mm, 12 tris, 39.7 cm³, manifold");
+        assert_eq!(thousands(1284), "1,284");
+        assert_eq!(thousands(1234567), "1,234,567");
+        assert_eq!(thousands(999), "999");
+    }
+
+    #[test]
+    fn offset_surface_moves_every_face_by_the_amount() {
+        let grown = centered_cube("block", 10.0, 6.0, 4.0).offset_surface(0.25);
+        assert!((grown.volume() - 10.5 * 6.5 * 4.5).abs() < 1e-3, "volume {}", grown.volume());
+        let (min, max) = grown.bounding_box();
+        assert!((min[0] + 5.25).abs() < 1e-5 && (max[2] - 2.25).abs() < 1e-5);
+
+        // A shrunk hole cutter leaves the bore 0.05 smaller in radius.
+        let hole = centered_cylinder("hole", 4.0, 6.0, 64);
+        let (_, max) = hole.offset_surface(-0.05).bounding_box();
+        // Each facet moves 0.05, so a corner moves further along its bisector.
+        let corner = 4.0 - 0.05 / (std::f64::consts::PI / 64.0).cos();
+        assert!((max[0] - corner).abs() < 1e-5, "corner at {}, expected {}", max[0], corner);
+        assert!((max[2] - 2.95).abs() < 1e-5, "caps move too");
+    }
+
+    #[test]
+    fn json_round_trip_keeps_the_exact_mesh() {
+        let part = (centered_cube("block", 10.0, 7.3, 4.1) - centered_cylinder("hole", 1.7, 6.0, 48).translate(0.3, 0.1, 0.0)).with_name("block");
+        let path = std::env::temp_dir().join(format!("vialbel-json-{}.json", std::process::id()));
+        part.write_json(&path).unwrap();
+        let loaded = Part::read_json(&path).unwrap();
+        let sorted = |p: &Part| {
+            let mut v: Vec<[u32; 3]> = p.to_mesh().vertices().chunks(3).map(|v| [v[0].to_bits(), v[1].to_bits(), v[2].to_bits()]).collect();
+            v.sort();
+            v
+        };
+        assert_eq!(loaded.name, "block");
+        assert_eq!(loaded.num_triangles(), part.num_triangles());
+        assert_eq!(sorted(&loaded), sorted(&part), "vertices come back bit for bit");
+
+        std::fs::write(&path, r#"{"name":"bad","vertices":[[0,0,0]],"triangles":[[0,1,2]]}"#).unwrap();
+        assert!(Part::read_json(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn intersection_of_overlapping_cubes_keeps_shared_volume() {
+        let a = centered_cube("a", 10.0, 10.0, 10.0);
+        let b = centered_cube("b", 10.0, 10.0, 10.0).translate(5.0, 4.0, 0.0);
+        // Overlap is 5 × 6 × 10.
+        assert!(((&a & &b).volume() - 300.0).abs() < 1e-6);
+        assert!((a.intersection(&b).volume() - 300.0).abs() < 1e-6);
+        let (min, max) = (a & b).bounding_box();
+        assert!((min[0] - 0.0).abs() < 1e-6 && (max[0] - 5.0).abs() < 1e-6);
+        assert!((min[1] + 1.0).abs() < 1e-6 && (max[1] - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn gap_between_leaves_a_uniform_gap_around_a_peg() {
+        let block = centered_cube("block", 20.0, 20.0, 10.0);
+        // Peg plunged 6mm into the block from above.
+        let peg = centered_cylinder("peg", 3.0, 10.0, 32).translate(0.0, 0.0, 6.0);
+        let clearance = 0.3;
+        let socket = &block - &gap_between(&peg, &block, clearance, 32);
+        let (socket, peg) = (crate::mesh::TriMesh::from_part(&socket), crate::mesh::TriMesh::from_part(&peg));
+        for k in 0..8 {
+            let angle = (k as f64 * 45.0 + 5.0).to_radians();
+            let dir = nalgebra::Vector3::new(angle.cos(), angle.sin(), 0.0);
+            let from = nalgebra::Point3::new(0.0, 0.0, 3.0);
+            let gap = crate::analysis::ray_distance(&socket, from, dir).unwrap() - crate::analysis::ray_distance(&peg, from, dir).unwrap();
+            assert!((gap - clearance).abs() < 0.02, "side gap {:.3} at {}°", gap, k * 45 + 5);
+        }
+        // Floor of the socket sits `clearance` below the peg's tip at z=1.
+        let floor = crate::analysis::ray_distance(&socket, nalgebra::Point3::new(0.0, 0.0, 3.0), -nalgebra::Vector3::z()).unwrap();
+        assert!((floor - (2.0 + clearance)).abs() < 0.02, "floor {:.3}", floor);
+        let apart = centered_cylinder("peg", 3.0, 10.0, 32).translate(30.0, 0.0, 0.0);
+        assert!(gap_between(&apart, &block, clearance, 32).is_empty(), "no overlap, no cutter");
+    }
+
+    #[test]
+    fn centered_grid_is_symmetric_about_the_origin() {
+        let pin = centered_cylinder("pin", 1.0, 2.0, 16);
+        let grid = pin.grid_pattern(2, 2, 10.0, 6.0, true);
+        let (min, max) = grid.bounding_box();
+        for k in 0..3 {
+            assert!((min[k] + max[k]).abs() < 1e-6, "axis {} not centered: {:?} {:?}", k, min, max);
+        }
+        assert!((max[0] - 6.0).abs() < 1e-6 && (max[1] - 4.0).abs() < 1e-6);
+        assert!((grid.volume() / pin.volume() - 4.0).abs() < 1e-4);
+        for (x, y) in [(-5.0, -3.0), (5.0, -3.0), (-5.0, 3.0), (5.0, 3.0)] {
+            assert!(crate::analysis::contains_point(&grid, nalgebra::Point3::new(x, y, 0.0)));
+        }
+        let (min, _) = pin.grid_pattern(3, 1, 4.0, 0.0, false).bounding_box();
+        assert!((min[0] + 1.0).abs() < 1e-6, "uncentered grid starts at the part");
+    }
+
+    #[test]
+    fn mirror_copy_keeps_the_original_and_adds_its_reflection() {
+        let hole = centered_cylinder("hole", 1.0, 4.0, 32).translate(5.0, 2.0, 0.0);
+        let pair = hole.mirror_copy(1.0, 0.0, 0.0);
+        assert!((pair.volume() / hole.volume() - 2.0).abs() < 1e-4);
+        let (min, max) = pair.bounding_box();
+        assert!((min[0] + 6.0).abs() < 1e-5 && (max[0] - 6.0).abs() < 1e-5);
+        // Across the diagonal plane x = y, (5, 2) reflects to (2, 5).
+        let diagonal = hole.mirror_copy(1.0, -1.0, 0.0);
+        for (x, y) in [(5.0, 2.0), (2.0, 5.0)] {
+            assert!(crate::analysis::contains_point(&diagonal, nalgebra::Point3::new(x, y, 0.0)));
+        }
+        assert!((diagonal.volume() / hole.volume() - 2.0).abs() < 1e-4, "reflection faces outward");
+    }
+
+    #[test]
+    fn clipping_through_the_spindle_keeps_a_closed_slice() {
+        let cfg = crate::config::load_config(None).unwrap();
+        let holder = crate::spool_holder::build(&cfg);
+        let (min, max) = holder.bounding_box();
+        // A band across the middle of the spindle, clear of the flange.
+        let (z0, z1) = (max[2] - 10.0, max[2] - 4.0);
+        let clipped = holder.clip_to_box([min[0] - 1.0, min[1] - 1.0, z0], [max[0] + 1.0, max[1] + 1.0, z1]);
+        let (cmin, cmax) = clipped.bounding_box();
+        assert!((cmin[2] - z0).abs() < 1e-4 && (cmax[2] - z1).abs() < 1e-4, "z {}..{}", cmin[2], cmax[2]);
+        assert_eq!(clipped.name, holder.name);
+        let mesh = crate::mesh::TriMesh::from_part(&clipped);
+        assert!(mesh.edge_faces().values().all(|faces| faces.len() == 2), "cut faces are capped");
+        assert!(clipped.volume() > 0.0 && clipped.volume() < holder.volume());
+    }
+
+    #[test]
+    fn centered_cone_is_a_closed_outward_frustum() {
+        let post = centered_cone("post", 3.0, 2.0, 10.0, 256);
+        let mesh = crate::mesh::TriMesh::from_part(&post);
+        assert!(mesh.edge_faces().values().all(|faces| faces.len() == 2), "caps are watertight");
+        // Signed volume is positive only when every face points outward.
+        let frustum = std::f64::consts::PI / 3.0 * 10.0 * (9.0 + 6.0 + 4.0);
+        assert!((post.volume() - frustum).abs() < 0.01 * frustum, "volume {}", post.volume());
+        let (min, max) = post.bounding_box();
+        assert!((min[2] + 5.0).abs() < 1e-6 && (max[2] - 5.0).abs() < 1e-6);
+        assert!((drafted_radius(3.0, 10.0, 45.0) + 7.0).abs() < 1e-9);
+        let (straight, cylinder) = (centered_cone("post", 3.0, 3.0, 10.0, 64).volume(), centered_cylinder("post", 3.0, 10.0, 64).volume());
+        assert!((straight - cylinder).abs() < 1e-9 * cylinder, "{straight} vs {cylinder}");
+    }
+
+    #[test]
+    fn centered_cone_frustum_has_two_full_rings() {
+        let frustum = centered_cone("frustum", 3.0, 2.0, 10.0, 32);
+        let mesh = crate::mesh::TriMesh::from_part(&frustum);
+        assert_eq!(mesh.vertices.len(), 64);
+        assert_eq!(mesh.vertices.len(), crate::mesh::TriMesh::from_part(&centered_cylinder("cylinder", 3.0, 10.0, 32)).vertices.len());
+        assert_eq!(mesh.triangles.len(), 2 * 30 + 2 * 32);
+        assert!(mesh.edge_faces().values().all(|faces| faces.len() == 2), "watertight");
+        // Signed volume is positive only when every face points outward.
+        let exact = std::f64::consts::PI / 3.0 * 10.0 * (9.0 + 6.0 + 4.0);
+        assert!(frustum.volume() > 0.97 * exact && frustum.volume() < exact, "volume {}", frustum.volume());
+        let (min, max) = frustum.bounding_box();
+        assert!((min[2] + 5.0).abs() < 1e-6 && (max[2] - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn centered_cone_with_zero_top_radius_meets_at_one_apex() {
+        let cone = centered_cone("cone", 3.0, 0.0, 10.0, 32);
+        let mesh = crate::mesh::TriMesh::from_part(&cone);
+        assert_eq!(mesh.vertices.len(), 33);
+        assert_eq!(mesh.triangles.len(), 30 + 32);
+        assert!(mesh.edge_faces().values().all(|faces| faces.len() == 2), "watertight");
+        let top: Vec<usize> = (0..mesh.vertices.len()).filter(|&v| (mesh.vertices[v].z - 5.0).abs() < 1e-6).collect();
+        assert_eq!(top.len(), 1, "single apex vertex");
+        assert!(mesh.vertices[top[0]].coords.xy().norm() < 1e-6, "apex on the axis");
+        assert_eq!(mesh.triangles.iter().filter(|t| t.contains(&top[0])).count(), 32, "every side triangle shares the apex");
+        assert!(cone.volume() > 0.0);
+    }
+
+    #[test]
+    fn extruded_l_profile_is_closed_and_outward_facing() {
+        // Clockwise L: 10×10 square with a 5×5 notch, 4 tall.
+        let outline = [(0.0, 0.0), (0.0, 10.0), (5.0, 10.0), (5.0, 5.0), (10.0, 5.0), (10.0, 0.0)];
+        let part = extrude("gusset", &outline, 4.0);
+        let mesh = crate::mesh::TriMesh::from_part(&part);
+        assert!(mesh.edge_faces().values().all(|faces| faces.len() == 2), "watertight");
+        // Signed volume is positive only when every face points outward.
+        assert!((part.volume() - 75.0 * 4.0).abs() < 1e-6);
+        let (min, max) = part.bounding_box();
+        assert!((min[2] + 2.0).abs() < 1e-6 && (max[2] - 2.0).abs() < 1e-6);
+        assert!(!crate::analysis::contains_point(&part, nalgebra::Point3::new(7.5, 7.5, 0.0)));
+    }
+
+    #[test]
+    fn with_name_replaces_the_operand_derived_name() {
+        let part = centered_cube("base", 1.0, 1.0, 1.0) + centered_cube("rib", 1.0, 1.0, 1.0).translate(0.5, 0.0, 0.0);
+        assert_ne!(part.name, "bracket");
+        assert_eq!(part.with_name("bracket").name, "bracket");
+    }
+
+    #[test]
+    fn insert_boss_bore_is_insert_wide_and_blind() {
+        let (od, id, depth) = (9.0, 4.0, 4.0);
+        let boss = insert_boss("boss", od, id, depth, INSERT_FLOOR, 128);
+        let (min, max) = boss.bounding_box();
+        assert!(max[2] - min[2] > depth, "boss taller than the insert");
+        assert!((max[2] - min[2] - depth - INSERT_FLOOR).abs() < 1e-6);
+        let inside = |r: f64, z: f64| crate::analysis::contains_point(&boss, nalgebra::Point3::new(r, 0.0, z));
+        let in_bore = max[2] - depth / 2.0;
+        assert!(!inside(id / 2.0 - 0.02, in_bore) && inside(id / 2.0 + 0.02, in_bore), "bore is insert_od across");
+        assert!(inside(0.0, max[2] - depth - INSERT_FLOOR / 2.0), "floor under the bore");
+    }
+
+    #[test]
+    fn rotate_axis_turns_about_any_axis() {
+        // A quarter turn about +Y carries +X to -Z.
+        let block = centered_cube("block", 2.0, 2.0, 2.0).translate(10.0, 0.0, 0.0);
+        let (min, max) = rotate_axis(&block, (0.0, 1.0, 0.0), 90.0).bounding_box();
+        assert!((min[2] + 11.0).abs() < 1e-5 && (max[2] + 9.0).abs() < 1e-5, "z {}..{}", min[2], max[2]);
+        assert!((min[0] + 1.0).abs() < 1e-5 && (max[0] - 1.0).abs() < 1e-5, "x {}..{}", min[0], max[0]);
+        // A third of a turn about the body diagonal cycles X to Y.
+        let (min, _) = rotate_axis(&block, (1.0, 1.0, 1.0), 120.0).bounding_box();
+        assert!((min[1] - 9.0).abs() < 1e-4, "y from {}", min[1]);
+        assert!((rotate_axis(&block, (0.0, 0.0, 0.0), 45.0).volume() - block.volume()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn standoff_is_bored_through() {
+        let boss = standoff("standoff", 8.0, 3.2, 6.0, 32);
+        assert!(!crate::analysis::contains_point(&boss, nalgebra::Point3::origin()));
+        assert!(crate::analysis::contains_point(&boss, nalgebra::Point3::new(3.0, 0.0, 0.0)));
+        let (min, max) = boss.bounding_box();
+        assert!((max[2] - min[2] - 6.0).abs() < 1e-6);
+    }
+}
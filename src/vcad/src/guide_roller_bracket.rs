@@ -6,28 +6,104 @@
 use vcad::*;
 
 use crate::config::Config;
+use crate::features::{feature, tagged, Role};
+use crate::geometry::{self, PartExt};
 
-pub fn build(cfg: &Config) -> Part {
-    let mount_hole_spacing = 15.0;
+/// Z of the bottom face of the base plate in build coordinates.
+pub fn base_bottom_z(cfg: &Config) -> f64 {
+    -cfg.wall_thickness / 2.0
+}
+
+/// XY centers of the base mounting holes in the bracket's own coordinates:
+/// a row along X, centered on the base. The frame drills the same row.
+pub fn mount_hole_positions(cfg: &Config) -> Vec<(f64, f64)> {
+    let (count, spacing) = (cfg.bracket_mount_hole_count, cfg.bracket_mount_hole_spacing);
+    let start = -(count.saturating_sub(1) as f64) * spacing / 2.0;
+    (0..count).map(|i| (start + i as f64 * spacing, 0.0)).collect()
+}
+
+/// Gap between the top of the bearing and the top of the wall (mm).
+const BEARING_TOP_GAP: f64 = 2.0;
 
+/// Z of the roller pin axis in build coordinates, `BEARING_TOP_GAP` plus the
+/// bearing radius below the top of the wall.
+pub fn pin_hole_z(cfg: &Config) -> f64 {
+    cfg.wall_thickness + height(cfg) - cfg.bearing_od / 2.0 - cfg.mm(BEARING_TOP_GAP)
+}
+
+/// Smallest `bracket_height` that holds the bearing `bracket_bearing_clearance`
+/// clear of the base plate.
+pub fn min_height(cfg: &Config) -> f64 {
+    // The pin axis must sit a bearing radius plus the clearance above the
+    // base plate's top face at wall_thickness / 2.
+    cfg.bearing_od + cfg.mm(BEARING_TOP_GAP) + cfg.bracket_bearing_clearance - cfg.wall_thickness / 2.0
+}
+
+/// Height of the wall above the base plate: `bracket_height`, raised to
+/// `min_height` when `bracket_auto_height` is on.
+pub fn height(cfg: &Config) -> f64 {
+    if cfg.bracket_auto_height {
+        cfg.bracket_height.max(min_height(cfg))
+    } else {
+        cfg.bracket_height
+    }
+}
+
+pub fn build(cfg: &Config) -> Part {
+    let height = height(cfg);
     // Horizontal base plate
-    let base = centered_cube("base", cfg.bracket_base_width, cfg.bracket_base_depth, cfg.wall_thickness);
+    let base = feature(centered_cube("base", cfg.bracket_base_width, cfg.bracket_base_depth, cfg.wall_thickness));
 
     // Vertical wall (L-shape)
-    let wall = centered_cube("wall", cfg.bracket_base_width, cfg.wall_thickness, cfg.bracket_height)
-        .translate(0.0, -cfg.bracket_base_depth / 2.0 + cfg.wall_thickness / 2.0, cfg.wall_thickness / 2.0 + cfg.bracket_height / 2.0);
-
-    // Roller pin hole through vertical wall
-    let hole_z = cfg.wall_thickness + cfg.bracket_height - cfg.bearing_od / 2.0 - 2.0;
-    let pin_hole = centered_cylinder("pin_hole", cfg.pivot_bore / 2.0, cfg.wall_thickness + 2.0, 32)
-        .rotate(90.0, 0.0, 0.0)
-        .translate(0.0, -cfg.bracket_base_depth / 2.0 + cfg.wall_thickness / 2.0, hole_z);
-
-    // Two M3 mounting holes in base
-    let mount_hole = centered_cylinder("mount_hole", cfg.mount_hole_diameter / 2.0, cfg.wall_thickness + 2.0, 32);
-    let mount_holes = mount_hole
-        .linear_pattern(mount_hole_spacing, 0.0, 0.0, 2)
-        .translate(-mount_hole_spacing / 2.0, 0.0, 0.0);
-
-    (base + wall) - pin_hole - mount_holes
+    let wall = feature(centered_cube("wall", cfg.bracket_base_width, cfg.wall_thickness, height)
+        .translate(0.0, -cfg.bracket_base_depth / 2.0 + cfg.wall_thickness / 2.0, cfg.wall_thickness / 2.0 + height / 2.0));
+
+    // Roller pin hole through vertical wall, chamfered where it exits the
+    // outer (-Y) face.
+    let hole_z = pin_hole_z(cfg);
+    let pin_cutter = centered_cylinder("pin_hole", cfg.pivot_bore / 2.0, geometry::through_length(cfg.wall_thickness), geometry::segments(cfg, cfg.segments_hole))
+        .offset_surface(cfg.bracket_pin_hole_offset)
+        .chamfer_hole_exit(cfg.hole_exit_chamfer, geometry::segments(cfg, cfg.segments_hole));
+    let pin_hole = feature(geometry::rotate_axis(&pin_cutter, (1.0, 0.0, 0.0), -90.0)
+        .translate(0.0, -cfg.bracket_base_depth / 2.0 + cfg.wall_thickness / 2.0, hole_z));
+
+    // Row of mounting holes in base
+    let mount_hole = geometry::mount_hole(cfg, "mount_hole", cfg.wall_thickness);
+    let mount_holes = tagged(Role::MountingHole, mount_hole.grid_pattern(cfg.bracket_mount_hole_count, 1, cfg.bracket_mount_hole_spacing, 0.0, true));
+
+    ((base + wall) - pin_hole - mount_holes).with_name("guide_roller_bracket")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::contains_point;
+    use crate::config::load_config;
+    use nalgebra::Point3;
+
+    #[test]
+    fn mount_holes_follow_count_and_stay_centered() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.bracket_mount_hole_count = 3;
+        cfg.bracket_mount_hole_spacing = 8.0;
+        assert_eq!(mount_hole_positions(&cfg), vec![(-8.0, 0.0), (0.0, 0.0), (8.0, 0.0)]);
+        let bracket = build(&cfg);
+        for (x, y) in mount_hole_positions(&cfg) {
+            assert!(!contains_point(&bracket, Point3::new(x, y, 0.0)), "hole at x={}", x);
+        }
+        assert!(contains_point(&bracket, Point3::new(4.0, 0.0, 0.0)), "solid between holes");
+    }
+
+    #[test]
+    fn countersunk_mount_holes_open_wide_on_top_only() {
+        let mut cfg = load_config(None).unwrap();
+        let head_r = cfg.fastener().countersunk_head / 2.0;
+        let (x, _) = mount_hole_positions(&cfg)[0];
+        let (top, bottom) = (cfg.wall_thickness / 2.0 - 0.05, -cfg.wall_thickness / 2.0 + 0.05);
+        let rim = |cfg: &Config, z: f64| contains_point(&build(cfg), Point3::new(x + head_r - 0.2, 0.0, z));
+        assert!(rim(&cfg, top), "plain hole leaves the rim solid");
+        cfg.countersink_mount_holes = true;
+        assert!(!rim(&cfg, top), "countersink opens the rim");
+        assert!(rim(&cfg, bottom), "bottom face keeps the plain bore");
+    }
 }
@@ -0,0 +1,190 @@
+//! Mating-hole alignment between the frame and the parts bolted to it.
+//!
+//! The frame and each mating part compute their hole positions separately,
+//! so a spacing changed in one builder but not the other only shows up at
+//! assembly. Parts are placed with the layout in `models/assembly_manifest.json`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use vcad::Part;
+
+use crate::config::Config;
+use crate::validate::MIN_EDGE_MARGIN;
+use crate::{frame, guide_roller_bracket, spool_holder, vial_cradle};
+
+/// Largest acceptable offset between a part's hole and the frame's (mm).
+pub const HOLE_TOLERANCE: f64 = 0.2;
+
+/// One entry of the assembly manifest.
+#[derive(Deserialize)]
+struct Placement {
+    file: String,
+    position: [f64; 3],
+    rotation: [f64; 3],
+}
+
+/// XYZ offset of each part's bottom center and its Z rotation (degrees),
+/// keyed by component name.
+pub type Layout = HashMap<String, (f64, f64, f64, f64)>;
+
+/// Path of the assembly manifest written by `src/frame.py`.
+pub fn manifest_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../models/assembly_manifest.json")
+}
+
+/// Read part placements from the assembly manifest.
+pub fn load_layout(path: &Path) -> Result<Layout, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let placements: Vec<Placement> =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    Ok(placements
+        .into_iter()
+        .map(|p| {
+            let name = p.file.trim_end_matches(".stl").to_string();
+            (name, (p.position[0], p.position[1], p.position[2], p.rotation[2]))
+        })
+        .collect())
+}
+
+/// Alignment of one part's mounting holes against the frame.
+pub struct Interface {
+    pub component: &'static str,
+    pub holes: usize,
+    /// Largest distance from a part hole to its nearest frame hole.
+    pub worst: f64,
+}
+
+impl Interface {
+    pub fn is_aligned(&self) -> bool {
+        self.worst <= HOLE_TOLERANCE
+    }
+}
+
+/// Check every frame-mounted part's holes against the frame's.
+pub fn check(cfg: &Config, layout: &Layout) -> Result<Vec<Interface>, String> {
+    type Holes = Vec<(f64, f64)>;
+    let mating: [(&'static str, Holes, Holes); 2] = [
+        ("vial_cradle", frame::cradle_hole_positions(cfg).to_vec(), vial_cradle::mount_hole_positions(cfg).to_vec()),
+        ("guide_roller_bracket", frame::guide_hole_positions(cfg), guide_roller_bracket::mount_hole_positions(cfg)),
+    ];
+    mating
+        .into_iter()
+        .map(|(component, frame_holes, part_holes)| {
+            let &(x, y, _, rot) = layout.get(component).ok_or_else(|| format!("{} is missing from the assembly layout", component))?;
+            let placed: Vec<(f64, f64)> = part_holes.iter().map(|&p| place(p, x, y, rot)).collect();
+            Ok(Interface { component, holes: placed.len(), worst: worst_mismatch(&frame_holes, &placed) })
+        })
+        .collect()
+}
+
+/// Reasons the spool holder won't bolt to the frame as laid out.
+///
+/// The holder's flange sits over the frame's spool hole, and the frame drills
+/// no other holes there, so every flange hole must both land on solid frame
+/// and have a frame hole to meet.
+pub fn check_spool(cfg: &Config, layout: &Layout) -> Result<Vec<String>, String> {
+    let &(x, y, _, rot) = layout.get("spool_holder").ok_or("spool_holder is missing from the assembly layout")?;
+    let (sx, sy) = frame::spool_center(cfg);
+    let mut problems = Vec::new();
+
+    let offset = (x - sx).hypot(y - sy);
+    if offset > HOLE_TOLERANCE {
+        problems.push(format!(
+            "layout places the spool holder {:.2}mm from the frame's spool_hole at ({}, {})",
+            offset, sx, sy
+        ));
+    }
+    let flange_r = cfg.spool_flange_diameter / 2.0;
+    if flange_r - (cfg.mm(frame::SPOOL_HOLE_RADIUS) + offset) < MIN_EDGE_MARGIN {
+        problems.push(format!(
+            "spool_flange_diameter ({}mm) overlaps the {}mm frame spool_hole by less than {}mm",
+            cfg.spool_flange_diameter,
+            2.0 * cfg.mm(frame::SPOOL_HOLE_RADIUS),
+            MIN_EDGE_MARGIN
+        ));
+    }
+
+    let frame_holes: [(f64, f64); 0] = [];
+    let clearance_r = cfg.fastener().clearance / 2.0;
+    for local in spool_holder::mount_hole_positions(cfg) {
+        let (hx, hy) = place(local, x, y, rot);
+        if (hx - sx).hypot(hy - sy) < cfg.mm(frame::SPOOL_HOLE_RADIUS) + clearance_r {
+            problems.push(format!(
+                "flange hole at ({:.1}, {:.1}) falls inside the frame's spool_hole; nothing to fasten into",
+                hx, hy
+            ));
+        } else if worst_mismatch(&frame_holes, &[(hx, hy)]) > HOLE_TOLERANCE {
+            problems.push(format!("flange hole at ({:.1}, {:.1}) has no matching frame hole", hx, hy));
+        }
+    }
+    Ok(problems)
+}
+
+/// Move a part from build coordinates to its place in the assembly: bottom
+/// face (at `bottom_z` as built) down to Z=0, turned and moved by the
+/// layout. `explode` pushes it further out along its own position vector,
+/// so parts far from the frame's origin travel further; 0 is assembled.
+pub fn place_part(part: &Part, bottom_z: f64, (x, y, z, rot): (f64, f64, f64, f64), explode: f64) -> Part {
+    let scale = 1.0 + explode;
+    part.translate(0.0, 0.0, -bottom_z).rotate(0.0, 0.0, rot).translate(x * scale, y * scale, z * scale)
+}
+
+/// Rotate a local XY point about Z and translate it into frame coordinates.
+fn place((px, py): (f64, f64), x: f64, y: f64, rot_deg: f64) -> (f64, f64) {
+    let (sin, cos) = rot_deg.to_radians().sin_cos();
+    (x + px * cos - py * sin, y + px * sin + py * cos)
+}
+
+/// Largest distance from any part hole to the nearest frame hole.
+fn worst_mismatch(frame_holes: &[(f64, f64)], part_holes: &[(f64, f64)]) -> f64 {
+    part_holes
+        .iter()
+        .map(|&(px, py)| frame_holes.iter().map(|&(fx, fy)| (px - fx).hypot(py - fy)).fold(f64::INFINITY, f64::min))
+        .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_config;
+
+    #[test]
+    fn default_layout_lines_up() {
+        let cfg = load_config(None).unwrap();
+        let layout = load_layout(&manifest_path()).unwrap();
+        for interface in check(&cfg, &layout).unwrap() {
+            assert!(interface.is_aligned(), "{} off by {:.3}mm", interface.component, interface.worst);
+        }
+    }
+
+    #[test]
+    fn spool_center_hole_over_the_spool_hole_is_reported() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.spool_flange_bolt_circle = false;
+        cfg.spool_bolt_circle_count = 0;
+        let layout = load_layout(&manifest_path()).unwrap();
+        let problems = check_spool(&cfg, &layout).unwrap();
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        assert!(problems[0].contains("falls inside the frame's spool_hole"));
+    }
+
+    #[test]
+    fn exploding_scales_each_part_away_from_the_origin() {
+        let block = vcad::centered_cube("block", 2.0, 2.0, 2.0);
+        let assembled = place_part(&block, -1.0, (10.0, -4.0, 5.0, 90.0), 0.0);
+        let (min, max) = assembled.bounding_box();
+        assert!((min[0] - 9.0).abs() < 1e-6 && (min[2] - 5.0).abs() < 1e-6 && (max[1] + 3.0).abs() < 1e-6);
+        let (min, _) = place_part(&block, -1.0, (10.0, -4.0, 5.0, 90.0), 0.5).bounding_box();
+        assert!((min[0] - 14.0).abs() < 1e-6 && (min[1] + 7.0).abs() < 1e-6 && (min[2] - 7.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn shifted_hole_is_the_worst_mismatch() {
+        let frame_holes = [(0.0, 0.0), (10.0, 0.0)];
+        let part_holes = [(0.05, 0.0), (10.0, 0.5)];
+        assert!((worst_mismatch(&frame_holes, &part_holes) - 0.5).abs() < 1e-9);
+        assert_eq!(place((1.0, 0.0), 5.0, 5.0, 90.0), (5.0, 6.0));
+    }
+}
@@ -4,39 +4,1291 @@
 //! These lack BREP fillets (vcad is mesh-based) but are suitable for
 //! Blender MCP import and rapid prototyping.
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Instant;
+
+use clap::Parser;
+use vcad::{centered_cube, Part};
+
+mod analysis;
+mod assembly;
+mod checks;
 mod config;
 mod dancer_arm;
+mod drawing;
+mod fasteners;
+mod features;
 mod frame;
+mod geometry;
 mod guide_roller_bracket;
+mod interfaces;
+mod mesh;
+mod output;
 mod peel_plate;
+mod print_in_place;
+mod schema;
 mod spool_holder;
+mod validate;
 mod vial_cradle;
+mod watch;
+
+use config::Config;
+use geometry::PartExt;
+
+/// Human-readable progress: stdout normally, stderr under `--json` so that
+/// stdout carries only the JSON summary.
+macro_rules! say {
+    ($cli:expr, $($arg:tt)*) => {
+        if $cli.json {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+#[derive(Parser)]
+#[command(about = "Build the vial label applicator components as STL files")]
+struct Cli {
+    /// Build only these components, e.g. `peel_plate vial_cradle`, like
+    /// --component. See --list for the names
+    #[arg(value_name = "COMPONENT")]
+    names: Vec<String>,
+
+    /// Build only this component (repeatable), regardless of the enable
+    /// flags in [components]
+    #[arg(long, value_name = "NAME")]
+    component: Vec<String>,
+
+    /// Build every component, including those disabled in [components]
+    #[arg(long, conflicts_with_all = ["names", "component"])]
+    all: bool,
+
+    /// Print the name of every component this config can build and exit
+    #[arg(long)]
+    list: bool,
+
+    /// Also write an SVG line drawing of each part's edges in this view
+    #[arg(long, value_enum)]
+    edges_svg: Option<drawing::Projection>,
+
+    /// Build only this component and write a dimensioned top/front/side
+    /// reference sheet as <name>_drawing.svg
+    #[arg(long, value_name = "COMPONENT")]
+    drawing: Option<String>,
+
+    /// Build only this component and print a rough ASCII rendering of it in
+    /// the --preview-view projection, then exit. Writes no files
+    #[arg(long, value_name = "COMPONENT")]
+    preview: Option<String>,
+
+    /// Projection for --preview
+    #[arg(long, value_enum, default_value = "top")]
+    preview_view: drawing::Projection,
+
+    /// Export each named feature to debug/<component>/<name>.stl before booleans
+    #[arg(long)]
+    debug_features: bool,
+
+    /// For each selected component, list its named features (added or cut
+    /// away) and the config fields that change its geometry, then exit.
+    /// Prints JSON with --json
+    #[arg(long)]
+    components_info: bool,
+
+    /// Also print surface area, footprint, triangle count, and center of mass
+    /// for each part (volume and mass are always printed)
+    #[arg(long)]
+    stats: bool,
+
+    /// Config profile from [profiles.<name>] to merge over [default]
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Output file name template, overriding the config's filename_template
+    /// (tokens: {component}, {profile}, {version}, {units})
+    #[arg(long)]
+    filename_template: Option<String>,
+
+    /// Directory the models are written to, created if missing; relative
+    /// paths resolve against the current directory
+    #[arg(long, value_name = "PATH", default_value = "../../models/vcad")]
+    output_dir: std::path::PathBuf,
+
+    /// Output formats, comma-separated (stl, obj, 3mf). STL is binary
+    /// unless --ascii. Every format is written from the same build of each
+    /// part
+    #[arg(long, alias = "format", value_enum, value_delimiter = ',', default_value = "stl")]
+    formats: Vec<output::Format>,
+
+    /// Write STL files as binary, 50 bytes per triangle (the default).
+    /// Overrides an earlier --ascii
+    #[arg(long, overrides_with = "ascii")]
+    binary: bool,
+
+    /// Write STL files as ASCII text: several times larger, but readable
+    /// and diffable. Overrides an earlier --binary
+    #[arg(long, overrides_with = "binary")]
+    ascii: bool,
+
+    /// Normals written to OBJ files: flat (per face, like the STL) or smooth
+    /// (averaged across curved surfaces, with sharp edges kept)
+    #[arg(long, value_enum, default_value = "flat")]
+    shading: output::Shading,
+
+    /// Build every part under both this config and OTHER (same profile and
+    /// overrides), report what changed in size, volume and triangle count,
+    /// and exit. Writes no files
+    #[arg(long, value_name = "OTHER")]
+    diff_config: Option<std::path::PathBuf>,
+
+    /// Check that mating holes on the frame and the parts bolted to it line
+    /// up in the assembly layout, print the worst mismatch per part, and exit
+    #[arg(long)]
+    check_holes: bool,
+
+    /// Place the selected components by the assembly layout, print the
+    /// smallest gap between every pair (or how much they overlap), and
+    /// exit non-zero if any two interfere
+    #[arg(long)]
+    check_fit: bool,
+
+    /// Build every component, run all geometry checks on it (empty result,
+    /// manifold, single body, through-holes, overlapping holes, minimum
+    /// wall, STL round trip), print a report, and exit non-zero if any
+    /// check fails. Writes no files
+    #[arg(long)]
+    validate_all: bool,
+
+    /// Leave a check out of --validate-all (repeatable)
+    #[arg(long, value_enum, value_name = "CHECK")]
+    skip: Vec<checks::Check>,
+
+    /// Low-poly preview: 16-segment circles for fast iteration. Use the
+    /// default full quality for final exports
+    #[arg(long)]
+    quick: bool,
+
+    /// Fail a part that is over max_triangles instead of decimating it
+    #[arg(long)]
+    strict: bool,
+
+    /// Keep running and rebuild whenever config.toml changes. Source edits
+    /// still need a recompile, so only the config file is watched.
+    #[arg(long)]
+    watch: bool,
+
+    /// Print a JSON Schema for config.toml (for editor completion and
+    /// validation) and exit
+    #[arg(long)]
+    emit_schema: bool,
+
+    /// Print a single JSON object summarizing the build to stdout (per
+    /// component status, time, files and triangle counts, plus warnings).
+    /// Progress text moves to stderr
+    #[arg(long, conflicts_with = "watch")]
+    json: bool,
+
+    /// Place the selected components by the assembly layout, push each
+    /// out from the frame's origin by FACTOR times its assembled position,
+    /// and write them as one exploded_assembly model
+    #[arg(long, value_name = "FACTOR")]
+    exploded: Option<f64>,
+
+    /// Place every component on the frame by the frame's own layout and
+    /// write them as one assembly model, then exit. Its 3MF keeps each
+    /// component a separate object colored from [colors]
+    #[arg(long)]
+    assembly: bool,
+
+    /// Trim every part to this box (after origin_mode placement) before
+    /// export, e.g. for a detail render of one region
+    #[arg(long, value_delimiter = ',', value_name = "MINX,MINY,MINZ,MAXX,MAXY,MAXZ", allow_hyphen_values = true)]
+    clip: Vec<f64>,
+
+    /// Rebuild every part instead of reloading one cached by an earlier run
+    /// of this binary with the same config
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Where to cache built parts; defaults to vial-applicator-vcad under
+    /// $XDG_CACHE_HOME, or ~/.cache when that isn't set
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<std::path::PathBuf>,
+
+    /// Move each part so its lowest mesh point sits at Z=0 and its
+    /// footprint is centered on the XY origin before export, whatever
+    /// origin_mode says. The --json summary records each part's offset
+    #[arg(long)]
+    bed_drop: bool,
+
+    /// Rotate each part so the largest flat face it can rest on lies on
+    /// the bed (facing -Z at Z=0), before any --bed-drop. The --json
+    /// summary records the face and the resulting height
+    #[arg(long)]
+    auto_orient: bool,
+
+    /// Warn (without failing the build) about any part, as placed for
+    /// export, larger than a printer's X by Y by Z mm build volume
+    #[arg(long, num_args = 3, value_names = ["X", "Y", "Z"])]
+    check_bed: Option<Vec<f64>>,
+
+    /// Also pack every exported file, the build summary (manifest.json),
+    /// the resolved config (config.toml) and a fastener BOM (bom.csv) into
+    /// one zip at PATH for sharing a release
+    #[arg(long, value_name = "PATH")]
+    pack: Option<std::path::PathBuf>,
+}
+
+type BuildFn = fn(&Config) -> Part;
+type BottomFn = fn(&Config) -> f64;
+
+/// Every component: output name, builder, and nominal base bottom Z.
+const COMPONENTS: &[(&str, BuildFn, BottomFn)] = &[
+    ("peel_plate", peel_plate::build, peel_plate::base_bottom_z),
+    ("vial_cradle", vial_cradle::build, vial_cradle::base_bottom_z),
+    ("main_frame", frame::build, frame::base_bottom_z),
+    ("spool_holder", spool_holder::build, spool_holder::base_bottom_z),
+    ("dancer_arm", dancer_arm::build, dancer_arm::base_bottom_z),
+    ("guide_roller_bracket", guide_roller_bracket::build, guide_roller_bracket::base_bottom_z),
+];
+
+/// The dancer arm printed captive on its pin, built when `print_in_place` is on.
+const IN_PLACE: (&str, BuildFn, BottomFn) = (print_in_place::NAME, print_in_place::build, print_in_place::base_bottom_z);
+
+/// Every component `cfg` can build: [`COMPONENTS`] plus the print-in-place
+/// dancer arm when it is turned on.
+fn all_components(cfg: &Config) -> Vec<(&'static str, BuildFn, BottomFn)> {
+    let mut list = COMPONENTS.to_vec();
+    if cfg.print_in_place {
+        list.push(IN_PLACE);
+    }
+    list
+}
+
+impl Cli {
+    /// Component names given on the command line, positionally or with
+    /// `--component`.
+    fn selected(&self) -> impl Iterator<Item = &String> {
+        self.names.iter().chain(&self.component)
+    }
+}
+
+/// The components to build: every one with `--all`, those named on the
+/// command line, otherwise every one not disabled in `[components]`.
+fn components(cli: &Cli, cfg: &Config) -> Vec<(&'static str, BuildFn, BottomFn)> {
+    let enabled = |name: &str| match (cli.all, cli.selected().next().is_none()) {
+        (true, _) => true,
+        (false, true) => cfg.components.get(name).copied().unwrap_or(true),
+        (false, false) => cli.selected().any(|c| c == name),
+    };
+    all_components(cfg).into_iter().filter(|(name, _, _)| enabled(name)).collect()
+}
+
+/// Names in `--component`, `[components]` or `[colors]` that aren't components.
+fn unknown_components(cli: &Cli, cfg: &Config) -> Vec<String> {
+    let all = all_components(cfg);
+    let known = |name: &str| all.iter().any(|(n, _, _)| *n == name) || name == print_in_place::NAME;
+    cli.selected().chain(cfg.components.keys()).chain(cfg.colors.keys()).filter(|n| !known(n)).cloned().collect()
+}
+
+/// `--output-dir`, created if missing and made absolute so every
+/// "Exported:" line says exactly where its file landed. Only modes that
+/// write files call this.
+fn output_dir(cli: &Cli) -> Result<String, AppError> {
+    std::fs::create_dir_all(&cli.output_dir)
+        .and_then(|_| std::fs::canonicalize(&cli.output_dir))
+        .map(|dir| dir.display().to_string())
+        .map_err(|e| AppError::OutputDir(cli.output_dir.clone(), e))
+}
+
+/// Why a run failed; [`main`] reports it and exits with its code.
+#[derive(Debug)]
+enum AppError {
+    /// The config couldn't be loaded.
+    Config(config::ConfigError),
+    /// `--output-dir` couldn't be created.
+    OutputDir(std::path::PathBuf, std::io::Error),
+    /// A step that failed, e.g. a file that couldn't be written.
+    Error(String),
+    /// A run that found problems, summarized for stderr as given.
+    Failed(String),
+}
+
+impl AppError {
+    /// Process exit code for the failure, from BSD `sysexits.h` where one fits.
+    fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Config(e) => e.exit_code(),
+            AppError::OutputDir(..) => 73,
+            AppError::Error(_) | AppError::Failed(_) => 1,
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AppError::Config(e) => write!(f, "Error: {}", e),
+            AppError::OutputDir(path, e) => write!(f, "Error: failed to create {}: {}", path.display(), e),
+            AppError::Error(message) => write!(f, "Error: {}", message),
+            AppError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<config::ConfigError> for AppError {
+    fn from(e: config::ConfigError) -> Self {
+        AppError::Config(e)
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Error(message)
+    }
+}
 
 fn main() {
-    let output_dir = "../../models/vcad";
-    std::fs::create_dir_all(output_dir).expect("Failed to create output directory");
-
-    let cfg = config::load_config();
-
-    println!("Building vcad components...\n");
-
-    type BuildFn = Box<dyn Fn(&config::Config) -> vcad::Part>;
-    let components: Vec<(&str, BuildFn)> = vec![
-        ("peel_plate", Box::new(peel_plate::build)),
-        ("vial_cradle", Box::new(vial_cradle::build)),
-        ("main_frame", Box::new(frame::build)),
-        ("spool_holder", Box::new(spool_holder::build)),
-        ("dancer_arm", Box::new(dancer_arm::build)),
-        ("guide_roller_bracket", Box::new(guide_roller_bracket::build)),
-    ];
-
-    for (name, build_fn) in &components {
-        let part = build_fn(&cfg);
-        let path = format!("{}/{}.stl", output_dir, name);
-        part.write_stl(&path)
-            .unwrap_or_else(|e| panic!("Failed to write {} STL: {}", name, e));
+    let cli = Cli::parse();
+    if let Err(e) = run(&cli) {
+        eprintln!("{}", e);
+        std::process::exit(e.exit_code());
+    }
+}
+
+/// Do what `cli` asks, leaving the exit code to [`main`].
+fn run(cli: &Cli) -> Result<(), AppError> {
+    if cli.emit_schema {
+        let names: Vec<&str> = COMPONENTS.iter().chain([&IN_PLACE]).map(|(n, _, _)| *n).collect();
+        println!("{}", serde_json::to_string_pretty(&schema::config_schema(&names)).map_err(|e| e.to_string())?);
+        return Ok(());
+    }
+
+    let cfg = load(cli)?;
+    if cli.list {
+        for (name, _, _) in all_components(&cfg) {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+    let errors = validate::validate(&cfg);
+    if !errors.is_empty() {
+        if cli.json {
+            println!("{}", serde_json::json!({ "config": cfg.hash(), "status": "invalid", "errors": errors }));
+        }
+        return Err(AppError::Failed(format!("Invalid config:\n  {}", errors.join("\n  "))));
+    }
+    let mut warnings = validate::warnings(&cfg);
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    let unknown = unknown_components(cli, &cfg);
+    if !unknown.is_empty() {
+        let names: Vec<&str> = all_components(&cfg).iter().map(|(n, _, _)| *n).collect();
+        return Err(AppError::Failed(format!("Unknown component(s): {}. Available: {}", unknown.join(", "), names.join(", "))));
+    }
+    let clip_ok = match cli.clip[..] {
+        [] => true,
+        [x0, y0, z0, x1, y1, z1] => x0 < x1 && y0 < y1 && z0 < z1,
+        _ => false,
+    };
+    if !clip_ok {
+        return Err(AppError::Failed("--clip takes six numbers MINX,MINY,MINZ,MAXX,MAXY,MAXZ with each minimum below its maximum".into()));
+    }
+    if components(cli, &cfg).is_empty() {
+        eprintln!("Warning: every component is disabled in [components]; nothing to build.");
+    }
+    if cli.components_info {
+        return components_info(cli, &cfg);
+    }
+    if cli.check_holes {
+        return check_holes(&cfg);
+    }
+    if cli.check_fit {
+        return check_fit(cli, &cfg);
+    }
+    if let Some(other) = &cli.diff_config {
+        return diff_config(cli, &cfg, other);
+    }
+    if cli.validate_all {
+        return validate_all(cli, &cfg);
+    }
+    if let Some(name) = &cli.preview {
+        return preview(cli, &cfg, name);
+    }
+
+    let output_dir = output_dir(cli)?;
+    let output_dir = output_dir.as_str();
+    if let Some(name) = &cli.drawing {
+        return write_drawing(cli, &cfg, output_dir, name);
+    }
+    if let Some(factor) = cli.exploded {
+        return write_exploded(cli, &cfg, output_dir, factor);
+    }
+    if cli.assembly {
+        return write_assembly(cli, &cfg, output_dir);
+    }
+
+    say!(cli, "Building vcad components...\n");
+
+    // Hash of each part's exported STL, so watch mode only rewrites parts
+    // whose geometry actually changed.
+    let mut hashes: HashMap<&str, u64> = HashMap::new();
+    let (mut triangles, mut merged) = (0, 0);
+    let started = Instant::now();
+    let mut summary = Vec::new();
+    for (name, build_fn, bottom_fn) in components(cli, &cfg) {
+        let start = Instant::now();
+        let cache = cache_path(cli, &cfg, name);
+        let part = match cache.as_deref().map(Part::read_json) {
+            Some(Ok(part)) => {
+                say!(cli, "Reusing cached {}", name);
+                part
+            }
+            _ => {
+                let part = build_component(cli, &cfg, output_dir, name, build_fn, bottom_fn);
+                if let Some(Err(e)) = cache.as_deref().map(|path| write_cache(name, &part, path)) {
+                    eprintln!("Warning: {}", e);
+                }
+                part
+            }
+        };
+        let (part, orient) = match cli.auto_orient.then(|| auto_orient(&part)) {
+            Some((part, normal, area)) => {
+                let (min, max) = part.bounding_box();
+                let height = max[2] - min[2];
+                say!(cli, "Oriented {} face ({}, {}, {}) of {:.1} mm² down, {:.1} mm tall", name, normal[0], normal[1], normal[2], area, height);
+                (part, Some((normal, area, height)))
+            }
+            None => (part, None),
+        };
+        let offset = cli.bed_drop.then(|| part.bed_offset());
+        let part = match offset {
+            Some([x, y, z]) => {
+                say!(cli, "Dropped {} to the bed by ({:.3}, {:.3}, {:.3})", name, x, y, z);
+                part.translate(x, y, z)
+            }
+            None => part,
+        };
+        if let Err(problems) = checks::run(checks::Check::Floating, &part, &[]) {
+            let warning = format!("{}: {}", name, problems.join("; "));
+            eprintln!("Warning: {}", warning);
+            warnings.push(warning);
+        }
+        let (min, max) = analysis::bounding_box(&part);
+        if let Some(&[x, y, z]) = cli.check_bed.as_deref() {
+            let over = analysis::over_bed(max - min, [x, y, z]);
+            if !over.is_empty() {
+                let size = max - min;
+                let warning = format!("{}: {:.1}×{:.1}×{:.1} mm is over the {}×{}×{} mm bed along {}", name, size.x, size.y, size.z, x, y, z, over.join(", "));
+                eprintln!("Warning: {}", warning);
+                warnings.push(warning);
+            }
+        }
+        triangles += part.num_triangles();
+        hashes.insert(name, stl_hash(&part));
+        let result = write_outputs(cli, &cfg, output_dir, name, &part);
+        let mut entry = serde_json::json!({
+            "name": name,
+            "seconds": start.elapsed().as_secs_f64(),
+            "triangles": part.num_triangles(),
+            "size": (max - min).as_slice(),
+        });
+        if let Some((normal, area, height)) = orient {
+            entry["auto_orient"] = serde_json::json!({ "normal": normal, "area": area, "height": height });
+        }
+        if let Some(offset) = offset {
+            entry["bed_offset"] = offset.to_vec().into();
+        }
+        match result {
+            Ok((files, removed)) => {
+                merged += removed;
+                entry["status"] = "ok".into();
+                entry["written_triangles"] = (part.num_triangles() - removed).into();
+                entry["files"] = files.into();
+            }
+            Err(e) if !cli.json => return Err(e.into()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                entry["status"] = "error".into();
+                entry["error"] = e.into();
+            }
+        }
+        summary.push(entry);
+    }
+
+    let failed = summary.iter().any(|c| c["status"] != "ok");
+    let report = serde_json::json!({
+        "config": cfg.hash(),
+        "profile": cli.profile,
+        "quick": cfg.quick,
+        "max_triangles": cfg.max_triangles,
+        "status": if failed { "error" } else { "ok" },
+        "seconds": started.elapsed().as_secs_f64(),
+        "triangles": triangles,
+        "components": summary,
+        "warnings": warnings,
+    });
+    if let Some(path) = &cli.pack {
+        if failed {
+            return Err(AppError::Failed(format!("Not writing {}: a component failed to export", path.display())));
+        }
+        let count = write_pack(cli, &cfg, path, &report)?;
+        say!(cli, "Packed {} files into {}", count, path.display());
+    }
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+        return if failed { Err(AppError::Failed("A component failed to export.".into())) } else { Ok(()) };
+    }
+
+    println!("\nAll vcad components built (config {}).", cfg.hash());
+    if !cfg.quick && cfg.max_triangles > 0 {
+        println!("Coplanar merge and decimation to {} per part removed {} of {} triangles.", cfg.max_triangles, merged, triangles);
+    } else if !cfg.quick {
+        println!("Coplanar merge removed {} of {} triangles.", merged, triangles);
+    } else {
+        println!(
+            "Quick mode: {} triangles, about {} fewer than full quality.",
+            triangles,
+            geometry::take_saved_triangles()
+        );
+    }
+
+    if cli.watch {
+        let path = config::resolve_config_path();
+        println!("\nWatching {} for changes (Ctrl-C to stop)...", path.display());
+        watch::on_change(&path, || rebuild_changed(cli, output_dir, &mut hashes));
+    }
+    Ok(())
+}
+
+/// Load the config for the selected profile and apply CLI overrides.
+fn load(cli: &Cli) -> Result<Config, config::ConfigError> {
+    config::load_config(cli.profile.as_deref()).map(|cfg| with_overrides(cli, cfg))
+}
+
+/// Apply the CLI's overrides to a loaded config.
+fn with_overrides(cli: &Cli, mut cfg: Config) -> Config {
+    cfg.quick |= cli.quick;
+    cfg
+}
+
+/// Describe each selected component's features and the config fields it
+/// depends on.
+///
+/// Dependencies are found by rebuilding under `Config::nudged` copies and
+/// comparing exported meshes, so they stay true to the code as it is.
+fn components_info(cli: &Cli, cfg: &Config) -> Result<(), AppError> {
+    let nudged = cfg.nudged();
+    let mut report = Vec::new();
+    for (name, build_fn, _) in components(cli, cfg) {
+        let (part, parts) = features::capture(|| build_fn(cfg));
+        let mut counted: Vec<(&str, bool, usize)> = Vec::new();
+        for feature in &parts {
+            let (base, additive) = (features::base_name(feature), features::is_additive(feature, &part));
+            match counted.iter_mut().find(|(n, a, _)| *n == base && *a == additive) {
+                Some(entry) => entry.2 += 1,
+                None => counted.push((base, additive, 1)),
+            }
+        }
+        let hash = stl_hash(&part);
+        // Some nudges make a builder panic (an outline that self-intersects);
+        // that counts as a dependency. The panic message still goes to stderr.
+        let depends: Vec<&str> = nudged
+            .iter()
+            .filter(|(_, other)| std::panic::catch_unwind(|| stl_hash(&build_fn(other))).map_or(true, |h| h != hash))
+            .map(|(key, _)| key.as_str())
+            .collect();
+        report.push(serde_json::json!({
+            "name": name,
+            "features": counted.iter().map(|&(feature, additive, count)| serde_json::json!({
+                "name": feature,
+                "kind": if additive { "add" } else { "cut" },
+                "count": count,
+            })).collect::<Vec<_>>(),
+            "mounting_holes": features::count_tagged(features::Role::MountingHole, || build_fn(cfg)),
+            "depends_on": depends,
+        }));
+    }
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+        return Ok(());
+    }
+    for component in &report {
+        println!("{}:", component["name"].as_str().unwrap_or_default());
+        for feature in component["features"].as_array().into_iter().flatten() {
+            let count = feature["count"].as_u64().unwrap_or(1);
+            println!(
+                "  {} {}{}",
+                feature["kind"].as_str().unwrap_or_default(),
+                feature["name"].as_str().unwrap_or_default(),
+                if count > 1 { format!(" ×{}", count) } else { String::new() }
+            );
+        }
+        println!("  mounting holes: {}", component["mounting_holes"]);
+        let depends: Vec<&str> = component["depends_on"].as_array().into_iter().flatten().filter_map(|k| k.as_str()).collect();
+        println!("  depends on: {}", depends.join(", "));
+    }
+    Ok(())
+}
+
+/// Print hole alignment per frame interface.
+fn check_holes(cfg: &Config) -> Result<(), AppError> {
+    let report = interfaces::load_layout(&interfaces::manifest_path())
+        .and_then(|layout| Ok((interfaces::check(cfg, &layout)?, interfaces::check_spool(cfg, &layout)?)));
+    let (report, spool) = report?;
+    for interface in &report {
+        println!(
+            "main_frame <-> {}: {} holes, worst mismatch {:.3} mm{}",
+            interface.component,
+            interface.holes,
+            interface.worst,
+            if interface.is_aligned() { "" } else { "  MISALIGNED" }
+        );
+    }
+    if spool.is_empty() {
+        println!("main_frame <-> spool_holder: ok");
+    } else {
+        println!("main_frame <-> spool_holder: won't bolt down\n  {}", spool.join("\n  "));
+    }
+    if report.iter().all(interfaces::Interface::is_aligned) && spool.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Failed(format!("Mating holes don't line up (tolerance {} mm).", interfaces::HOLE_TOLERANCE)))
+    }
+}
+
+/// Print the clearance between every pair of assembled components.
+fn check_fit(cli: &Cli, cfg: &Config) -> Result<(), AppError> {
+    let layout = interfaces::load_layout(&interfaces::manifest_path())?;
+    let mut placed = Vec::new();
+    for (name, build_fn, bottom_fn) in components(cli, cfg) {
+        let Some(&pose) = layout.get(name) else {
+            eprintln!("Warning: {} is not in the assembly layout; skipped", name);
+            continue;
+        };
+        placed.push((name, interfaces::place_part(&build_fn(cfg), bottom_fn(cfg), pose, 0.0)));
+    }
+    let mut interfering = 0;
+    for (i, (a_name, a)) in placed.iter().enumerate() {
+        for (b_name, b) in &placed[i + 1..] {
+            let overlap = a.intersection(b).volume();
+            let fit = if overlap > 1e-6 {
+                interfering += 1;
+                format!("interferes ({:.2} mm³)", overlap)
+            } else {
+                match analysis::min_distance(a, b) {
+                    gap if gap < 1e-6 => "touching".to_string(),
+                    gap => format!("clear by {:.2} mm", gap),
+                }
+            };
+            println!("{} <-> {}: {}", a_name, b_name, fit);
+        }
+    }
+    if interfering > 0 {
+        Err(AppError::Failed(format!("{} pair{} of parts interfere.", interfering, if interfering == 1 { "" } else { "s" })))
+    } else {
+        Ok(())
+    }
+}
+
+/// Build the selected components and run every geometry check not named in
+/// `--skip`.
+fn validate_all(cli: &Cli, cfg: &Config) -> Result<(), AppError> {
+    const SHOWN: usize = 5;
+    let mut failures = 0;
+    for (name, build_fn, _) in components(cli, cfg) {
+        let (part, features) = features::capture(|| build_fn(cfg));
+        println!("{}:", name);
+        for check in checks::Check::ALL {
+            if cli.skip.contains(&check) {
+                println!("  {:<18} skipped", check.name());
+                continue;
+            }
+            match checks::run(check, &part, &features) {
+                Ok(summary) => println!("  {:<18} ok ({})", check.name(), summary),
+                Err(problems) => {
+                    failures += 1;
+                    println!("  {:<18} FAIL", check.name());
+                    for problem in problems.iter().take(SHOWN) {
+                        println!("    {}", problem);
+                    }
+                    if problems.len() > SHOWN {
+                        println!("    ... and {} more", problems.len() - SHOWN);
+                    }
+                }
+            }
+        }
+    }
+    if failures == 0 {
+        println!("\nAll checks passed (config {}).", cfg.hash());
+        Ok(())
+    } else {
+        Err(AppError::Failed(format!("\n{} check(s) failed.", failures)))
+    }
+}
+
+/// Build every part under `cfg` and under the config at `other`, and print
+/// how each part's geometry differs.
+fn diff_config(cli: &Cli, cfg: &Config, other: &std::path::Path) -> Result<(), AppError> {
+    let theirs = with_overrides(cli, config::load_config_from(other, cli.profile.as_deref())?);
+    let errors = validate::validate(&theirs);
+    if !errors.is_empty() {
+        return Err(AppError::Failed(format!("Invalid config {}:\n  {}", other.display(), errors.join("\n  "))));
+    }
+    println!(
+        "Comparing {} (config {}) with {} (config {}):\n",
+        config::resolve_config_path().display(),
+        cfg.hash(),
+        other.display(),
+        theirs.hash()
+    );
+
+    let (ours, others) = (components(cli, cfg), components(cli, &theirs));
+    let mut names: Vec<&str> = ours.iter().map(|c| c.0).collect();
+    for &(name, _, _) in &others {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    let build = |list: &[(&str, BuildFn, BottomFn)], name: &str, cfg: &Config| {
+        list.iter().find(|c| c.0 == name).map(|&(_, build_fn, _)| build_fn(cfg))
+    };
+
+    let mut changed = 0;
+    for name in &names {
+        let (before, after) = match (build(&ours, name, cfg), build(&others, name, &theirs)) {
+            (Some(before), Some(after)) => (before, after),
+            (before, _) => {
+                changed += 1;
+                println!("{}: only built under {}", name, if before.is_some() { "this config" } else { "the other config" });
+                continue;
+            }
+        };
+        let size = |part: &Part| {
+            let (min, max) = part.bounding_box();
+            [max[0] - min[0], max[1] - min[1], max[2] - min[2]]
+        };
+        let (size_a, size_b) = (size(&before), size(&after));
+        let (vol_a, vol_b) = (before.volume(), after.volume());
+        let (tri_a, tri_b) = (before.num_triangles(), after.num_triangles());
+        let same_size = size_a.iter().zip(&size_b).all(|(a, b)| (a - b).abs() < 1e-6);
+        if same_size && (vol_a - vol_b).abs() < 1e-6 && tri_a == tri_b {
+            println!("{}: unchanged", name);
+            continue;
+        }
+        changed += 1;
+        println!("{}: CHANGED", name);
+        let deltas: Vec<String> = ["x", "y", "z"]
+            .iter()
+            .zip(size_a.iter().zip(&size_b))
+            .filter(|(_, (a, b))| (*a - *b).abs() >= 1e-6)
+            .map(|(axis, (a, b))| format!("{} {:+.2}", axis, b - a))
+            .collect();
+        println!(
+            "  size      {:.2} x {:.2} x {:.2} -> {:.2} x {:.2} x {:.2} mm{}",
+            size_a[0],
+            size_a[1],
+            size_a[2],
+            size_b[0],
+            size_b[1],
+            size_b[2],
+            if deltas.is_empty() { String::new() } else { format!(" ({})", deltas.join(", ")) }
+        );
+        println!("  volume    {:.1} -> {:.1} mm³ ({:+.1})", vol_a, vol_b, vol_b - vol_a);
+        println!("  triangles {} -> {}", tri_a, tri_b);
+    }
+    println!("\n{} of {} parts changed.", changed, names.len());
+    Ok(())
+}
+
+/// Build one component and write its reference sheet.
+fn write_drawing(cli: &Cli, cfg: &Config, output_dir: &str, name: &str) -> Result<(), AppError> {
+    let components = all_components(cfg);
+    let Some(&(name, build_fn, bottom_fn)) = components.iter().find(|(n, _, _)| *n == name) else {
+        let names: Vec<&str> = components.iter().map(|(n, _, _)| *n).collect();
+        return Err(AppError::Failed(format!("Unknown component '{}'. Available: {}", name, names.join(", "))));
+    };
+    let part = build_component(cli, cfg, output_dir, name, build_fn, bottom_fn);
+    let stem = file_stem(cli, cfg, name);
+    let path = format!("{}/{}_drawing.svg", output_dir, stem);
+    drawing::write_drawing_svg(&part, &path, name).map_err(|e| format!("failed to write {} drawing: {}", name, e))?;
+    println!("Exported: {}", path);
+    Ok(())
+}
+
+/// Print an ASCII rendering of one component.
+fn preview(cli: &Cli, cfg: &Config, name: &str) -> Result<(), AppError> {
+    const COLUMNS: usize = 72;
+    let components = all_components(cfg);
+    let Some(&(name, build_fn, bottom_fn)) = components.iter().find(|(n, _, _)| *n == name) else {
+        let names: Vec<&str> = components.iter().map(|(n, _, _)| *n).collect();
+        return Err(AppError::Failed(format!("Unknown component '{}'. Available: {}", name, names.join(", "))));
+    };
+    // Only --debug-features writes anything while previewing.
+    let output_dir = if cli.debug_features { output_dir(cli)? } else { String::new() };
+    let part = build_component(cli, cfg, &output_dir, name, build_fn, bottom_fn);
+    let (min, max) = part.bounding_box();
+    let (u, v) = match cli.preview_view {
+        drawing::Projection::Top => ("X", "Y"),
+        drawing::Projection::Front => ("X", "Z"),
+        drawing::Projection::Side => ("Y", "Z"),
+    };
+    let axis = |a: &str| ["X", "Y", "Z"].iter().position(|&n| n == a).unwrap_or(0);
+    println!(
+        "{} {} view: {} right ({:.1} mm), {} up ({:.1} mm); nearer surfaces darker",
+        name,
+        cli.preview_view.name(),
+        u,
+        max[axis(u)] - min[axis(u)],
+        v,
+        max[axis(v)] - min[axis(v)]
+    );
+    print!("{}", drawing::ascii_preview(&part, cli.preview_view, COLUMNS));
+    Ok(())
+}
+
+/// Write the selected components, laid out and pushed apart, as one model.
+fn write_exploded(cli: &Cli, cfg: &Config, output_dir: &str, factor: f64) -> Result<(), AppError> {
+    let layout = interfaces::load_layout(&interfaces::manifest_path())?;
+    let mut assembly = Part::empty("exploded_assembly");
+    for (name, build_fn, bottom_fn) in components(cli, cfg) {
+        let Some(&pose) = layout.get(name) else {
+            eprintln!("Warning: {} is not in the assembly layout; left out", name);
+            continue;
+        };
+        // Built centered; place_part drops each bottom face to Z=0 itself.
+        assembly = assembly + interfaces::place_part(&build_fn(cfg), bottom_fn(cfg), pose, factor);
+    }
+    write_model(cli, cfg, output_dir, "exploded_assembly", &assembly, &cli.formats)
+}
+
+/// Write the frame-placed assembly: a 3MF with one colored object per
+/// component, and the other formats as one merged mesh.
+fn write_assembly(cli: &Cli, cfg: &Config, output_dir: &str) -> Result<(), AppError> {
+    let placed = assembly::placed(cfg);
+    let (three_mf, merged): (Vec<output::Format>, Vec<output::Format>) = cli.formats.iter().partition(|&&f| f == output::Format::ThreeMf);
+    if !merged.is_empty() {
+        write_model(cli, cfg, output_dir, "assembly", &assembly::merge(&placed), &merged)?;
+    }
+    if three_mf.is_empty() {
+        return Ok(());
+    }
+    let stem = format!("{}/{}", output_dir, file_stem(cli, cfg, "assembly"));
+    let (path, _) = output::export_3mf(&placed, &stem, "assembly", &cfg.colors, &metadata(cli, cfg, "assembly"), &mesh_options(cli, cfg))?;
+    println!("Exported: {}", path);
+    Ok(())
+}
+
+/// Write a multi-part model `name` in `formats`, reporting the
+/// files written.
+fn write_model(cli: &Cli, cfg: &Config, output_dir: &str, name: &str, model: &Part, formats: &[output::Format]) -> Result<(), AppError> {
+    let stem = format!("{}/{}", output_dir, file_stem(cli, cfg, name));
+    let (paths, _) = output::write_part(model, &stem, formats, name, &metadata(cli, cfg, name), &mesh_options(cli, cfg))?;
+    for path in paths {
         println!("Exported: {}", path);
     }
+    Ok(())
+}
+
+/// Tags recording where an exported `name` came from: the component, the
+/// generator version, and the config, profile and origin mode it was built
+/// with.
+fn metadata(cli: &Cli, cfg: &Config, name: &str) -> output::Metadata {
+    let origin = match cfg.origin_mode {
+        config::OriginMode::Center => "center",
+        config::OriginMode::Bottom => "bottom",
+    };
+    [
+        ("component", name),
+        ("generator", concat!("vial-applicator-vcad ", env!("CARGO_PKG_VERSION"))),
+        ("config", &cfg.hash()),
+        ("profile", cli.profile.as_deref().unwrap_or("default")),
+        ("origin_mode", origin),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value.to_string()))
+    .collect()
+}
+
+/// Export mesh options: quick mode skips the coplanar merge along with the
+/// fine tessellation, and `max_triangles` caps each part, strictly under
+/// `--strict`.
+fn mesh_options(cli: &Cli, cfg: &Config) -> output::MeshOptions {
+    output::MeshOptions {
+        merge: !cfg.quick,
+        shading: cli.shading,
+        budget: (cfg.max_triangles > 0).then_some(output::TriangleBudget { max: cfg.max_triangles, strict: cli.strict }),
+        stl: if cli.ascii && !cli.binary { output::StlEncoding::Ascii } else { output::StlEncoding::Binary },
+    }
+}
+
+/// Output file stem for `name` from the filename template.
+fn file_stem(cli: &Cli, cfg: &Config, name: &str) -> String {
+    let template = cli.filename_template.as_deref().unwrap_or(&cfg.filename_template);
+    let tokens = output::NameTokens {
+        component: name,
+        profile: cli.profile.as_deref().unwrap_or("default"),
+    };
+    output::file_stem(template, &tokens)
+}
+
+/// Build one component, exporting debug features and applying the origin
+/// mode and any --clip box.
+fn build_component(cli: &Cli, cfg: &Config, output_dir: &str, name: &str, build_fn: BuildFn, bottom_fn: BottomFn) -> Part {
+    let part = if cli.debug_features {
+        let (part, parts) = features::capture(|| build_fn(cfg));
+        let dir = std::path::Path::new(output_dir).join("debug").join(name);
+        match features::write_features(&parts, &dir) {
+            Ok(paths) => say!(cli, "Debug: {} features in {}", paths.len(), dir.display()),
+            Err(e) => eprintln!("Warning: failed to write {} debug features: {}", name, e),
+        }
+        part
+    } else {
+        build_fn(cfg)
+    };
+    let part = match cfg.origin_mode {
+        config::OriginMode::Center => part,
+        config::OriginMode::Bottom => part.translate(0.0, 0.0, -bottom_fn(cfg)),
+    };
+    match cli.clip[..] {
+        [x0, y0, z0, x1, y1, z1] => part.clip_to_box([x0, y0, z0], [x1, y1, z1]),
+        _ => part,
+    }
+}
+
+/// `--cache-dir`, else `vial-applicator-vcad` under `$XDG_CACHE_HOME` (when
+/// absolute, per the XDG spec) or `~/.cache`. `None` when there's no home
+/// to cache under.
+fn cache_dir(cli: &Cli) -> Option<std::path::PathBuf> {
+    if let Some(dir) = &cli.cache_dir {
+        return Some(dir.clone());
+    }
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(std::path::PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".cache")))?;
+    Some(base.join("vial-applicator-vcad"))
+}
+
+/// Where `name` is cached for this binary, config and clip box, or `None`
+/// when caching is off. The binary's build time stands in for the source
+/// version, so a recompile starts a fresh cache.
+fn cache_path(cli: &Cli, cfg: &Config, name: &str) -> Option<std::path::PathBuf> {
+    if cli.no_cache || cli.debug_features {
+        return None;
+    }
+    let built = std::env::current_exe().and_then(|exe| exe.metadata()?.modified()).ok()?;
+    let mut hasher = DefaultHasher::new();
+    (cfg.hash(), format!("{:?}", cli.clip), built).hash(&mut hasher);
+    Some(cache_dir(cli)?.join(format!("{}-{:016x}.json", name, hasher.finish())))
+}
+
+/// Cache component `name` at `path`, dropping its older entries.
+fn write_cache(name: &str, part: &Part, path: &std::path::Path) -> Result<(), String> {
+    let prefix = format!("{}-", name);
+    if let Some(Ok(entries)) = path.parent().map(std::fs::read_dir) {
+        for entry in entries.flatten().filter(|e| e.file_name().to_string_lossy().starts_with(&prefix)) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+    part.write_json(path)
+}
+
+/// Write the part in each requested format (plus any requested drawings and
+/// stats). Returns the files written and how many triangles the coplanar
+/// merge and any decimation removed.
+fn write_outputs(cli: &Cli, cfg: &Config, output_dir: &str, name: &str, part: &Part) -> Result<(Vec<String>, usize), String> {
+    let stem = file_stem(cli, cfg, name);
+    let options = mesh_options(cli, cfg);
+    let (mut paths, written) = output::write_part(part, &format!("{}/{}", output_dir, stem), &cli.formats, name, &metadata(cli, cfg, name), &options)?;
+    if written < part.num_triangles() {
+        let how = if options.budget.is_some() { "merging coplanar faces and decimating" } else { "merging coplanar faces" };
+        say!(cli, "{} -> {} ({} triangles written after {})", part.describe(), paths.join(", "), written, how);
+    } else {
+        say!(cli, "{} -> {}", part.describe(), paths.join(", "));
+    }
+    let (min, max) = analysis::bounding_box(part);
+    say!(cli, "  bounding box {:.1}×{:.1}×{:.1} mm", max.x - min.x, max.y - min.y, max.z - min.z);
+    say!(cli, "  volume {:.1} mm³, estimated mass {:.1} g", analysis::volume(part), analysis::estimate_mass(part, cfg.material_density));
+    if let Some(budget) = options.budget {
+        say!(cli, "  {} of {} budgeted triangles", written, budget.max);
+        if written > budget.max {
+            eprintln!(
+                "Warning: {} stays at {} triangles, over max_triangles ({}): decimating further would move its surface more than {}mm",
+                name,
+                written,
+                budget.max,
+                output::DECIMATE_TOLERANCE_MM
+            );
+        }
+    }
+
+    if let Some(projection) = cli.edges_svg {
+        let svg_path = format!("{}/{}_{}.svg", output_dir, stem, projection.name());
+        drawing::write_edges_svg(part, &svg_path, projection).map_err(|e| format!("Failed to write {} SVG: {}", name, e))?;
+        say!(cli, "Exported: {}", svg_path);
+        paths.push(svg_path);
+    }
+
+    if cli.stats {
+        let com = analysis::center_of_mass(part);
+        say!(
+            cli,
+            "  surface {:.1} mm², footprint {:.1} mm², {} triangles, center of mass ({:.2}, {:.2}, {:.2})",
+            part.surface_area(),
+            analysis::footprint_area(part),
+            part.num_triangles(),
+            com.x,
+            com.y,
+            com.z
+        );
+        // Tighter than the bounding box for round parts, whose corners it overstates.
+        let (axis, radius, height) = analysis::tightest_bounding_cylinder(part);
+        say!(cli, "  bounding cylinder ⌀{:.2} x {:.2} mm about {}", 2.0 * radius, height, axis.name());
+        if name == "spool_holder" {
+            // The spindle envelope is what a spool's bore has to clear.
+            let (radius, height) = analysis::bounding_cylinder(part, analysis::Axis::Z);
+            let flange_top = part.bounding_box().0[2] + cfg.spool_flange_thickness;
+            let above_flange = part & &centered_cube("spindle_zone", 4.0 * radius, 4.0 * radius, height)
+                .translate(0.0, 0.0, flange_top + height / 2.0);
+            let (spindle_r, spindle_h) = analysis::bounding_cylinder(&above_flange, analysis::Axis::Z);
+            say!(cli, "  spindle ⌀{:.2} x {:.2} mm (spool_spindle_od {})", 2.0 * spindle_r, spindle_h, cfg.spool_spindle_od);
+        }
+        if name == "dancer_arm" {
+            let (px, py) = dancer_arm::PIVOT_XY;
+            let lever = (com.x - px).hypot(com.y - py);
+            say!(cli, "  pivot to center of mass: {:.2} mm", lever);
+        }
+    }
+    Ok((paths, part.num_triangles() - written))
+}
+
+/// Reload the config and rewrite only the parts whose geometry changed.
+fn rebuild_changed(cli: &Cli, output_dir: &str, hashes: &mut HashMap<&'static str, u64>) {
+    let start = Instant::now();
+    let cfg = match load(cli) {
+        Ok(cfg) => cfg,
+        Err(e) => return println!("Config reload failed ({}); keeping previous outputs.", e),
+    };
+    let errors = validate::validate(&cfg);
+    if !errors.is_empty() {
+        return println!("Invalid config:\n  {}", errors.join("\n  "));
+    }
+    for warning in validate::warnings(&cfg) {
+        println!("Warning: {}", warning);
+    }
+
+    let mut changed = Vec::new();
+    let components = components(cli, &cfg);
+    for &(name, build_fn, bottom_fn) in &components {
+        let part = build_component(cli, &cfg, output_dir, name, build_fn, bottom_fn);
+        let part = if cli.auto_orient { auto_orient(&part).0 } else { part };
+        let part = match cli.bed_drop.then(|| part.bed_offset()) {
+            Some([x, y, z]) => part.translate(x, y, z),
+            None => part,
+        };
+        let hash = stl_hash(&part);
+        if hashes.insert(name, hash) != Some(hash) {
+            if let Err(e) = write_outputs(cli, &cfg, output_dir, name, &part) {
+                // Forget the hash so the part is retried on the next change.
+                hashes.remove(name);
+                println!("Error: {}", e);
+                continue;
+            }
+            changed.push(name);
+        }
+    }
+
+    let unchanged = components.len() - changed.len();
+    match changed.len() {
+        0 => println!("Rebuilt in {:.2?} (config {}): no geometry changed ({} parts).", start.elapsed(), cfg.hash(), unchanged),
+        _ => println!(
+            "Rebuilt in {:.2?} (config {}): {} changed ({}), {} unchanged.",
+            start.elapsed(),
+            cfg.hash(),
+            changed.len(),
+            changed.join(", "),
+            unchanged
+        ),
+    }
+}
+
+/// Zip the files exported for every component in `report` under `parts/`,
+/// with `report` itself as `manifest.json`, the resolved config as
+/// `config.toml` and the fastener BOM as `bom.csv`. Returns the number of
+/// entries written.
+fn write_pack(cli: &Cli, cfg: &Config, path: &std::path::Path, report: &serde_json::Value) -> Result<usize, String> {
+    let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+    for file in report["components"].as_array().into_iter().flatten().flat_map(|c| c["files"].as_array().into_iter().flatten()) {
+        let file = std::path::Path::new(file.as_str().unwrap_or_default());
+        let data = std::fs::read(file).map_err(|e| format!("Failed to read {} for the pack: {}", file.display(), e))?;
+        let name = file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        entries.push((format!("parts/{}", name), data));
+    }
+    let manifest = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    let config = format!(
+        "# Resolved config {} (profile {}), defaults and overrides applied\n{}",
+        cfg.hash(),
+        cli.profile.as_deref().unwrap_or("default"),
+        toml::to_string(cfg).map_err(|e| format!("Failed to write the resolved config: {}", e))?
+    );
+    entries.push(("manifest.json".into(), manifest.into_bytes()));
+    entries.push(("config.toml".into(), config.into_bytes()));
+    entries.push(("bom.csv".into(), bom_csv(cfg, &components(cli, cfg)).into_bytes()));
 
-    println!("\nAll vcad components built.");
+    let refs: Vec<(&str, &[u8])> = entries.iter().map(|(name, data)| (name.as_str(), data.as_slice())).collect();
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(path, output::zip_stored(&refs)).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(entries.len())
+}
+
+/// Fastener bill of materials as CSV: the mounting holes each component
+/// takes a fastener through, and the total.
+fn bom_csv(cfg: &Config, components: &[(&str, BuildFn, BottomFn)]) -> String {
+    let fastener = cfg.fastener().designation;
+    let mut csv = String::from("component,fastener,quantity\n");
+    let mut total = 0;
+    for (name, build_fn, _) in components {
+        let count = features::count_tagged(features::Role::MountingHole, || build_fn(cfg));
+        total += count;
+        csv += &format!("{},{},{}\n", name, fastener, count);
+    }
+    csv += &format!("total,{},{}\n", fastener, total);
+    csv
+}
+
+/// `part` laid on its largest flat face, with that face's outward normal
+/// (rounded for the report) and area.
+fn auto_orient(part: &Part) -> (Part, [f64; 3], f64) {
+    let (normal, area) = part.largest_flat_face();
+    // Rounding drops float noise; adding 0.0 turns -0.0 into 0.0.
+    (part.lay_flat(normal), normal.map(|n| (n * 1000.0).round() / 1000.0 + 0.0), area)
+}
+
+/// Stable fingerprint of a part's exported geometry.
+fn stl_hash(part: &Part) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    part.to_stl().unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positional_names_and_all_select_components() {
+        let mut cfg = config::load_config(None).unwrap();
+        cfg.components.insert("spool_holder".into(), false);
+        let names = |args: &[&str]| {
+            let cli = Cli::parse_from([&["vial-applicator-vcad"], args].concat());
+            components(&cli, &cfg).into_iter().map(|(name, _, _)| name).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&["vial_cradle", "peel_plate", "--component", "dancer_arm"]), ["peel_plate", "vial_cradle", "dancer_arm"]);
+        assert_eq!(names(&[]).len(), COMPONENTS.len() - 1);
+        assert_eq!(names(&["--all"]).len(), COMPONENTS.len());
+        assert!(Cli::try_parse_from(["vial-applicator-vcad", "--all", "peel_plate"]).is_err());
+        let cli = Cli::parse_from(["vial-applicator-vcad", "peel_plat"]);
+        assert_eq!(unknown_components(&cli, &cfg), ["peel_plat"]);
+    }
+
+    #[test]
+    fn output_dir_defaults_to_models_and_resolves_overrides() {
+        let cli = Cli::parse_from(["vial-applicator-vcad"]);
+        let models = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../models/vcad");
+        assert_eq!(output_dir(&cli).unwrap(), std::fs::canonicalize(models).unwrap().display().to_string());
+
+        let temp = std::env::temp_dir().join(format!("vialbel-output-{}", std::process::id()));
+        let cli = Cli::parse_from(["vial-applicator-vcad", "--output-dir", temp.join("nested").to_str().unwrap()]);
+        let dir = output_dir(&cli).unwrap();
+        assert!(std::path::Path::new(&dir).is_dir(), "missing directories are created");
+        assert_eq!(dir, std::fs::canonicalize(temp.join("nested")).unwrap().display().to_string());
+
+        // Relative paths resolve against the current directory.
+        let relative = std::path::Path::new("target").join(format!("vialbel-output-{}", std::process::id()));
+        let cli = Cli::parse_from(["vial-applicator-vcad", "--output-dir", relative.to_str().unwrap()]);
+        let dir = output_dir(&cli).unwrap();
+        assert!(std::path::Path::new(&dir).is_absolute());
+        assert_eq!(dir, std::env::current_dir().unwrap().join(&relative).canonicalize().unwrap().display().to_string());
+        std::fs::remove_dir_all(&relative).unwrap();
+        std::fs::remove_dir_all(&temp).unwrap();
+    }
+
+    #[test]
+    fn cache_dir_prefers_the_flag_and_stays_out_of_the_source_tree() {
+        let cli = Cli::parse_from(["vial-applicator-vcad", "--cache-dir", "/tmp/parts"]);
+        assert_eq!(cache_dir(&cli), Some(std::path::PathBuf::from("/tmp/parts")));
+        let cli = Cli::parse_from(["vial-applicator-vcad"]);
+        if let Some(dir) = cache_dir(&cli) {
+            assert!(dir.ends_with("vial-applicator-vcad"));
+            assert!(!dir.starts_with(env!("CARGO_MANIFEST_DIR")));
+        }
+    }
+
+    #[test]
+    fn check_bed_takes_three_sizes_before_the_component_names() {
+        let cli = Cli::parse_from(["vial-applicator-vcad", "--check-bed", "256", "256", "250", "peel_plate"]);
+        assert_eq!(cli.check_bed, Some(vec![256.0, 256.0, 250.0]));
+        assert_eq!(cli.names, ["peel_plate"]);
+        assert!(Cli::try_parse_from(["vial-applicator-vcad", "--check-bed", "256", "256"]).is_err());
+    }
+
+    #[test]
+    fn every_registered_component_builds() {
+        let mut cfg = config::load_config(None).unwrap();
+        cfg.print_in_place = true;
+        let all = all_components(&cfg);
+        assert_eq!(all.len(), COMPONENTS.len() + 1);
+        for (name, build_fn, bottom_fn) in all {
+            let part = build_fn(&cfg);
+            assert!(part.num_triangles() > 0, "{name} built no geometry");
+            let (min, max) = part.bounding_box();
+            assert!(min.iter().chain(&max).all(|v| v.is_finite()), "{name} has a non-finite bounding box");
+            assert!(bottom_fn(&cfg).is_finite(), "{name} has no finite bottom face");
+        }
+    }
+
+    #[test]
+    fn every_component_is_sized_by_its_config() {
+        let cfg = config::load_config(None).unwrap();
+        let nudged = cfg.nudged();
+        let fields = [
+            ("peel_plate", "peel_body_depth"),
+            ("vial_cradle", "cradle_v_block_height"),
+            ("main_frame", "frame_length"),
+            ("spool_holder", "spool_height"),
+            ("dancer_arm", "dancer_arm_length"),
+            ("guide_roller_bracket", "bracket_base_width"),
+        ];
+        for (name, _, _) in COMPONENTS {
+            assert!(fields.iter().any(|(n, _)| n == name), "no sizing field listed for {name}");
+        }
+        for (name, field) in fields {
+            let (_, build_fn, _) = COMPONENTS.iter().find(|(n, _, _)| *n == name).unwrap();
+            let changed = nudged.iter().find(|(key, _)| key == field).map(|(_, c)| c).unwrap();
+            assert_ne!(build_fn(&cfg).bounding_box(), build_fn(changed).bounding_box(), "{name} ignores {field}");
+        }
+    }
+
+    #[test]
+    fn mounting_hole_counts_match_the_fastener_bom() {
+        let cfg = config::load_config(None).unwrap();
+        // Frame: 4 corner + 4 cradle + 2 guide roller bracket holes.
+        let expected = [("peel_plate", 2), ("vial_cradle", 4), ("main_frame", 10), ("spool_holder", 1), ("dancer_arm", 0), ("guide_roller_bracket", 2)];
+        assert!(expected.iter().map(|(name, _)| name).eq(COMPONENTS.iter().map(|(name, _, _)| name)), "one count per component");
+        for ((name, count), (_, build_fn, _)) in expected.into_iter().zip(COMPONENTS) {
+            assert_eq!(features::count_tagged(features::Role::MountingHole, || build_fn(&cfg)), count, "{name} mounting holes");
+        }
+        let bom = bom_csv(&cfg, COMPONENTS);
+        assert!(bom.starts_with("component,fastener,quantity\npeel_plate,M3,2\n"), "{bom}");
+        assert!(bom.ends_with("total,M3,19\n"), "{bom}");
+    }
 }
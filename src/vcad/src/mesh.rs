@@ -0,0 +1,591 @@
+//! Triangle-mesh view of a `Part` for analysis and export.
+//!
+//! vcad keeps its geometry behind a manifold handle; this module pulls the
+//! indexed triangles out once so callers can walk vertices and faces directly.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use nalgebra::{Matrix4, Point3, Vector3, Vector4};
+use vcad::Part;
+
+/// Indexed triangle mesh with `f64` vertex positions.
+pub struct TriMesh {
+    pub vertices: Vec<Point3<f64>>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl TriMesh {
+    /// Extract the mesh of a part. Vertices shared between triangles keep a
+    /// single index, as produced by the manifold kernel.
+    pub fn from_part(part: &Part) -> Self {
+        let mesh = part.to_mesh();
+        let props = (mesh.num_props() as usize).max(3);
+        let vertices = mesh
+            .vertices()
+            .chunks(props)
+            .map(|v| Point3::new(v[0] as f64, v[1] as f64, v[2] as f64))
+            .collect();
+        let triangles = mesh
+            .indices()
+            .chunks(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .collect();
+        Self { vertices, triangles }
+    }
+
+    /// The three corner positions of triangle `tri`.
+    pub fn corners(&self, tri: usize) -> [Point3<f64>; 3] {
+        let [a, b, c] = self.triangles[tri];
+        [self.vertices[a], self.vertices[b], self.vertices[c]]
+    }
+
+    /// Outward unit normal of triangle `tri` (zero for degenerate triangles).
+    pub fn normal(&self, tri: usize) -> Vector3<f64> {
+        let [a, b, c] = self.corners(tri);
+        (b - a).cross(&(c - a)).try_normalize(1e-12).unwrap_or_else(Vector3::zeros)
+    }
+
+    /// Map each undirected edge `(lo, hi)` to the triangles that use it.
+    pub fn edge_faces(&self) -> HashMap<(usize, usize), Vec<usize>> {
+        let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (i, tri) in self.triangles.iter().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                edges.entry((a.min(b), a.max(b))).or_default().push(i);
+            }
+        }
+        edges
+    }
+
+    /// Axis-aligned bounds as `(min, max)`; both at the origin when empty.
+    pub fn bounds(&self) -> (Point3<f64>, Point3<f64>) {
+        let mut iter = self.vertices.iter();
+        let Some(&first) = iter.next() else {
+            return (Point3::origin(), Point3::origin());
+        };
+        iter.fold((first, first), |(min, max), p| (min.inf(p), max.sup(p)))
+    }
+
+    /// Enclosed volume: positive for an outward-facing shell, negative for
+    /// one wound inward (the wall of a cavity).
+    pub fn volume(&self) -> f64 {
+        (0..self.triangles.len())
+            .map(|tri| {
+                let [a, b, c] = self.corners(tri);
+                a.coords.dot(&b.coords.cross(&c.coords))
+            })
+            .sum::<f64>()
+            / 6.0
+    }
+
+    /// Split into connected pieces (triangles sharing a vertex stay together).
+    pub fn components(&self) -> Vec<TriMesh> {
+        // Union-find over vertex indices.
+        let mut parent: Vec<usize> = (0..self.vertices.len()).collect();
+        fn root(parent: &mut [usize], mut v: usize) -> usize {
+            while parent[v] != v {
+                parent[v] = parent[parent[v]];
+                v = parent[v];
+            }
+            v
+        }
+        for &[a, b, c] in &self.triangles {
+            for (x, y) in [(a, b), (b, c)] {
+                let (rx, ry) = (root(&mut parent, x), root(&mut parent, y));
+                parent[rx] = ry;
+            }
+        }
+
+        let mut pieces: HashMap<usize, Vec<[usize; 3]>> = HashMap::new();
+        for &tri in &self.triangles {
+            pieces.entry(root(&mut parent, tri[0])).or_default().push(tri);
+        }
+        let mut pieces: Vec<Vec<[usize; 3]>> = pieces.into_values().collect();
+        pieces.sort_by_key(|tris| tris.iter().flatten().min().copied());
+        pieces
+            .into_iter()
+            .map(|triangles| TriMesh { vertices: self.vertices.clone(), triangles }.compacted())
+            .collect()
+    }
+
+    /// Drop vertices no triangle uses, renumbering the rest.
+    fn compacted(self) -> TriMesh {
+        let mut remap = vec![usize::MAX; self.vertices.len()];
+        let mut vertices = Vec::new();
+        let mut index = |v: usize| {
+            if remap[v] == usize::MAX {
+                remap[v] = vertices.len();
+                vertices.push(self.vertices[v]);
+            }
+            remap[v]
+        };
+        let triangles = self.triangles.iter().map(|&[a, b, c]| [index(a), index(b), index(c)]).collect();
+        TriMesh { vertices, triangles }
+    }
+
+    /// Merge connected triangles whose normals are within `angle_epsilon_deg`
+    /// of each other into one polygon and re-triangulate it with no interior
+    /// vertices. Boundary vertices are kept so the mesh stays watertight;
+    /// regions with holes or a pinched boundary are left as they were.
+    pub fn merge_coplanar(&self, angle_epsilon_deg: f64) -> TriMesh {
+        let cos_limit = angle_epsilon_deg.to_radians().cos();
+        let edges = self.edge_faces();
+        let normals: Vec<Vector3<f64>> = (0..self.triangles.len()).map(|i| self.normal(i)).collect();
+
+        let mut visited = vec![false; self.triangles.len()];
+        let mut triangles = Vec::with_capacity(self.triangles.len());
+        for seed in 0..self.triangles.len() {
+            if visited[seed] {
+                continue;
+            }
+            // Compare against the seed normal so gently curved faces don't drift.
+            let normal = normals[seed];
+            visited[seed] = true;
+            let (mut members, mut stack) = (vec![seed], vec![seed]);
+            while let Some(tri) = stack.pop() {
+                for k in 0..3 {
+                    let (a, b) = (self.triangles[tri][k], self.triangles[tri][(k + 1) % 3]);
+                    for &other in &edges[&(a.min(b), a.max(b))] {
+                        if !visited[other] && normals[other].dot(&normal) >= cos_limit {
+                            visited[other] = true;
+                            members.push(other);
+                            stack.push(other);
+                        }
+                    }
+                }
+            }
+            match self.retriangulate(&members, &normal) {
+                Some(merged) => triangles.extend(merged),
+                None => triangles.extend(members.iter().map(|&t| self.triangles[t])),
+            }
+        }
+        TriMesh { vertices: self.vertices.clone(), triangles }
+    }
+
+    /// Ear-clip the boundary of a planar region, or `None` when it isn't a
+    /// single simple loop or clipping wouldn't reduce the triangle count.
+    fn retriangulate(&self, members: &[usize], normal: &Vector3<f64>) -> Option<Vec<[usize; 3]>> {
+        // Directed boundary edges are those whose reverse isn't in the region.
+        let directed: HashMap<(usize, usize), ()> = members
+            .iter()
+            .flat_map(|&t| {
+                let [a, b, c] = self.triangles[t];
+                [((a, b), ()), ((b, c), ()), ((c, a), ())]
+            })
+            .collect();
+        let mut next = HashMap::new();
+        for &(a, b) in directed.keys() {
+            if !directed.contains_key(&(b, a)) && next.insert(a, b).is_some() {
+                return None;
+            }
+        }
+        let start = *next.keys().next()?;
+        let mut ring = vec![start];
+        let mut v = next[&start];
+        while v != start {
+            ring.push(v);
+            v = *next.get(&v)?;
+            if ring.len() > next.len() {
+                return None;
+            }
+        }
+        if ring.len() != next.len() || ring.len() < 3 || members.len() <= ring.len() - 2 {
+            return None;
+        }
+
+        // Project onto the plane's dominant axes, flipped so the ring is CCW.
+        let axis = normal.iamax();
+        let (i, j) = ((axis + 1) % 3, (axis + 2) % 3);
+        let flip = if normal[axis] < 0.0 { -1.0 } else { 1.0 };
+        let pts: Vec<(f64, f64)> = ring.iter().map(|&v| (self.vertices[v][i] * flip, self.vertices[v][j])).collect();
+        let out = ear_clip(&pts)?;
+        Some(out.into_iter().map(|[a, b, c]| [ring[a], ring[b], ring[c]]).collect())
+    }
+
+    /// Collapse edges until at most `target` triangles remain, or no edge
+    /// can go without moving the surface more than about `tolerance` or
+    /// breaking the mesh. Each collapse moves one end of an edge onto the
+    /// other, cheapest first by quadric error (the summed squared distance
+    /// to the original planes of the faces merged into both ends), so flat
+    /// regions go before fine curves and corners are kept. Collapses that
+    /// would pinch the surface or fold a triangle over are skipped, keeping
+    /// the mesh closed and outward-facing.
+    pub fn decimate(&self, target: usize, tolerance: f64) -> TriMesh {
+        let mut triangles: Vec<Option<[usize; 3]>> = self.triangles.iter().copied().map(Some).collect();
+        let mut around: Vec<Vec<usize>> = vec![Vec::new(); self.vertices.len()];
+        let mut quadrics = vec![Matrix4::zeros(); self.vertices.len()];
+        for (tri, &corners) in self.triangles.iter().enumerate() {
+            let normal = self.normal(tri);
+            let plane = Vector4::new(normal.x, normal.y, normal.z, -normal.dot(&self.vertices[corners[0]].coords));
+            let quadric = plane * plane.transpose();
+            for v in corners {
+                around[v].push(tri);
+                quadrics[v] += quadric;
+            }
+        }
+        let cost = |quadric: &Matrix4<f64>, p: &Point3<f64>| {
+            let h = p.to_homogeneous();
+            h.dot(&(quadric * h))
+        };
+
+        let mut live = self.triangles.len();
+        while live > target {
+            // Costs go stale once either end of an edge changes, so each pass
+            // collapses each vertex at most once and then re-ranks.
+            let mut candidates: Vec<(f64, usize, usize)> = self
+                .edge_faces_of(&triangles)
+                .into_iter()
+                .flat_map(|(a, b)| [(a, b), (b, a)])
+                .map(|(from, to)| (cost(&(quadrics[from] + quadrics[to]), &self.vertices[to]), from, to))
+                .filter(|&(cost, _, _)| cost <= tolerance * tolerance)
+                .collect();
+            candidates.sort_by(|x, y| x.0.total_cmp(&y.0));
+            let mut touched = vec![false; self.vertices.len()];
+            let mut collapsed = 0;
+            for (_, from, to) in candidates {
+                if live <= target {
+                    break;
+                }
+                if touched[from] || touched[to] || !self.can_collapse(&triangles, &around, from, to) {
+                    continue;
+                }
+                for tri in std::mem::take(&mut around[from]) {
+                    let corners = triangles[tri].as_mut().expect("adjacency lists only live triangles");
+                    if corners.contains(&to) {
+                        for v in *corners {
+                            around[v].retain(|&t| t != tri);
+                        }
+                        triangles[tri] = None;
+                        live -= 1;
+                    } else {
+                        corners.iter_mut().filter(|v| **v == from).for_each(|v| *v = to);
+                        around[to].push(tri);
+                    }
+                }
+                quadrics[to] = quadrics[to] + quadrics[from];
+                for v in [from, to] {
+                    touched[v] = true;
+                }
+                collapsed += 1;
+            }
+            if collapsed == 0 {
+                break;
+            }
+        }
+        TriMesh { vertices: self.vertices.clone(), triangles: triangles.into_iter().flatten().collect() }.compacted()
+    }
+
+    /// Undirected edges of the live triangles, each once.
+    fn edge_faces_of(&self, triangles: &[Option<[usize; 3]>]) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = triangles
+            .iter()
+            .flatten()
+            .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    /// Whether moving vertex `from` onto `to` keeps the mesh a closed
+    /// manifold with no triangle flipped or flattened.
+    fn can_collapse(&self, triangles: &[Option<[usize; 3]>], around: &[Vec<usize>], from: usize, to: usize) -> bool {
+        let live = |tri: usize| triangles[tri].expect("adjacency lists only live triangles");
+        let shared: Vec<usize> = around[from].iter().copied().filter(|&t| live(t).contains(&to)).collect();
+        if shared.len() != 2 {
+            return false;
+        }
+        // The ends may only share the two neighbours across the edge, or the
+        // collapse would pinch the surface.
+        let neighbours = |v: usize| -> std::collections::HashSet<usize> { around[v].iter().flat_map(|&t| live(t)).filter(|&n| n != v).collect() };
+        let common = neighbours(from).intersection(&neighbours(to)).count();
+        if common != 2 {
+            return false;
+        }
+        around[from].iter().filter(|t| !shared.contains(t)).all(|&tri| {
+            let corners = live(tri);
+            let moved = corners.map(|v| if v == from { self.vertices[to] } else { self.vertices[v] });
+            let before = {
+                let [a, b, c] = corners.map(|v| self.vertices[v]);
+                (b - a).cross(&(c - a))
+            };
+            let after = (moved[1] - moved[0]).cross(&(moved[2] - moved[0]));
+            // Keep each face within ~25° of its old orientation and clear
+            // of degenerate slivers.
+            after.norm() > 1e-9 && after.normalize().dot(&before.normalize()) > 0.9
+        })
+    }
+
+    /// Binary STL bytes with `header` (truncated or space-padded to 80 bytes).
+    pub fn to_stl(&self, header: &str) -> Vec<u8> {
+        let mut stl = Vec::with_capacity(84 + 50 * self.triangles.len());
+        let mut head = String::new();
+        let _ = write!(head, "{:<80}", header);
+        stl.extend_from_slice(&head.as_bytes()[..80]);
+        stl.extend_from_slice(&(self.triangles.len() as u32).to_le_bytes());
+        for tri in 0..self.triangles.len() {
+            let n = self.normal(tri);
+            for value in [n.x, n.y, n.z] {
+                stl.extend_from_slice(&(value as f32).to_le_bytes());
+            }
+            for p in self.corners(tri) {
+                for value in [p.x, p.y, p.z] {
+                    stl.extend_from_slice(&(value as f32).to_le_bytes());
+                }
+            }
+            stl.extend_from_slice(&[0, 0]);
+        }
+        stl
+    }
+
+    /// ASCII STL text of the same facets as [`to_stl`](Self::to_stl), under
+    /// `solid <header>`. Coordinates are written as the shortest decimal
+    /// that reads back to the same `f32`, so nothing is lost against the
+    /// binary form.
+    pub fn to_stl_ascii(&self, header: &str) -> String {
+        let mut stl = format!("solid {}\n", header);
+        for tri in 0..self.triangles.len() {
+            let n = self.normal(tri);
+            let _ = writeln!(stl, "  facet normal {} {} {}", n.x as f32, n.y as f32, n.z as f32);
+            stl.push_str("    outer loop\n");
+            for p in self.corners(tri) {
+                let _ = writeln!(stl, "      vertex {} {} {}", p.x as f32, p.y as f32, p.z as f32);
+            }
+            stl.push_str("    endloop\n  endfacet\n");
+        }
+        let _ = writeln!(stl, "endsolid {}", header);
+        stl
+    }
+
+    /// Parse a binary or ASCII STL, welding corners at identical positions
+    /// into one vertex as the manifold kernel shares them. A file is read as
+    /// binary when its length matches the triangle count in its header, so
+    /// a binary header that happens to start with `solid` still parses.
+    pub fn from_stl(bytes: &[u8]) -> Result<Self, String> {
+        let binary_size = bytes.get(80..84).map(|count| 84 + 50 * u32::from_le_bytes(count.try_into().unwrap()) as usize);
+        let corners = if bytes.starts_with(b"solid") && binary_size != Some(bytes.len()) {
+            ascii_stl_corners(bytes)?
+        } else {
+            binary_stl_corners(bytes)?
+        };
+        let mut index: HashMap<[u32; 3], usize> = HashMap::new();
+        let mut vertices = Vec::new();
+        let triangles = corners
+            .into_iter()
+            .map(|tri| {
+                tri.map(|p| {
+                    *index.entry(p.map(f32::to_bits)).or_insert_with(|| {
+                        vertices.push(Point3::new(p[0] as f64, p[1] as f64, p[2] as f64));
+                        vertices.len() - 1
+                    })
+                })
+            })
+            .collect();
+        Ok(Self { vertices, triangles })
+    }
+
+    /// Normal at each corner of each triangle for smooth shading: the
+    /// angle-weighted average of the normals of the triangles sharing that
+    /// vertex, leaving out any that turn more than `crease_deg` from the
+    /// triangle's own, so box edges and cylinder rims stay sharp.
+    pub fn smooth_normals(&self, crease_deg: f64) -> Vec<[Vector3<f64>; 3]> {
+        let mut around: Vec<Vec<(Vector3<f64>, f64)>> = vec![Vec::new(); self.vertices.len()];
+        for (tri, corners) in self.triangles.iter().enumerate() {
+            let normal = self.normal(tri);
+            let p = self.corners(tri);
+            for k in 0..3 {
+                let angle = (p[(k + 1) % 3] - p[k]).angle(&(p[(k + 2) % 3] - p[k]));
+                around[corners[k]].push((normal, angle));
+            }
+        }
+        let min_cos = crease_deg.to_radians().cos();
+        (0..self.triangles.len())
+            .map(|tri| {
+                let own = self.normal(tri);
+                self.triangles[tri].map(|v| {
+                    let sum: Vector3<f64> = around[v].iter().filter(|(n, _)| n.dot(&own) >= min_cos).map(|(n, w)| n * *w).sum();
+                    sum.try_normalize(1e-12).unwrap_or(own)
+                })
+            })
+            .collect()
+    }
+
+    /// Wavefront OBJ text: one `v` per vertex, one `vn` per distinct normal
+    /// and one `f` per triangle (1-based, `v//vn`), under a comment `header`
+    /// and an object named `name`. `normals` gives each triangle's corner
+    /// normals.
+    pub fn to_obj(&self, header: &str, name: &str, normals: &[[Vector3<f64>; 3]]) -> String {
+        let mut obj = format!("# {}\no {}\n", header, name);
+        for v in &self.vertices {
+            let _ = writeln!(obj, "v {} {} {}", v.x as f32, v.y as f32, v.z as f32);
+        }
+        let mut index: HashMap<[u32; 3], usize> = HashMap::new();
+        let mut faces = String::new();
+        for (&[a, b, c], corners) in self.triangles.iter().zip(normals) {
+            let [na, nb, nc] = corners.map(|n| {
+                let key = [n.x as f32, n.y as f32, n.z as f32];
+                let next = index.len() + 1;
+                *index.entry(key.map(f32::to_bits)).or_insert_with(|| {
+                    let _ = writeln!(obj, "vn {} {} {}", key[0], key[1], key[2]);
+                    next
+                })
+            });
+            let _ = writeln!(faces, "f {}//{} {}//{} {}//{}", a + 1, na, b + 1, nb, c + 1, nc);
+        }
+        obj + &faces
+    }
+}
+
+/// Ear-clip a simple counter-clockwise polygon into triangles, as index
+/// triples into `pts`. Returns `None` when no ear can be found, as for
+/// self-intersecting or clockwise input.
+pub fn ear_clip(pts: &[(f64, f64)]) -> Option<Vec<[usize; 3]>> {
+    if pts.len() < 3 {
+        return None;
+    }
+    let cross = |a: (f64, f64), b: (f64, f64), c: (f64, f64)| (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+
+    let mut open: Vec<usize> = (0..pts.len()).collect();
+    let mut out = Vec::with_capacity(pts.len() - 2);
+    while open.len() > 3 {
+        let n = open.len();
+        let ear = (0..n).find(|&k| {
+            let (a, b, c) = (open[(k + n - 1) % n], open[k], open[(k + 1) % n]);
+            cross(pts[a], pts[b], pts[c]) > 1e-12
+                && !open.iter().any(|&p| {
+                    p != a
+                        && p != b
+                        && p != c
+                        && cross(pts[a], pts[b], pts[p]) >= -1e-9
+                        && cross(pts[b], pts[c], pts[p]) >= -1e-9
+                        && cross(pts[c], pts[a], pts[p]) >= -1e-9
+                })
+        })?;
+        out.push([open[(ear + n - 1) % n], open[ear], open[(ear + 1) % n]]);
+        open.remove(ear);
+    }
+    if cross(pts[open[0]], pts[open[1]], pts[open[2]]) <= 1e-12 {
+        return None;
+    }
+    out.push([open[0], open[1], open[2]]);
+    Some(out)
+}
+
+/// Corners of every facet of a binary STL.
+fn binary_stl_corners(bytes: &[u8]) -> Result<Vec<[[f32; 3]; 3]>, String> {
+    let count_bytes = bytes.get(80..84).ok_or("STL is shorter than its 84-byte header")?;
+    let count = u32::from_le_bytes(count_bytes.try_into().unwrap()) as usize;
+    if bytes.len() != 84 + 50 * count {
+        return Err(format!("STL declares {} triangles but holds {} bytes of them", count, bytes.len() - 84));
+    }
+    Ok(bytes[84..]
+        .chunks(50)
+        .map(|record| {
+            let value = |k: usize| f32::from_le_bytes(record[4 * k..4 * k + 4].try_into().unwrap());
+            // Skip the facet normal (values 0..3); corners are values 3..12.
+            [0, 1, 2].map(|c| [value(3 + 3 * c), value(4 + 3 * c), value(5 + 3 * c)])
+        })
+        .collect())
+}
+
+/// Corners of every facet of an ASCII STL: each run of three `vertex`
+/// lines, ignoring the normals and loop keywords around them.
+fn ascii_stl_corners(bytes: &[u8]) -> Result<Vec<[[f32; 3]; 3]>, String> {
+    let text = std::str::from_utf8(bytes).map_err(|e| format!("ASCII STL is not UTF-8: {}", e))?;
+    let mut points = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        if words.next() != Some("vertex") {
+            continue;
+        }
+        let coords: Vec<f32> = words.map(str::parse).collect::<Result<_, _>>().map_err(|e| format!("STL line {}: {}", line_no + 1, e))?;
+        let [x, y, z] = coords[..] else {
+            return Err(format!("STL line {}: a vertex needs three coordinates", line_no + 1));
+        };
+        points.push([x, y, z]);
+    }
+    if points.len() % 3 != 0 {
+        return Err(format!("ASCII STL has {} vertices, not a whole number of triangles", points.len()));
+    }
+    Ok(points.chunks(3).map(|c| [c[0], c[1], c[2]]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smooth_normals_blend_facets_but_keep_creases() {
+        let mesh = TriMesh::from_part(&vcad::centered_cylinder("c", 5.0, 4.0, 64));
+        let normals = mesh.smooth_normals(30.0);
+        for (tri, corners) in normals.iter().enumerate() {
+            let own = mesh.normal(tri);
+            for (k, n) in corners.iter().enumerate() {
+                if own.z.abs() > 0.9 {
+                    assert!((n - own).norm() < 1e-9, "cap corners keep the cap normal");
+                } else {
+                    let p = mesh.corners(tri)[k];
+                    let radial = Vector3::new(p.x, p.y, 0.0).normalize();
+                    assert!((n - radial).norm() < 1e-6, "side corners point straight out, got {n:?}");
+                }
+            }
+        }
+    }
+
+    /// Enclosed volume by the divergence theorem.
+    fn volume(mesh: &TriMesh) -> f64 {
+        (0..mesh.triangles.len())
+            .map(|t| {
+                let [a, b, c] = mesh.corners(t);
+                a.coords.dot(&b.coords.cross(&c.coords)) / 6.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn fan_around_an_interior_vertex_merges_to_two_triangles() {
+        let vertices = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(0.5, 0.5, 0.0),
+        ];
+        let triangles = vec![[0, 1, 4], [1, 2, 4], [2, 3, 4], [3, 0, 4]];
+        let merged = TriMesh { vertices, triangles }.merge_coplanar(0.01);
+        assert_eq!(merged.triangles.len(), 2);
+        assert!(merged.triangles.iter().all(|t| !t.contains(&4)));
+        assert!((0..2).all(|t| merged.normal(t).z > 0.99));
+    }
+
+    #[test]
+    fn decimation_meets_the_budget_and_stays_closed() {
+        let part = vcad::centered_cylinder("spindle", 8.0, 30.0, 128) + vcad::centered_cube("flange", 30.0, 30.0, 3.0).translate(0.0, 0.0, -15.0);
+        let mesh = TriMesh::from_part(&part);
+        let decimated = mesh.decimate(mesh.triangles.len() / 2, 0.1);
+        assert!(decimated.triangles.len() <= mesh.triangles.len() / 2, "{} triangles", decimated.triangles.len());
+        assert!(decimated.edge_faces().values().all(|faces| faces.len() == 2), "watertight");
+        assert!((volume(&decimated) - part.volume()).abs() < 0.05 * part.volume(), "volume {} vs {}", volume(&decimated), part.volume());
+        // Past the tolerance it stops short of the budget rather than
+        // flattening the flange.
+        let floor = mesh.decimate(20, 0.1);
+        assert!(floor.triangles.len() > 20 && (floor.bounds().0.z + 16.5).abs() < 1e-6);
+        // A box has nothing to spare: every collapse would cut a corner.
+        let cube = TriMesh::from_part(&vcad::centered_cube("cube", 10.0, 10.0, 10.0));
+        assert_eq!(cube.decimate(4, 0.1).triangles.len(), 12);
+    }
+
+    #[test]
+    fn merging_keeps_a_real_part_closed_and_its_volume() {
+        let plate = vcad::centered_cube("plate", 40.0, 20.0, 5.0)
+            - vcad::centered_cylinder("hole", 3.0, 7.0, 32).translate(10.0, 0.0, 0.0)
+            + vcad::centered_cube("rib", 30.0, 4.0, 5.0).translate(0.0, 0.0, 4.0);
+        let mesh = TriMesh::from_part(&plate);
+        let merged = mesh.merge_coplanar(0.01);
+        assert!(merged.triangles.len() <= mesh.triangles.len());
+        assert!((volume(&merged) - plate.volume()).abs() < 1e-3);
+        assert!(merged.edge_faces().values().all(|faces| faces.len() == 2));
+    }
+}
@@ -0,0 +1,508 @@
+//! Output file naming and writing.
+//!
+//! File names come from a template such as `"{component}_{profile}_v{version}"`
+//! so versioned parts-library artifacts don't need renaming by hand. Each
+//! part can be written as STL, OBJ and 3MF from a single build.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use vcad::Part;
+
+use crate::mesh::TriMesh;
+
+/// Normal tolerance (degrees) for merging coplanar triangles on export.
+pub const COPLANAR_EPSILON_DEG: f64 = 0.01;
+
+/// File formats a part can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Stl,
+    Obj,
+    #[value(name = "3mf")]
+    ThreeMf,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Stl => "stl",
+            Format::Obj => "obj",
+            Format::ThreeMf => "3mf",
+        }
+    }
+}
+
+/// How exported meshes that carry normals (OBJ) are shaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Shading {
+    /// One normal per triangle, faceted like the STL.
+    Flat,
+    /// Normals averaged across neighbouring triangles, except across edges
+    /// sharper than `SMOOTH_CREASE_DEG`.
+    Smooth,
+}
+
+/// Edges whose faces meet at more than this angle (degrees) stay sharp under
+/// smooth shading.
+pub const SMOOTH_CREASE_DEG: f64 = 30.0;
+
+/// Furthest decimation may move the surface of a part over its triangle
+/// budget (mm), well under a printed line width.
+pub const DECIMATE_TOLERANCE_MM: f64 = 0.1;
+
+/// Cap on the triangles written per part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriangleBudget {
+    pub max: usize,
+    /// Fail a part over `max` instead of decimating it.
+    pub strict: bool,
+}
+
+/// How STL files are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StlEncoding {
+    /// 50 bytes per triangle after an 80-byte header and a little-endian
+    /// triangle count.
+    Binary,
+    /// `facet` and `vertex` text: several times larger, but readable and
+    /// diffable.
+    Ascii,
+}
+
+/// How a part's mesh is prepared and encoded for export.
+#[derive(Debug, Clone, Copy)]
+pub struct MeshOptions {
+    /// Merge coplanar triangles first.
+    pub merge: bool,
+    /// Normals for formats that carry them.
+    pub shading: Shading,
+    pub budget: Option<TriangleBudget>,
+    pub stl: StlEncoding,
+}
+
+/// Display colors for assembly components without one in `[colors]`, taken
+/// in order.
+pub const FALLBACK_COLORS: [&str; 6] = ["#1F77B4", "#FF7F0E", "#2CA02C", "#D62728", "#9467BD", "#8C564B"];
+
+/// Key-value tags written into exported files that carry metadata (3MF).
+/// vcad's `Part` has no room for them, so they travel beside it. The
+/// `config` tag also goes in the STL and OBJ headers.
+pub type Metadata = BTreeMap<String, String>;
+
+/// Values substituted into the filename template.
+pub struct NameTokens<'a> {
+    pub component: &'a str,
+    pub profile: &'a str,
+}
+
+/// Render `template` into a safe file stem (no extension).
+///
+/// Supported tokens: `{component}`, `{profile}`, `{version}` (crate version)
+/// and `{units}` (always `mm`). A trailing `.stl` in the template is dropped
+/// since each writer adds its own extension. Characters outside
+/// `[A-Za-z0-9._-]` become `_`, and an empty result falls back to the
+/// component name.
+pub fn file_stem(template: &str, tokens: &NameTokens) -> String {
+    let template = template.strip_suffix(".stl").unwrap_or(template);
+    let rendered = template
+        .replace("{component}", tokens.component)
+        .replace("{profile}", tokens.profile)
+        .replace("{version}", env!("CARGO_PKG_VERSION"))
+        .replace("{units}", "mm");
+    let sanitized: String = rendered
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '_' })
+        .collect();
+    let sanitized = sanitized.trim_start_matches('.');
+    if sanitized.is_empty() {
+        tokens.component.to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+/// Write `part` for `component` to `<stem>.<ext>` in each of `formats`,
+/// refusing an empty mesh.
+///
+/// A cutter that engulfs the whole base (usually a bad config value) leaves
+/// nothing behind; writing that as a zero-triangle file hides the problem.
+/// The mesh is extracted (and with `options.merge`, its coplanar triangles
+/// merged) once and shared by every format. The STL is encoded per
+/// `options.stl`; its header (the 80-byte binary header or the ASCII
+/// `solid` line) and the OBJ's leading comment record the part's name and
+/// the `config` tag; the 3MF carries every tag in `metadata` and the OBJ carries normals
+/// per `options.shading`. A mesh still over `options.budget` after the
+/// merge is decimated toward it within `DECIMATE_TOLERANCE_MM`, or refused
+/// when the budget is strict. Returns the paths written and the number of
+/// triangles in each.
+pub fn write_part(part: &Part, stem: &str, formats: &[Format], component: &str, metadata: &Metadata, options: &MeshOptions) -> Result<(Vec<String>, usize), String> {
+    let mesh = export_mesh(part, stem, component, options)?;
+    let header = format!("{} config {}", part.name, metadata.get("config").map_or("", String::as_str));
+    let mut paths = Vec::new();
+    for &format in formats {
+        let path = format!("{}.{}", stem, format.extension());
+        let bytes = match format {
+            Format::Stl => match options.stl {
+                StlEncoding::Binary => mesh.to_stl(&header),
+                StlEncoding::Ascii => mesh.to_stl_ascii(&header).into_bytes(),
+            },
+            Format::Obj => mesh.to_obj(&header, &part.name, &normals(&mesh, options.shading)).into_bytes(),
+            Format::ThreeMf => three_mf(&part.name, &[(&part.name, &mesh, None)], metadata),
+        };
+        std::fs::write(&path, bytes).map_err(|e| format!("Failed to write {} {}: {}", component, format.extension(), e))?;
+        paths.push(path);
+    }
+    Ok((paths, mesh.triangles.len()))
+}
+
+/// Write `parts` to `{stem}.3mf` as one object per component, each with its
+/// own base material colored by `colors[component]`, or from
+/// `FALLBACK_COLORS` when unlisted, so a slicer keeps them apart. Meshes are
+/// prepared per part as in [`write_part`]. Returns the path written and the
+/// total number of triangles.
+pub fn export_3mf(parts: &[(&str, Part)], stem: &str, name: &str, colors: &BTreeMap<String, String>, metadata: &Metadata, options: &MeshOptions) -> Result<(String, usize), String> {
+    let meshes = parts.iter().map(|(component, part)| export_mesh(part, stem, component, options)).collect::<Result<Vec<_>, _>>()?;
+    let objects: Vec<(&str, &TriMesh, Option<&str>)> = parts
+        .iter()
+        .zip(&meshes)
+        .enumerate()
+        .map(|(i, ((component, _), mesh))| {
+            let color = colors.get(*component).map_or(FALLBACK_COLORS[i % FALLBACK_COLORS.len()], String::as_str);
+            (*component, mesh, Some(color))
+        })
+        .collect();
+    let path = format!("{}.3mf", stem);
+    std::fs::write(&path, three_mf(name, &objects, metadata)).map_err(|e| format!("Failed to write {} 3mf: {}", name, e))?;
+    Ok((path, meshes.iter().map(|mesh| mesh.triangles.len()).sum()))
+}
+
+/// Refuse to write `component` to `stem` when its boolean operations left
+/// nothing behind.
+fn ensure_nonempty(part: &Part, component: &str, stem: &str) -> Result<(), String> {
+    if part.is_empty() || part.num_triangles() == 0 {
+        return Err(format!(
+            "{} is empty after its boolean operations, refusing to write {}. A cutter likely \
+             covers the whole part; check the {} dimensions in config.toml (--debug-features \
+             exports each cutter).",
+            component, stem, component
+        ));
+    }
+    Ok(())
+}
+
+/// `part`'s mesh as exported: refused when empty, merged and held to the
+/// triangle budget per `options`.
+fn export_mesh(part: &Part, stem: &str, component: &str, options: &MeshOptions) -> Result<TriMesh, String> {
+    ensure_nonempty(part, component, stem)?;
+    let mut mesh = TriMesh::from_part(part);
+    if options.merge {
+        mesh = mesh.merge_coplanar(COPLANAR_EPSILON_DEG);
+    }
+    if let Some(budget) = options.budget.filter(|b| mesh.triangles.len() > b.max) {
+        if budget.strict {
+            return Err(format!(
+                "{} has {} triangles, over max_triangles ({}); raise it, lower the segment counts, or drop --strict to decimate",
+                component,
+                mesh.triangles.len(),
+                budget.max
+            ));
+        }
+        mesh = mesh.decimate(budget.max, DECIMATE_TOLERANCE_MM);
+    }
+    Ok(mesh)
+}
+
+/// Corner normals of every triangle of `mesh` for `shading`.
+fn normals(mesh: &TriMesh, shading: Shading) -> Vec<[nalgebra::Vector3<f64>; 3]> {
+    match shading {
+        Shading::Flat => (0..mesh.triangles.len()).map(|tri| [mesh.normal(tri); 3]).collect(),
+        Shading::Smooth => mesh.smooth_normals(SMOOTH_CREASE_DEG),
+    }
+}
+
+/// Write `part`'s mesh as STL, read it back, and check the result matches:
+/// same vertex and triangle counts after welding, and every triangle's
+/// corners in the same order within `f32` precision. Guards the binary
+/// writer and reader against byte-order and ordering mistakes.
+pub fn stl_round_trip(part: &Part) -> Result<usize, String> {
+    let mesh = TriMesh::from_part(part);
+    let back = TriMesh::from_stl(&mesh.to_stl(&part.name))?;
+    // The kernel can keep two vertices at one point where features touch
+    // (a hole tangent to an edge); STL can't tell them apart.
+    let positions: std::collections::HashSet<[u32; 3]> =
+        mesh.vertices.iter().map(|v| [v.x as f32, v.y as f32, v.z as f32].map(f32::to_bits)).collect();
+    if back.triangles.len() != mesh.triangles.len() || back.vertices.len() != positions.len() {
+        return Err(format!(
+            "wrote {} triangles over {} vertex positions, read back {} over {}",
+            mesh.triangles.len(),
+            positions.len(),
+            back.triangles.len(),
+            back.vertices.len()
+        ));
+    }
+    for tri in 0..mesh.triangles.len() {
+        let moved = mesh.corners(tri).iter().zip(back.corners(tri)).map(|(a, b)| (a - b).norm()).fold(0.0, f64::max);
+        if moved > 1e-5 {
+            return Err(format!("triangle {} moved {:.2e}mm through STL", tri, moved));
+        }
+    }
+    Ok(mesh.triangles.len())
+}
+
+/// A 3MF package titled `title` and tagged with `metadata`, holding each
+/// `(name, mesh, color)` in `objects` as its own object and build item.
+/// Colored objects reference a base material of that display color. 3MF
+/// reserves unprefixed metadata names for its own, so the tags go under a
+/// `vialbel:` namespace.
+fn three_mf(title: &str, objects: &[(&str, &TriMesh, Option<&str>)], metadata: &Metadata) -> Vec<u8> {
+    let mut model = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <model unit=\"millimeter\" xml:lang=\"en-US\" xmlns=\"http://schemas.microsoft.com/3dmanufacturing/core/2015/02\" xmlns:vialbel=\"urn:vialbel:metadata\">\n",
+    );
+    let _ = writeln!(model, "<metadata name=\"Title\">{}</metadata>", xml_escape(title));
+    for (key, value) in metadata {
+        let _ = writeln!(model, "<metadata name=\"vialbel:{}\">{}</metadata>", xml_escape(key), xml_escape(value));
+    }
+    model.push_str("<resources>\n");
+    // Materials are resource 1 when present; objects are numbered after.
+    let colored = objects.iter().any(|(_, _, color)| color.is_some());
+    let first_id = usize::from(colored) + 1;
+    if colored {
+        model.push_str("<basematerials id=\"1\">\n");
+        for (name, color) in objects.iter().filter_map(|(name, _, color)| Some((name, (*color)?))) {
+            let _ = writeln!(model, "<base name=\"{}\" displaycolor=\"{}\"/>", xml_escape(name), xml_escape(color));
+        }
+        model.push_str("</basematerials>\n");
+    }
+    let mut material = 0;
+    for (i, (name, mesh, color)) in objects.iter().enumerate() {
+        let _ = write!(model, "<object id=\"{}\" name=\"{}\" type=\"model\"", first_id + i, xml_escape(name));
+        if color.is_some() {
+            let _ = write!(model, " pid=\"1\" pindex=\"{}\"", material);
+            material += 1;
+        }
+        model.push_str(">\n<mesh>\n<vertices>\n");
+        for v in &mesh.vertices {
+            let _ = writeln!(model, "<vertex x=\"{}\" y=\"{}\" z=\"{}\"/>", v.x as f32, v.y as f32, v.z as f32);
+        }
+        model.push_str("</vertices>\n<triangles>\n");
+        for [a, b, c] in &mesh.triangles {
+            let _ = writeln!(model, "<triangle v1=\"{}\" v2=\"{}\" v3=\"{}\"/>", a, b, c);
+        }
+        model.push_str("</triangles>\n</mesh>\n</object>\n");
+    }
+    model.push_str("</resources>\n<build>\n");
+    for i in 0..objects.len() {
+        let _ = writeln!(model, "<item objectid=\"{}\"/>", first_id + i);
+    }
+    model.push_str("</build>\n</model>\n");
+
+    let content_types = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+        <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+        <Default Extension=\"model\" ContentType=\"application/vnd.ms-package.3dmanufacturing-3dmodel+xml\"/>\
+        </Types>\n";
+    let rels = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+        <Relationship Target=\"/3D/3dmodel.model\" Id=\"rel0\" Type=\"http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel\"/>\
+        </Relationships>\n";
+    zip_stored(&[
+        ("[Content_Types].xml", content_types.as_bytes()),
+        ("_rels/.rels", rels.as_bytes()),
+        ("3D/3dmodel.model", model.as_bytes()),
+    ])
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// A zip archive of `entries`, uncompressed ("stored"), which is all a 3MF
+/// package requires.
+pub fn zip_stored(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    // 1980-01-01 00:00, the earliest DOS date, keeps output reproducible.
+    const DOS_TIME: u16 = 0;
+    const DOS_DATE: u16 = 0x21;
+    let mut zip = Vec::new();
+    let mut central = Vec::new();
+    for &(name, data) in entries {
+        let offset = zip.len() as u32;
+        let crc = crc32fast::hash(data);
+        let common = |out: &mut Vec<u8>| {
+            for half in [20u16, 0, 0, DOS_TIME, DOS_DATE] {
+                out.extend_from_slice(&half.to_le_bytes());
+            }
+            for word in [crc, data.len() as u32, data.len() as u32] {
+                out.extend_from_slice(&word.to_le_bytes());
+            }
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes());
+        };
+
+        zip.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        common(&mut zip);
+        zip.extend_from_slice(name.as_bytes());
+        zip.extend_from_slice(data);
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes());
+        common(&mut central);
+        // Comment length, disk number, internal and external attributes.
+        central.extend_from_slice(&[0; 10]);
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+    let central_offset = zip.len() as u32;
+    zip.extend_from_slice(&central);
+    zip.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    for half in [0u16, 0, entries.len() as u16, entries.len() as u16] {
+        zip.extend_from_slice(&half.to_le_bytes());
+    }
+    zip.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    zip.extend_from_slice(&central_offset.to_le_bytes());
+    zip.extend_from_slice(&0u16.to_le_bytes());
+    zip
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAT: MeshOptions = MeshOptions { merge: true, shading: Shading::Flat, budget: None, stl: StlEncoding::Binary };
+
+    const TOKENS: NameTokens = NameTokens { component: "spool_holder", profile: "22mm" };
+
+    fn tags() -> Metadata {
+        Metadata::from([("config".to_string(), "0123456789ab".to_string()), ("material".to_string(), "PETG & co".to_string())])
+    }
+
+    #[test]
+    fn refuses_to_write_an_empty_part() {
+        let base = vcad::centered_cube("base", 10.0, 10.0, 2.0);
+        let cutter = vcad::centered_cube("cutter", 20.0, 20.0, 4.0);
+        let stem = std::env::temp_dir().join("vcad_empty_part");
+        let err = write_part(&(base - cutter), stem.to_str().unwrap(), &[Format::Stl], "peel_plate", &tags(), &FLAT).unwrap_err();
+        assert!(err.starts_with("peel_plate is empty"), "{}", err);
+        assert!(!stem.with_extension("stl").exists());
+    }
+
+    #[test]
+    fn header_records_part_name_and_config_hash() {
+        let stem = std::env::temp_dir().join("vcad_header_part");
+        let part = vcad::centered_cube("peel_plate", 10.0, 10.0, 2.0);
+        write_part(&part, stem.to_str().unwrap(), &[Format::Stl], "peel_plate", &tags(), &FLAT).unwrap();
+        let stl = std::fs::read(stem.with_extension("stl")).unwrap();
+        assert!(stl[..80].starts_with(b"peel_plate config 0123456789ab "));
+        std::fs::remove_file(stem.with_extension("stl")).unwrap();
+    }
+
+    #[test]
+    fn stl_is_binary_at_fifty_bytes_per_triangle() {
+        let stem = std::env::temp_dir().join("vcad_binary_part");
+        let flange = vcad::centered_cylinder("spool_holder", 30.0, 4.0, 64);
+        let (_, triangles) = write_part(&flange, stem.to_str().unwrap(), &[Format::Stl], "spool_holder", &tags(), &FLAT).unwrap();
+        let stl = std::fs::read(stem.with_extension("stl")).unwrap();
+        std::fs::remove_file(stem.with_extension("stl")).unwrap();
+        assert_eq!(stl.len(), 84 + 50 * triangles);
+        assert_eq!(u32::from_le_bytes(stl[80..84].try_into().unwrap()) as usize, triangles);
+        assert_eq!(TriMesh::from_stl(&stl).unwrap().triangles.len(), triangles);
+    }
+
+    #[test]
+    fn ascii_and_binary_stl_read_back_to_the_same_mesh() {
+        let flange = vcad::centered_cylinder("spool_holder", 30.0, 4.0, 64);
+        let mut meshes = Vec::new();
+        for (stl, stem) in [(StlEncoding::Binary, "vcad_stl_binary"), (StlEncoding::Ascii, "vcad_stl_ascii")] {
+            let stem = std::env::temp_dir().join(stem);
+            write_part(&flange, stem.to_str().unwrap(), &[Format::Stl], "spool_holder", &tags(), &MeshOptions { stl, ..FLAT }).unwrap();
+            let bytes = std::fs::read(stem.with_extension("stl")).unwrap();
+            std::fs::remove_file(stem.with_extension("stl")).unwrap();
+            meshes.push((bytes.len(), TriMesh::from_stl(&bytes).unwrap()));
+        }
+        let [(binary_len, binary), (ascii_len, ascii)] = &meshes[..] else { unreachable!() };
+        assert!(ascii_len > binary_len, "ASCII {ascii_len} bytes, binary {binary_len}");
+        assert_eq!(ascii.triangles.len(), binary.triangles.len());
+        assert_eq!(ascii.triangles, binary.triangles);
+        assert!(ascii.vertices.iter().zip(&binary.vertices).all(|(a, b)| a == b), "coordinates survive the text form exactly");
+    }
+
+    #[test]
+    fn obj_shares_vertices_between_triangles() {
+        let stem = std::env::temp_dir().join("vcad_obj_spool_holder");
+        let part = crate::spool_holder::build(&crate::config::load_config(None).unwrap());
+        let (_, triangles) = write_part(&part, stem.to_str().unwrap(), &[Format::Obj], "spool_holder", &tags(), &FLAT).unwrap();
+        let obj = std::fs::read_to_string(stem.with_extension("obj")).unwrap();
+        std::fs::remove_file(stem.with_extension("obj")).unwrap();
+        let vertices = obj.lines().filter(|l| l.starts_with("v ")).count();
+        // Triangle soup would write 3 vertices per triangle.
+        assert!(vertices < 3 * triangles, "{vertices} vertices for {triangles} triangles");
+        // A closed mesh shares each vertex between about six triangles.
+        assert!(vertices < triangles, "{vertices} vertices for {triangles} triangles");
+        assert!(obj.lines().filter(|l| l.starts_with("vn ")).count() < triangles, "facet normals are shared too");
+    }
+
+    #[test]
+    fn writes_every_requested_format_from_one_mesh() {
+        let stem = std::env::temp_dir().join("vcad_formats_part");
+        let part = vcad::centered_cube("peel_plate", 10.0, 10.0, 2.0);
+        let formats = [Format::Stl, Format::Obj, Format::ThreeMf];
+        let (paths, triangles) = write_part(&part, stem.to_str().unwrap(), &formats, "peel_plate", &tags(), &MeshOptions { shading: Shading::Smooth, ..FLAT }).unwrap();
+        assert_eq!(paths.len(), 3);
+
+        let obj = std::fs::read_to_string(stem.with_extension("obj")).unwrap();
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), triangles);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), 8);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("vn ")).count(), 6, "a box keeps its six face normals even when smooth");
+
+        let package = std::fs::read(stem.with_extension("3mf")).unwrap();
+        assert!(package.starts_with(b"PK\x03\x04"));
+        // Walk the central directory from the end record: three entries,
+        // each a signature followed by 46 bytes of header and its name.
+        let le32 = |at: usize| u32::from_le_bytes(package[at..at + 4].try_into().unwrap()) as usize;
+        let end = package.len() - 22;
+        assert!(package[end..].starts_with(b"PK\x05\x06"));
+        let mut entry = le32(end + 16);
+        for _ in 0..3 {
+            assert!(package[entry..].starts_with(b"PK\x01\x02"));
+            entry += 46 + u16::from_le_bytes([package[entry + 28], package[entry + 29]]) as usize;
+        }
+        assert_eq!(entry, end);
+        let model = String::from_utf8_lossy(&package);
+        assert_eq!(model.matches("<triangle ").count(), triangles);
+        assert!(model.contains("3D/3dmodel.model") && model.contains("name=\"peel_plate\""));
+        assert!(model.contains(r#"<metadata name="Title">peel_plate</metadata>"#));
+        assert!(model.contains(r#"<metadata name="vialbel:material">PETG &amp; co</metadata>"#));
+        for path in paths {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn stl_round_trips_simple_and_real_parts() {
+        let cfg = crate::config::load_config(None).unwrap();
+        for part in [
+            vcad::centered_cube("cube", 10.0, 10.0, 10.0),
+            vcad::centered_cylinder("cylinder", 5.0, 8.0, 64),
+            crate::spool_holder::build(&cfg),
+        ] {
+            let triangles = stl_round_trip(&part).unwrap_or_else(|e| panic!("{}: {}", part.name, e));
+            assert_eq!(triangles, part.num_triangles());
+        }
+        let mut stl = TriMesh::from_part(&vcad::centered_cube("cube", 1.0, 1.0, 1.0)).to_stl("cube");
+        stl.pop();
+        assert!(TriMesh::from_stl(&stl).is_err(), "truncated file");
+    }
+
+    #[test]
+    fn substitutes_all_tokens() {
+        let stem = file_stem("{component}_{profile}_v{version}_{units}.stl", &TOKENS);
+        assert_eq!(stem, format!("spool_holder_22mm_v{}_mm", env!("CARGO_PKG_VERSION")));
+    }
+
+    #[test]
+    fn sanitizes_path_separators_and_hidden_names() {
+        assert_eq!(file_stem("../{component} final", &TOKENS), "_spool_holder_final");
+        assert_eq!(file_stem("", &TOKENS), "spool_holder");
+    }
+}
@@ -1,32 +1,301 @@
 //! Peel plate — simplified CSG version.
 //!
 //! The Build123d version uses a complex wedge profile with BREP fillets.
-//! This vcad version approximates the shape with box primitives and
-//! boolean operations. No fillets (mesh-based geometry).
+//! This vcad version builds the wedge as a box with an angled box cut off
+//! its top, sloping from the rear face down to the label exit, and cuts the
+//! label channel parallel to that slope. No fillets (mesh-based geometry).
 
 use vcad::*;
 
 use crate::config::Config;
+use crate::features::{feature, tagged, Role};
+use crate::geometry::{self, CubeEdge, PartExt};
+
+/// Z of the bottom face of the body in build coordinates.
+pub fn base_bottom_z(cfg: &Config) -> f64 {
+    -cfg.peel_body_height_rear / 2.0
+}
+
+/// Radius the label's neutral axis follows around the leading edge, or
+/// `None` while the edge is left sharp.
+pub fn effective_bend_radius(cfg: &Config) -> Option<f64> {
+    (cfg.peel_edge_radius > 0.0).then(|| cfg.peel_edge_radius + cfg.label_thickness / 2.0)
+}
+
+/// Depth of the label channel cut into the top of the body.
+pub const CHANNEL_DEPTH: f64 = 1.5;
+
+/// Height of the body's front (+Y) face, where the label leaves the plate.
+pub fn front_height(cfg: &Config) -> f64 {
+    cfg.peel_body_height_front.unwrap_or(cfg.peel_body_height_rear)
+}
+
+/// Angle the top slopes down from the rear face to the front (degrees).
+pub fn top_slope_deg(cfg: &Config) -> f64 {
+    ((cfg.peel_body_height_rear - front_height(cfg)).max(0.0) / cfg.peel_body_depth).atan().to_degrees()
+}
+
+/// Z of the body's top surface at `y` in build coordinates.
+pub fn top_z(cfg: &Config, y: f64) -> f64 {
+    base_bottom_z(cfg) + front_height(cfg) + (cfg.peel_body_depth / 2.0 - y) * top_slope_deg(cfg).to_radians().tan()
+}
+
+/// Z of the label channel floor at `y`, `CHANNEL_DEPTH` under the top.
+pub fn channel_floor_z(cfg: &Config, y: f64) -> f64 {
+    top_z(cfg, y) - cfg.mm(CHANNEL_DEPTH)
+}
+
+/// Centers of the rear face mounting holes as `(x, z)`, empty when
+/// `peel_rear_holes` is off. They run along Y through the body.
+pub fn rear_hole_positions(cfg: &Config) -> Vec<(f64, f64)> {
+    let x = cfg.peel_mount_hole_spacing / 2.0;
+    if cfg.peel_rear_holes { vec![(-x, 0.0), (x, 0.0)] } else { Vec::new() }
+}
+
+/// Centers of the bottom face mounting holes as `(x, y)`, empty when
+/// `peel_bottom_holes` is off. They run `peel_bottom_hole_depth` up from
+/// the bottom face.
+pub fn bottom_hole_positions(cfg: &Config) -> Vec<(f64, f64)> {
+    let x = cfg.peel_bottom_hole_spacing / 2.0;
+    if cfg.peel_bottom_holes { vec![(-x, 0.0), (x, 0.0)] } else { Vec::new() }
+}
+
+/// Y centers of the stiffening ribs, spread evenly over the body depth;
+/// empty when `peel_rib_count` is 0.
+pub fn rib_positions(cfg: &Config) -> Vec<f64> {
+    let pitch = cfg.peel_body_depth / cfg.peel_rib_count as f64;
+    (0..cfg.peel_rib_count).map(|i| -cfg.peel_body_depth / 2.0 + pitch * (i as f64 + 0.5)).collect()
+}
+
+/// Side wall thickness either side of the label, `(-X, +X)`.
+pub fn side_walls(cfg: &Config) -> (f64, f64) {
+    (cfg.peel_wall_left.unwrap_or(cfg.wall_thickness), cfg.peel_wall_right.unwrap_or(cfg.wall_thickness))
+}
+
+/// X extent of the body, from the -X face to the +X face. The channel stays
+/// on the label path at X=0, so unequal walls shift the body off center.
+pub fn body_x_range(cfg: &Config) -> (f64, f64) {
+    let (left, right) = side_walls(cfg);
+    (-cfg.label_width / 2.0 - left, cfg.label_width / 2.0 + right)
+}
 
 pub fn build(cfg: &Config) -> Part {
     let channel_width = cfg.label_width + cfg.peel_channel_width_clearance;
-    let body_width = cfg.label_width + 2.0 * cfg.wall_thickness;
+    let (min_x, max_x) = body_x_range(cfg);
+    let (body_width, body_x) = (max_x - min_x, (min_x + max_x) / 2.0);
+
+    // Main body — a wedge, full height at the rear mounting face and
+    // sloping down to front_height at the label exit.
+    let mut body = feature(geometry::wedge("body", body_width, cfg.peel_body_depth, cfg.peel_body_height_rear, front_height(cfg)).translate(body_x, 0.0, 0.0));
+
+    // Leading edge — chamfer or round the front top corner the label peels
+    // over. The slope drops that corner below the body's top front edge, so
+    // the 45° chamfer is cut from that edge as much deeper; it still meets
+    // the front face `peel_edge_chamfer` below the corner. The round is cut
+    // in the YZ profile, since the slope leaves the corner obtuse.
+    if cfg.peel_edge_chamfer > 0.0 {
+        let drop = (cfg.peel_body_height_rear - front_height(cfg)).max(0.0);
+        body = body.chamfer_edge(CubeEdge::TopFront, cfg.peel_edge_chamfer + drop);
+    } else if let Some(edge) = leading_edge_cutter(cfg) {
+        body = body - feature(edge.rotate(90.0, 0.0, 0.0).rotate(0.0, 0.0, 90.0).translate(body_x, 0.0, 0.0));
+    }
+
+    // Channel cut — slot along the top for the label path, its floor
+    // parallel to the sloped top.
+    let reach = cfg.peel_body_depth + cfg.peel_body_height_rear;
+    let front = cfg.peel_body_depth / 2.0;
+    let channel = feature(geometry::slope_cutter("channel", channel_width, reach, front, channel_floor_z(cfg, front), top_slope_deg(cfg)));
+    let mut plate = body - channel;
+
+    // Ribs — hang below the bottom face across the channel width, reaching
+    // up into the body so the union doesn't meet on a coplanar face.
+    let rib_height = cfg.peel_rib_height + geometry::HOLE_OVERCUT;
+    for y in rib_positions(cfg) {
+        plate = plate + feature(centered_cube("rib", channel_width, cfg.peel_rib_thickness, rib_height)
+            .translate(0.0, y, base_bottom_z(cfg) - cfg.peel_rib_height + rib_height / 2.0));
+    }
+
+    // Mounting holes — clearance holes through the rear face, screwed in
+    // from the front (countersunk there when enabled), and/or blind holes
+    // up into the bottom face.
+    let hole_r = cfg.fastener().clearance / 2.0;
+    let segments = geometry::segments(cfg, cfg.segments_hole);
+    for (x, z) in rear_hole_positions(cfg) {
+        plate = plate - tagged(Role::MountingHole, geometry::mount_hole(cfg, "rear_hole", cfg.peel_body_depth)
+            .rotate(-90.0, 0.0, 0.0)
+            .translate(x, 0.0, z));
+    }
+    let depth = cfg.peel_bottom_hole_depth + geometry::HOLE_OVERCUT;
+    for (x, y) in bottom_hole_positions(cfg) {
+        plate = plate - tagged(Role::MountingHole, centered_cylinder("bottom_hole", hole_r, depth, segments)
+            .translate(x, y, base_bottom_z(cfg) - geometry::HOLE_OVERCUT + depth / 2.0));
+    }
+
+    plate.with_name("peel_plate")
+}
+
+/// Cutter for the leading edge round, built in the YZ profile plane
+/// (profile Y along X, Z along Y) and extruded through the body width along
+/// Z; `None` while the edge is left sharp.
+///
+/// The arc of `peel_edge_radius` is tangent to the front face and the
+/// sloped top, inside the corner.
+fn leading_edge_cutter(cfg: &Config) -> Option<Part> {
+    let r = cfg.peel_edge_radius;
+    if r <= 0.0 {
+        return None;
+    }
+    let front = cfg.peel_body_depth / 2.0;
+    let corner = nalgebra::Vector2::new(front, top_z(cfg, front));
+    // Down the front face, and back up the top towards the rear.
+    let slope = top_slope_deg(cfg).to_radians();
+    let (down, back) = (nalgebra::Vector2::new(0.0, -1.0), nalgebra::Vector2::new(-slope.cos(), slope.sin()));
+    // Outward normals of the front face and top, to push the cutter past them.
+    let e = geometry::HOLE_OVERCUT;
+    let (out_front, out_top) = (nalgebra::Vector2::new(e, 0.0), nalgebra::Vector2::new(slope.sin(), slope.cos()) * e);
+    let width = geometry::through_length(body_x_range(cfg).1 - body_x_range(cfg).0);
+    let point = |v: nalgebra::Vector2<f64>| (v.x, v.y);
+
+    // The arc's center sits on the corner's bisector, r from both faces.
+    let half = down.angle(&back) / 2.0;
+    let center = corner + (down + back).normalize() * (r / half.sin());
+    let (on_front, on_top) = (corner + down * (r / half.tan()), corner + back * (r / half.tan()));
+    let outline = [center, on_front, on_front + out_front, corner + out_front + out_top, on_top + out_top, on_top].map(point);
+    let round = centered_cylinder("edge_round", r, width + 2.0, geometry::segments(cfg, cfg.segments_cylinder)).translate(center.x, center.y, 0.0);
+    Some(geometry::extrude("edge_corner", &outline, width) - round)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::contains_point;
+    use crate::config::load_config;
+    use nalgebra::Point3;
+    use crate::mesh::TriMesh;
+
+    #[test]
+    fn mounting_holes_go_through_the_rear_and_blind_into_the_bottom() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.peel_bottom_holes = true;
+        cfg.peel_bottom_hole_spacing = 20.0;
+        let plate = build(&cfg);
+        let [(rear_x, rear_z), _] = rear_hole_positions(&cfg)[..] else { panic!("two rear holes") };
+        for y in [-cfg.peel_body_depth / 2.0 + 0.5, 0.0, cfg.peel_body_depth / 2.0 - 0.5] {
+            assert!(!contains_point(&plate, Point3::new(rear_x, y, rear_z)), "rear hole open at y={y}");
+        }
+        let [(bottom_x, bottom_y), _] = bottom_hole_positions(&cfg)[..] else { panic!("two bottom holes") };
+        let bottom = base_bottom_z(&cfg);
+        assert!(!contains_point(&plate, Point3::new(bottom_x, bottom_y, bottom + cfg.peel_bottom_hole_depth - 0.1)));
+        assert!(contains_point(&plate, Point3::new(bottom_x, bottom_y, bottom + cfg.peel_bottom_hole_depth + 0.1)), "bottom hole is blind");
+
+        cfg.peel_rear_holes = false;
+        assert!(contains_point(&build(&cfg), Point3::new(rear_x, 0.0, rear_z)));
+    }
+
+    #[test]
+    fn rear_holes_are_centered_where_documented() {
+        let cfg = load_config(None).unwrap();
+        let (_, features) = crate::features::capture(|| build(&cfg));
+        let mut centers: Vec<[f64; 3]> = features
+            .iter()
+            .filter(|f| crate::features::base_name(f) == "rear_hole")
+            .map(|f| {
+                let (min, max) = f.bounding_box();
+                [0, 1, 2].map(|k| (min[k] + max[k]) / 2.0)
+            })
+            .collect();
+        centers.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        // peel_mount_hole_spacing apart about the channel, halfway up the
+        // rear face, through the body depth centered on Y=0.
+        let x = cfg.peel_mount_hole_spacing / 2.0;
+        let (rear_bottom, rear_top) = (-cfg.peel_body_height_rear / 2.0, cfg.peel_body_height_rear / 2.0);
+        let z = (rear_bottom + rear_top) / 2.0;
+        let expected = [[-x, 0.0, z], [x, 0.0, z]];
+        assert_eq!(centers.len(), expected.len());
+        for (center, expected) in centers.iter().zip(expected) {
+            assert!((0..3).all(|k| (center[k] - expected[k]).abs() < 1e-6), "hole at {center:?}, expected {expected:?}");
+        }
+        assert_eq!(rear_hole_positions(&cfg), [(-x, z), (x, z)]);
+    }
+
+    #[test]
+    fn body_top_slopes_down_to_the_label_exit() {
+        let cfg = Config { peel_edge_chamfer: 0.0, peel_edge_radius: 0.0, ..load_config(None).unwrap() };
+        assert!(front_height(&cfg) < cfg.peel_body_height_rear);
+        let mesh = TriMesh::from_part(&build(&cfg));
+        // Highest vertex within a millimetre of each face, beside the channel.
+        let (min_x, _) = body_x_range(&cfg);
+        let top_near = |y: f64| {
+            mesh.vertices.iter().filter(|p| (p.y - y).abs() < 1.0 && p.x < min_x + 1.0).map(|p| p.z).fold(f64::MIN, f64::max)
+        };
+        let (front, rear) = (top_near(cfg.peel_body_depth / 2.0), top_near(-cfg.peel_body_depth / 2.0));
+        assert!((front - (base_bottom_z(&cfg) + front_height(&cfg))).abs() < 1e-5, "front edge at {front}");
+        assert!((rear - cfg.peel_body_height_rear / 2.0).abs() < 1e-5, "rear edge at {rear}");
+        assert!(front < rear);
+    }
 
-    // Main body — rectangular block (the wedge shape is approximated as a box
-    // since vcad doesn't have native wedge/loft operations).
-    let body = centered_cube("body", body_width, cfg.peel_body_depth, cfg.peel_body_height_rear);
+    #[test]
+    fn leading_edge_chamfer_trims_the_corner_within_the_same_box() {
+        let sharp = Config { peel_edge_chamfer: 0.0, ..load_config(None).unwrap() };
+        let cfg = Config { peel_edge_chamfer: 2.0, ..load_config(None).unwrap() };
+        let (plain, chamfered) = (build(&sharp), build(&cfg));
+        assert_eq!(plain.bounding_box(), chamfered.bounding_box());
+        assert!(chamfered.volume() < plain.volume());
+        // Just inside the front edge of the label channel floor.
+        let y = cfg.peel_body_depth / 2.0 - 0.1;
+        let floor = Point3::new(0.0, y, channel_floor_z(&cfg, y) - 0.1);
+        assert!(contains_point(&plain, floor));
+        assert!(!contains_point(&chamfered, floor), "the chamfer reaches the label path");
+        // At 45°, meeting the front face peel_edge_chamfer below the corner.
+        let (x, y) = (body_x_range(&cfg).0 + 0.5, cfg.peel_body_depth / 2.0 - 0.05);
+        let corner = top_z(&cfg, cfg.peel_body_depth / 2.0);
+        assert!(contains_point(&chamfered, Point3::new(x, y, corner - 2.1)));
+        assert!(!contains_point(&chamfered, Point3::new(x, y, corner - 1.9)));
+    }
 
-    // Channel cut — slot along the top for the label path.
-    let channel_depth = 1.5;
-    let channel = centered_cube("channel", channel_width, cfg.peel_body_depth + 2.0, channel_depth)
-        .translate(0.0, 0.0, cfg.peel_body_height_rear / 2.0 - channel_depth / 2.0);
+    #[test]
+    fn rear_holes_are_countersunk_from_the_front() {
+        let cfg = Config { countersink_mount_holes: true, peel_body_height_front: None, ..load_config(None).unwrap() };
+        let plate = build(&cfg);
+        let (x, z) = rear_hole_positions(&cfg)[0];
+        let rim_x = x + cfg.fastener().countersunk_head / 2.0 - 0.2;
+        let face = cfg.peel_body_depth / 2.0 - 0.05;
+        assert!(!contains_point(&plate, Point3::new(rim_x, face, z)), "countersink on the front face");
+        assert!(contains_point(&plate, Point3::new(rim_x, -face, z)), "plain bore out the rear face");
+    }
 
-    // Mounting holes — two M3 clearance holes on the rear face.
-    let hole = centered_cylinder("hole", cfg.mount_hole_diameter / 2.0, cfg.peel_body_depth + 2.0, 32);
-    let holes = hole
-        .translate(0.0, 0.0, 0.0)
-        .linear_pattern(cfg.peel_mount_hole_spacing, 0.0, 0.0, 2)
-        .translate(-cfg.peel_mount_hole_spacing / 2.0, 0.0, 0.0);
+    #[test]
+    fn ribs_hang_under_the_channel_only() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.peel_rib_count = 2;
+        let plate = build(&cfg);
+        let (min, _) = plate.bounding_box();
+        assert!((min[2] - (base_bottom_z(&cfg) - cfg.peel_rib_height)).abs() < 1e-5);
+        let below = base_bottom_z(&cfg) - cfg.peel_rib_height / 2.0;
+        let half = (cfg.label_width + cfg.peel_channel_width_clearance) / 2.0;
+        for y in rib_positions(&cfg) {
+            assert!(contains_point(&plate, Point3::new(half - 0.05, y, below)), "rib at y={y}");
+            assert!(!contains_point(&plate, Point3::new(half + 0.05, y, below)), "rib ends under the channel edge");
+        }
+        assert!(!contains_point(&plate, Point3::new(0.0, 0.0, below)), "open between the ribs");
+    }
 
-    body - channel - holes
+    #[test]
+    fn thicker_wall_shifts_the_body_but_not_the_channel() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.peel_wall_left = Some(8.0);
+        cfg.peel_wall_right = Some(3.0);
+        let plate = build(&cfg);
+        let (min, max) = plate.bounding_box();
+        assert!((min[0] + cfg.label_width / 2.0 + 8.0).abs() < 1e-5 && (max[0] - cfg.label_width / 2.0 - 3.0).abs() < 1e-5);
+        // Channel: label_width + clearance wide, centered on X=0.
+        let half = (cfg.label_width + cfg.peel_channel_width_clearance) / 2.0;
+        let z = top_z(&cfg, 0.0) - 0.5;
+        for x in [-half + 0.05, half - 0.05] {
+            assert!(!contains_point(&plate, Point3::new(x, 0.0, z)), "channel open at x={}", x);
+        }
+        for x in [-half - 0.05, half + 0.05] {
+            assert!(contains_point(&plate, Point3::new(x, 0.0, z)), "wall solid at x={}", x);
+        }
+    }
 }
@@ -0,0 +1,104 @@
+//! Dancer arm printed already captive on its pivot pin.
+//!
+//! The arm is built as usual and a pin is added through its pivot bore: a
+//! foot below the arm, a post through the bore and a cap above it. Every
+//! surface of the pin that faces the arm is held `print_in_place_gap` away,
+//! so the two print as one job but turn freely once off the bed.
+
+use vcad::*;
+
+use crate::config::Config;
+use crate::dancer_arm;
+use crate::features::{self, feature};
+use crate::geometry::{self, PartExt};
+
+/// Component name of the arm and pin printed together.
+pub const NAME: &str = "dancer_arm_in_place";
+
+/// How far the cap reaches past the pivot bore to retain the arm (mm).
+pub const CAP_LIP: f64 = 1.5;
+
+/// Height of the retaining cap (mm).
+pub const CAP_HEIGHT: f64 = 2.0;
+
+/// Z of the bottom face of the foot in build coordinates.
+pub fn base_bottom_z(cfg: &Config) -> f64 {
+    -cfg.dancer_arm_thickness / 2.0 - cfg.print_in_place_gap - cfg.base_thickness
+}
+
+/// The pin alone: foot, post and cap, centered on the arm's pivot and held
+/// `print_in_place_gap` off `arm` (the arm as built, pivot at `PIVOT_XY`).
+pub fn pin(cfg: &Config, arm: &Part) -> Part {
+    let gap = cfg.print_in_place_gap;
+    let (half_t, bore_r) = (cfg.dancer_arm_thickness / 2.0, cfg.pivot_bore / 2.0);
+    let hub_r = dancer_arm::pivot_hub_radius(cfg);
+    let hole_segments = geometry::segments(cfg, cfg.segments_hole);
+
+    // Modeled reaching `gap` into the arm on every mating face; cutting the
+    // overlap grown by `gap` back out leaves the pin `gap` clear of it.
+    let foot = feature(Part::cylinder("pin_foot", hub_r, cfg.base_thickness + 2.0 * gap, geometry::segments(cfg, cfg.segments_cylinder))
+        .translate(0.0, 0.0, base_bottom_z(cfg)));
+    let post = feature(geometry::centered_cylinder("pin_post", bore_r + gap, geometry::through_length(2.0 * half_t), hole_segments));
+    let cap = feature(Part::cylinder("pin_cap", bore_r + cfg.mm(CAP_LIP), cfg.mm(CAP_HEIGHT) + 2.0 * gap, hole_segments)
+        .translate(0.0, 0.0, half_t - gap));
+    let nominal = foot + post + cap;
+
+    // gap_between needs each overlap piece convex, but the overlap is a ring
+    // around the bore. Taken one bore facet's wedge at a time, its hull only
+    // fills the arm's side of the C-shaped section, never the pin's.
+    let (px, py) = dancer_arm::PIVOT_XY;
+    let reach = 2.0 * hub_r;
+    let height = geometry::through_length(2.0 * (half_t + gap + cfg.base_thickness + cfg.mm(CAP_HEIGHT)));
+    let arm = arm.translate(-px, -py, 0.0).clip_to_box([-reach, -reach, -half_t - 1.0], [reach, reach, half_t + 1.0]);
+    let step = std::f64::consts::TAU / hole_segments as f64;
+    let mut clearance = Part::empty("pin_clearance");
+    for k in 0..hole_segments {
+        let (a0, a1) = (k as f64 * step, (k + 1) as f64 * step);
+        let wedge = geometry::extrude("wedge", &[(0.0, 0.0), (reach * a0.cos(), reach * a0.sin()), (reach * a1.cos(), reach * a1.sin())], height);
+        clearance = clearance + geometry::gap_between(&(&nominal & &wedge), &arm, gap, hole_segments);
+    }
+    nominal - clearance
+}
+
+pub fn build(cfg: &Config) -> Part {
+    // The arm's own features stay with the dancer_arm component; here its
+    // pivot_hole is meant to be filled by the pin.
+    let (arm, _) = features::capture(|| dancer_arm::build(cfg));
+    let (px, py) = dancer_arm::PIVOT_XY;
+    let pin = pin(cfg, &arm).translate(px, py, 0.0);
+    (arm + pin).with_name(NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::ray_distance;
+    use crate::mesh::TriMesh;
+    use nalgebra::{Point3, Vector3};
+
+    #[test]
+    fn gap_to_the_arm_is_uniform_around_the_pin() {
+        let mut cfg = crate::config::load_config(None).unwrap();
+        cfg.print_in_place = true;
+        cfg.print_in_place_gap = 0.3;
+        let arm = dancer_arm::build(&cfg);
+        let pin = TriMesh::from_part(&pin(&cfg, &arm));
+        let arm = TriMesh::from_part(&arm);
+        let gap = cfg.print_in_place_gap;
+        let (half_t, bore_r) = (cfg.dancer_arm_thickness / 2.0, cfg.pivot_bore / 2.0);
+        let tolerance = 0.05;
+
+        // Radially, from the post surface out to the bore wall, all round.
+        for k in 0..12 {
+            let angle = (k as f64 * 30.0 + 7.0).to_radians();
+            let dir = Vector3::new(angle.cos(), angle.sin(), 0.0);
+            let radial = ray_distance(&arm, Point3::origin(), dir).unwrap() - ray_distance(&pin, Point3::origin(), dir).unwrap();
+            assert!((radial - gap).abs() < tolerance, "radial gap {:.3} at {}°", radial, k * 30 + 7);
+        }
+        // Axially, between the arm faces and the foot and cap.
+        let r = bore_r + CAP_LIP / 2.0;
+        let below = ray_distance(&pin, Point3::new(r, 0.0, -half_t), -Vector3::z()).unwrap();
+        let above = ray_distance(&pin, Point3::new(r, 0.0, half_t), Vector3::z()).unwrap();
+        assert!((below - gap).abs() < tolerance && (above - gap).abs() < tolerance, "below {:.3}, above {:.3}", below, above);
+    }
+}
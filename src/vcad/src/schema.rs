@@ -0,0 +1,267 @@
+//! JSON Schema for config.toml, printed by `--emit-schema`.
+//!
+//! Editors with TOML schema support (e.g. Even Better TOML in VS Code) use it
+//! for completion and to flag typos and wrong types. Field types are listed by
+//! hand; a test keeps the list in step with `Config`.
+
+use serde_json::{json, Map, Value};
+
+use crate::fasteners;
+
+/// JSON type of a config value.
+enum Kind {
+    Number,
+    Integer,
+    Bool,
+    String,
+    /// Array of `[x, y]` pairs.
+    Points,
+    /// A designation from `fasteners::FASTENERS`.
+    Fastener,
+    /// `center` or `bottom`.
+    OriginMode,
+    /// `v` or `saddle`.
+    CradleStyle,
+    /// `solid` or `ribbed`.
+    ArmProfile,
+    /// `x` or `y`.
+    SlotAxis,
+}
+
+/// Every `[default]` key in `Config` field order, with its type and whether
+/// it must be given (no serde default).
+const FIELDS: &[(&str, Kind, bool)] = &[
+    ("vial_diameter", Kind::Number, true),
+    ("vial_height", Kind::Number, true),
+    ("label_width", Kind::Number, true),
+    ("label_height", Kind::Number, true),
+    ("label_offset_from_bottom", Kind::Number, true),
+    ("label_thickness", Kind::Number, true),
+    ("min_bend_radius", Kind::Number, true),
+    ("wall_thickness", Kind::Number, true),
+    ("base_thickness", Kind::Number, true),
+    ("mount_hole_diameter", Kind::Number, false),
+    ("mount_fastener", Kind::Fastener, false),
+    ("fillet_radius", Kind::Number, true),
+    ("hole_exit_chamfer", Kind::Number, false),
+    ("countersink_mount_holes", Kind::Bool, false),
+    ("insert_od", Kind::Number, false),
+    ("insert_depth", Kind::Number, false),
+    ("frame_length", Kind::Number, true),
+    ("frame_width", Kind::Number, true),
+    ("frame_wall_height", Kind::Number, true),
+    ("frame_wall_thickness", Kind::Number, true),
+    ("frame_pockets", Kind::Bool, false),
+    ("frame_pocket_size", Kind::Number, false),
+    ("frame_pocket_pitch", Kind::Number, false),
+    ("frame_pocket_floor", Kind::Number, false),
+    ("frame_standoffs", Kind::Bool, false),
+    ("frame_standoff_od", Kind::Number, false),
+    ("frame_standoff_height", Kind::Number, false),
+    ("frame_standoff_positions", Kind::Points, false),
+    ("keep_out_margin", Kind::Number, false),
+    ("peel_channel_width_clearance", Kind::Number, true),
+    ("peel_body_depth", Kind::Number, true),
+    ("peel_body_height_rear", Kind::Number, true),
+    ("peel_body_height_front", Kind::Number, false),
+    ("peel_mount_hole_spacing", Kind::Number, true),
+    ("peel_rear_holes", Kind::Bool, false),
+    ("peel_bottom_holes", Kind::Bool, false),
+    ("peel_bottom_hole_spacing", Kind::Number, false),
+    ("peel_bottom_hole_depth", Kind::Number, false),
+    ("peel_rib_count", Kind::Integer, false),
+    ("peel_rib_thickness", Kind::Number, false),
+    ("peel_rib_height", Kind::Number, false),
+    ("peel_edge_radius", Kind::Number, false),
+    ("peel_edge_chamfer", Kind::Number, false),
+    ("peel_wall_left", Kind::Number, false),
+    ("peel_wall_right", Kind::Number, false),
+    ("cradle_base_height", Kind::Number, true),
+    ("cradle_v_block_height", Kind::Number, true),
+    ("cradle_mount_slot_spacing_x", Kind::Number, true),
+    ("cradle_mount_slot_spacing_y", Kind::Number, true),
+    ("vial_cradle_clearance", Kind::Number, false),
+    ("cradle_style", Kind::CradleStyle, false),
+    ("cradle_vial_rise", Kind::Number, false),
+    ("cradle_end_clearance", Kind::Number, false),
+    ("cradle_base_margin", Kind::Number, false),
+    ("cradle_hole_edge_margin", Kind::Number, false),
+    ("cradle_slot_travel", Kind::Number, false),
+    ("cradle_slot_axis", Kind::SlotAxis, false),
+    ("cradle_window", Kind::Bool, false),
+    ("cradle_window_width", Kind::Number, false),
+    ("cradle_window_height", Kind::Number, false),
+    ("cradle_window_offset", Kind::Number, false),
+    ("spool_spindle_od", Kind::Number, true),
+    ("spool_flange_diameter", Kind::Number, true),
+    ("spool_flange_thickness", Kind::Number, true),
+    ("spool_height", Kind::Number, true),
+    ("spool_spindle_chamfer", Kind::Number, false),
+    ("spool_spindle_draft", Kind::Number, false),
+    ("spool_flange_bolt_circle", Kind::Bool, false),
+    ("spool_bolt_circle_count", Kind::Integer, false),
+    ("spool_bolt_circle_diameter", Kind::Number, false),
+    ("dancer_arm_length", Kind::Number, true),
+    ("dancer_arm_width", Kind::Number, true),
+    ("dancer_arm_thickness", Kind::Number, true),
+    ("dancer_arm_profile", Kind::ArmProfile, false),
+    ("dancer_arm_rib_web", Kind::Number, false),
+    ("dancer_arm_rib_rim", Kind::Number, false),
+    ("dancer_spring_hole_x", Kind::Number, false),
+    ("dancer_spring_hole_offset", Kind::Number, false),
+    ("dancer_spring_hole_diameter", Kind::Number, false),
+    ("dancer_spring_hole_2_x", Kind::Number, false),
+    ("dancer_spring_inserts", Kind::Bool, false),
+    ("dancer_roller_counterbore_diameter", Kind::Number, false),
+    ("dancer_roller_counterbore_depth", Kind::Number, false),
+    ("pivot_bore", Kind::Number, true),
+    ("bearing_od", Kind::Number, true),
+    ("bearing_id", Kind::Number, true),
+    ("bracket_base_width", Kind::Number, true),
+    ("bracket_base_depth", Kind::Number, true),
+    ("bracket_height", Kind::Number, true),
+    ("bracket_bearing_clearance", Kind::Number, false),
+    ("bracket_auto_height", Kind::Bool, false),
+    ("bracket_mount_hole_count", Kind::Integer, false),
+    ("bracket_mount_hole_spacing", Kind::Number, false),
+    ("bracket_pin_hole_offset", Kind::Number, false),
+    ("pivot_post_height", Kind::Number, true),
+    ("pivot_post_bore", Kind::Number, false),
+    ("pivot_post_bore_depth", Kind::Number, false),
+    ("pivot_post_draft", Kind::Number, false),
+    ("pivot_reinforce_radius", Kind::Number, false),
+    ("pivot_reinforce_height", Kind::Number, false),
+    ("print_in_place", Kind::Bool, false),
+    ("print_in_place_gap", Kind::Number, false),
+    ("filename_template", Kind::String, false),
+    ("quick", Kind::Bool, false),
+    ("segments_hole", Kind::Integer, false),
+    ("segments_cylinder", Kind::Integer, false),
+    ("segments_flange", Kind::Integer, false),
+    ("max_triangles", Kind::Integer, false),
+    ("model_scale", Kind::Number, false),
+    ("material_density", Kind::Number, false),
+    ("origin_mode", Kind::OriginMode, false),
+];
+
+/// Keys the vcad parts still accept but ignore, with what to use instead.
+const DEPRECATED: &[(&str, &str)] = &[(
+    "mount_hole_diameter",
+    "Deprecated: vcad parts size mounting holes from mount_fastener. Only the build123d scripts read this.",
+)];
+
+fn kind_schema(kind: &Kind) -> Value {
+    match kind {
+        Kind::Number => json!({ "type": "number" }),
+        Kind::Integer => json!({ "type": "integer", "minimum": 0 }),
+        Kind::Bool => json!({ "type": "boolean" }),
+        Kind::String => json!({ "type": "string" }),
+        Kind::Points => json!({
+            "type": "array",
+            "items": { "type": "array", "items": { "type": "number" }, "minItems": 2, "maxItems": 2 }
+        }),
+        Kind::Fastener => json!({ "enum": fasteners::FASTENERS.iter().map(|f| f.designation).collect::<Vec<_>>() }),
+        Kind::OriginMode => json!({ "enum": ["center", "bottom"] }),
+        Kind::CradleStyle => json!({ "enum": ["v", "saddle"] }),
+        Kind::ArmProfile => json!({ "enum": ["solid", "ribbed"] }),
+        Kind::SlotAxis => json!({ "enum": ["x", "y"] }),
+    }
+}
+
+/// For a numeric `[default]` key, whether it takes whole numbers; `None`
+/// for any other key. Environment overrides resolve their field with this.
+pub fn numeric_field(name: &str) -> Option<bool> {
+    FIELDS.iter().find(|(field, ..)| *field == name).and_then(|(_, kind, _)| match kind {
+        Kind::Number => Some(false),
+        Kind::Integer => Some(true),
+        _ => None,
+    })
+}
+
+/// Schema for the whole file: `[default]` with every field, `[components]`
+/// with one flag per name in `components`, `[colors]` with one hex color per
+/// name, and `[profiles.*]` overriding any subset of them.
+pub fn config_schema(components: &[&str]) -> Value {
+    let mut properties: Map<String, Value> = FIELDS.iter().map(|(name, kind, _)| (name.to_string(), kind_schema(kind))).collect();
+    for (name, note) in DEPRECATED {
+        if let Some(Value::Object(field)) = properties.get_mut(*name) {
+            field.insert("deprecated".into(), true.into());
+            field.insert("description".into(), (*note).into());
+        }
+    }
+    let required: Vec<&str> = FIELDS.iter().filter(|(_, _, required)| *required).map(|(name, _, _)| *name).collect();
+    let flags: Map<String, Value> = components.iter().map(|name| (name.to_string(), json!({ "type": "boolean" }))).collect();
+    let flags = json!({ "type": "object", "properties": flags, "additionalProperties": false });
+    let colors: Map<String, Value> =
+        components.iter().map(|name| (name.to_string(), json!({ "type": "string", "pattern": "^#([0-9A-Fa-f]{6}|[0-9A-Fa-f]{8})$" }))).collect();
+    let colors = json!({ "type": "object", "properties": colors, "additionalProperties": false });
+    let mut overrides = properties.clone();
+    overrides.insert("components".into(), flags.clone());
+    overrides.insert("colors".into(), colors.clone());
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Vial label applicator config.toml",
+        "type": "object",
+        "properties": {
+            "default": { "type": "object", "properties": properties, "required": required, "additionalProperties": false },
+            "components": flags,
+            "colors": colors,
+            "profiles": {
+                "type": "object",
+                "additionalProperties": { "type": "object", "properties": overrides, "additionalProperties": false }
+            }
+        },
+        "required": ["default"],
+        "additionalProperties": false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{load_config, Config};
+
+    #[test]
+    fn fields_match_config() {
+        let value = serde_json::to_value(load_config(None).unwrap()).unwrap();
+        let mut keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).filter(|k| !["components", "colors"].contains(k)).collect();
+        let mut listed: Vec<&str> = FIELDS.iter().map(|(name, _, _)| *name).collect();
+        keys.sort();
+        listed.sort();
+        assert_eq!(keys, listed);
+
+        // Exactly the required fields are enough to load a config.
+        let defaults = toml::Table::try_from(load_config(None).unwrap()).unwrap();
+        let required: toml::Table = defaults.into_iter().filter(|(k, _)| FIELDS.iter().any(|(name, _, r)| name == k && *r)).collect();
+        assert!(required.clone().try_into::<Config>().is_ok());
+        for (name, _, _) in FIELDS.iter().filter(|(_, _, r)| *r) {
+            let mut missing = required.clone();
+            missing.remove(*name);
+            assert!(missing.try_into::<Config>().is_err(), "{} is listed as required but has a default", name);
+        }
+    }
+
+    #[test]
+    fn deprecated_fields_are_optional_and_marked() {
+        let schema = config_schema(&[]);
+        let default = &schema["properties"]["default"];
+        for (name, _) in DEPRECATED {
+            assert_eq!(default["properties"][*name]["deprecated"], true, "{} not marked deprecated", name);
+            assert!(!default["required"].as_array().unwrap().iter().any(|r| r == *name), "{} is still required", name);
+        }
+    }
+
+    #[test]
+    fn project_config_uses_only_known_keys() {
+        let schema = config_schema(&[]);
+        let content = std::fs::read_to_string(crate::config::resolve_config_path()).unwrap();
+        let file: toml::Table = toml::from_str(&content).unwrap();
+        for key in file.keys() {
+            assert!(schema["properties"].get(key).is_some(), "unknown table [{}]", key);
+        }
+        let known = &schema["properties"]["default"]["properties"];
+        for key in file["default"].as_table().unwrap().keys() {
+            assert!(known.get(key).is_some(), "[default] key {} missing from the schema", key);
+        }
+    }
+}
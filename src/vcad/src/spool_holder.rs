@@ -7,17 +7,108 @@
 use vcad::*;
 
 use crate::config::Config;
+use crate::features::{feature, tagged, Role};
+use crate::geometry::{self, PartExt};
+
+/// Z of the bottom face of the flange in build coordinates.
+pub fn base_bottom_z(cfg: &Config) -> f64 {
+    -cfg.spool_flange_thickness / 2.0
+}
+
+/// Holes on the bolt circle `spool_flange_bolt_circle` switches on.
+pub const FLANGE_BOLT_CIRCLE_HOLES: usize = 3;
+
+/// Number of holes on the flange bolt circle: three when
+/// `spool_flange_bolt_circle` is set, otherwise `spool_bolt_circle_count`.
+/// Zero means the single center hole.
+pub fn bolt_circle_count(cfg: &Config) -> usize {
+    if cfg.spool_flange_bolt_circle {
+        FLANGE_BOLT_CIRCLE_HOLES
+    } else {
+        cfg.spool_bolt_circle_count
+    }
+}
+
+/// XY centers of the flange mounting holes in the holder's own coordinates:
+/// the bolt circle from +X, or the single center hole.
+pub fn mount_hole_positions(cfg: &Config) -> Vec<(f64, f64)> {
+    match bolt_circle_count(cfg) {
+        0 => vec![(0.0, 0.0)],
+        count => geometry::polar_positions(count, cfg.spool_bolt_circle_diameter / 2.0, 0.0),
+    }
+}
 
 pub fn build(cfg: &Config) -> Part {
     // Base flange
-    let flange = centered_cylinder("flange", cfg.spool_flange_diameter / 2.0, cfg.spool_flange_thickness, 64);
+    let flange = feature(centered_cylinder("flange", cfg.spool_flange_diameter / 2.0, cfg.spool_flange_thickness, geometry::segments(cfg, cfg.segments_flange)));
 
     // Spindle on top of flange
-    let spindle = centered_cylinder("spindle", cfg.spool_spindle_od / 2.0, cfg.spool_height, 64)
-        .translate(0.0, 0.0, (cfg.spool_flange_thickness + cfg.spool_height) / 2.0);
+    let spindle_r = cfg.spool_spindle_od / 2.0;
+    let spindle_top_r = geometry::drafted_radius(spindle_r, cfg.spool_height, cfg.spool_spindle_draft);
+    let spindle = feature(geometry::centered_cone("spindle", spindle_r, spindle_top_r, cfg.spool_height, geometry::segments(cfg, cfg.segments_cylinder))
+        .stack_on(&flange, 0.0));
+
+    // Lead-in chamfer on the spindle top so the spool slides on. Applied
+    // before the holes are cut so they stay where they were placed.
+    let spindle_top = cfg.spool_flange_thickness / 2.0 + cfg.spool_height;
+    let body = geometry::bevel_top_edge(&(flange + spindle), spindle_top_r, spindle_top, cfg.spool_spindle_chamfer, geometry::segments(cfg, cfg.segments_cylinder));
+
+    // M3 mounting holes — a bolt circle when configured, otherwise a single
+    // hole down the center, through the spindle so the screw can reach it.
+    let holes = match bolt_circle_count(cfg) {
+        0 => tagged(Role::MountingHole, geometry::mount_hole(cfg, "hole", cfg.spool_flange_thickness + cfg.spool_height).translate(0.0, 0.0, cfg.spool_height / 2.0)),
+        count => {
+            let hole = geometry::mount_hole(cfg, "hole", cfg.spool_flange_thickness);
+            tagged(Role::MountingHole, hole.polar_pattern(count, cfg.spool_bolt_circle_diameter / 2.0, 0.0).with_name("bolt_circle"))
+        }
+    };
+
+    (body - holes).with_name("spool_holder")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::{self, base_name};
+
+    #[test]
+    fn spindle_sits_on_the_flange() {
+        let cfg = crate::config::load_config(None).unwrap();
+        let (_, parts) = features::capture(|| build(&cfg));
+        let z = |name: &str| parts.iter().find(|p| base_name(p) == name).map(|p| p.bounding_box()).unwrap();
+        let ((_, flange_top), (spindle_bottom, spindle_top)) = (z("flange"), z("spindle"));
+        assert!((spindle_bottom[2] - flange_top[2]).abs() < 1e-6, "spindle bottom {} vs flange top {}", spindle_bottom[2], flange_top[2]);
+        assert!((spindle_top[2] - (base_bottom_z(&cfg) + cfg.spool_flange_thickness + cfg.spool_height)).abs() < 1e-5);
+    }
 
-    // M3 mounting hole through center
-    let hole = centered_cylinder("hole", cfg.mount_hole_diameter / 2.0, cfg.spool_flange_thickness + 2.0, 32);
+    #[test]
+    fn bolt_circle_holes_are_equidistant_from_center() {
+        let cfg = Config { spool_flange_bolt_circle: true, spool_bolt_circle_count: 0, ..crate::config::load_config(None).unwrap() };
+        let (_, parts) = features::capture(|| build(&cfg));
+        let circle = parts.iter().find(|p| base_name(p) == "bolt_circle").expect("bolt circle cutter");
+        let centers: Vec<(f64, f64)> = crate::mesh::TriMesh::from_part(circle)
+            .components()
+            .iter()
+            .map(|hole| {
+                let (min, max) = hole.bounds();
+                ((min.x + max.x) / 2.0, (min.y + max.y) / 2.0)
+            })
+            .collect();
+        assert_eq!(centers.len(), 3);
+        let r = cfg.spool_bolt_circle_diameter / 2.0;
+        for &(x, y) in &centers {
+            assert!((x.hypot(y) - r).abs() < 0.05, "hole at ({x:.3}, {y:.3}) is not {r}mm from center");
+            assert!(mount_hole_positions(&cfg).iter().any(|&(px, py)| (px - x).hypot(py - y) < 0.05), "hole at ({x:.3}, {y:.3}) isn't a documented position");
+        }
+        let gap = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).hypot(a.1 - b.1);
+        let spacing = [gap(centers[0], centers[1]), gap(centers[1], centers[2]), gap(centers[2], centers[0])];
+        assert!(spacing.iter().all(|s| (s - spacing[0]).abs() < 0.05), "uneven spacing {spacing:?}");
+    }
 
-    (flange + spindle) - hole
+    #[test]
+    fn without_the_flag_the_count_picks_the_holes() {
+        let with_count = |count| Config { spool_flange_bolt_circle: false, spool_bolt_circle_count: count, ..crate::config::load_config(None).unwrap() };
+        assert_eq!(mount_hole_positions(&with_count(0)), vec![(0.0, 0.0)]);
+        assert_eq!(mount_hole_positions(&with_count(5)).len(), 5);
+    }
 }
@@ -0,0 +1,742 @@
+//! Config validation — cross-field checks on the geometry the builders derive.
+//!
+//! Mirrors `src/config_validator.py`: every problem is collected so a bad
+//! config reports all of its issues at once instead of failing on the first.
+
+use crate::config::{ArmProfile, Config, CradleStyle};
+use crate::dancer_arm;
+use crate::fasteners;
+use crate::frame;
+use crate::geometry;
+use crate::guide_roller_bracket;
+use crate::peel_plate;
+use crate::spool_holder;
+use crate::vial_cradle;
+
+/// Smallest clearance that reliably prints open between moving parts (mm).
+pub const MIN_PRINT_GAP: f64 = 0.2;
+
+/// Minimum material left between a hole's edge and the edge of its host face (mm).
+pub const MIN_EDGE_MARGIN: f64 = 1.5;
+
+/// Largest surface offset accepted for press-fit tuning (mm).
+pub const MAX_FIT_OFFSET: f64 = 0.5;
+
+/// Steepest draft accepted on posts and spindles (degrees). More than a
+/// few degrees loosens what slides onto them.
+pub const MAX_DRAFT: f64 = 5.0;
+
+/// Thinnest printable wall (mm), matching `MIN_WALL` in `src/config_validator.py`.
+pub const MIN_WALL: f64 = 0.8;
+
+/// Check the config for geometry that would build but not work. Returns one
+/// message per problem; an empty list means the config is valid.
+pub fn validate(cfg: &Config) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    // Mounting fastener must be a known size; builders size holes from it.
+    let Some(fastener) = fasteners::lookup(&cfg.mount_fastener) else {
+        errors.push(format!(
+            "mount_fastener: unknown designation '{}' (expected one of {})",
+            cfg.mount_fastener,
+            fasteners::designations()
+        ));
+        return errors;
+    };
+    let fastener = fastener.scaled(cfg.model_scale);
+    // Width of the countersunk mount holes where the screw head sits.
+    let mount_d = if cfg.countersink_mount_holes { fastener.countersunk_head } else { fastener.clearance };
+
+    if cfg.model_scale <= 0.0 {
+        errors.push(format!("model_scale: {} must be positive", cfg.model_scale));
+        return errors;
+    }
+    if cfg.material_density <= 0.0 {
+        errors.push(format!("material_density: {} g/cm³ must be positive", cfg.material_density));
+    }
+
+    // wall_thickness sizes the peel plate walls, bracket plates, and dancer hubs.
+    if cfg.wall_thickness < MIN_WALL {
+        errors.push(format!(
+            "wall_thickness: {}mm below printable minimum ({}mm)",
+            cfg.wall_thickness, MIN_WALL
+        ));
+    }
+
+    // Spool flange bolt circle must keep every hole inside the flange.
+    if spool_holder::bolt_circle_count(cfg) > 0 {
+        let hole_reach = cfg.spool_bolt_circle_diameter / 2.0 + mount_d / 2.0;
+        let limit = cfg.spool_flange_diameter / 2.0 - MIN_EDGE_MARGIN;
+        if hole_reach > limit {
+            errors.push(format!(
+                "spool_bolt_circle_diameter ({}mm) puts holes {:.2}mm from center, beyond the flange limit of {:.2}mm (flange radius - {}mm margin)",
+                cfg.spool_bolt_circle_diameter, hole_reach, limit, MIN_EDGE_MARGIN
+            ));
+        }
+    }
+
+    // Lightening pockets must leave a floor and fit between each other.
+    if cfg.frame_pockets {
+        if cfg.frame_pocket_floor <= 0.0 || cfg.frame_pocket_floor >= cfg.base_thickness {
+            errors.push(format!(
+                "frame_pocket_floor ({}mm) must be between 0 and base_thickness ({}mm)",
+                cfg.frame_pocket_floor, cfg.base_thickness
+            ));
+        }
+        if cfg.frame_pocket_pitch <= cfg.frame_pocket_size {
+            errors.push(format!(
+                "frame_pocket_pitch ({}mm) must exceed frame_pocket_size ({}mm) to leave ribs between pockets",
+                cfg.frame_pocket_pitch, cfg.frame_pocket_size
+            ));
+        }
+    }
+
+    // Standoffs must sit under the base and be wider than their bore.
+    if cfg.frame_standoffs {
+        if cfg.frame_standoff_od <= fastener.clearance {
+            errors.push(format!(
+                "frame_standoff_od ({}mm) must exceed the {} clearance hole ({}mm)",
+                cfg.frame_standoff_od, fastener.designation, fastener.clearance
+            ));
+        }
+        let positions = cfg
+            .frame_standoff_positions
+            .clone()
+            .unwrap_or_else(|| frame::corner_hole_positions(cfg).to_vec());
+        let r = cfg.frame_standoff_od / 2.0;
+        for (x, y) in positions {
+            if x.abs() + r > cfg.frame_length / 2.0 || y.abs() + r > cfg.frame_width / 2.0 {
+                errors.push(format!("frame_standoff_positions: standoff at ({x}, {y}) overhangs the base plate"));
+            }
+        }
+    }
+
+    // Draft narrows posts towards the top, so walls are checked there.
+    for (key, draft) in [("pivot_post_draft", cfg.pivot_post_draft), ("spool_spindle_draft", cfg.spool_spindle_draft)] {
+        if !(0.0..=MAX_DRAFT).contains(&draft) {
+            errors.push(format!("{key} ({draft}°) must be between 0 and {MAX_DRAFT}°"));
+        }
+    }
+    let post_top_od = 2.0 * geometry::drafted_radius(cfg.pivot_bore / 2.0, cfg.pivot_post_height, cfg.pivot_post_draft);
+    let spindle_top_od = 2.0 * geometry::drafted_radius(cfg.spool_spindle_od / 2.0, cfg.spool_height, cfg.spool_spindle_draft);
+    if spool_holder::bolt_circle_count(cfg) == 0 && spindle_top_od - cfg.fastener().clearance < 2.0 * MIN_WALL {
+        errors.push(format!(
+            "spool_spindle_draft ({}°) narrows the spindle top to {:.2}mm, leaving less than {}mm of wall around its mounting hole",
+            cfg.spool_spindle_draft, spindle_top_od, MIN_WALL
+        ));
+    }
+
+    // The post bore must leave a printable wall and, when blind, stop short
+    // of the underside of the base.
+    if cfg.pivot_post_bore > 0.0 {
+        if post_top_od - cfg.pivot_post_bore < 2.0 * MIN_WALL {
+            errors.push(format!(
+                "pivot_post_bore ({}mm) leaves less than {}mm of wall in the {:.2}mm pivot post top (pivot_bore {}mm, pivot_post_draft {}°)",
+                cfg.pivot_post_bore, MIN_WALL, post_top_od, cfg.pivot_bore, cfg.pivot_post_draft
+            ));
+        }
+        let max_depth = cfg.pivot_post_height + cfg.base_thickness - MIN_WALL;
+        if cfg.pivot_post_bore_depth < 0.0 || cfg.pivot_post_bore_depth > max_depth {
+            errors.push(format!(
+                "pivot_post_bore_depth ({}mm) must be between 0 (through) and {:.2}mm so a blind bore doesn't break through the base",
+                cfg.pivot_post_bore_depth, max_depth
+            ));
+        }
+    }
+
+    // The post reinforcement must taper into the post below its top.
+    if cfg.pivot_reinforce_radius < 0.0 {
+        errors.push(format!("pivot_reinforce_radius ({}mm) must not be negative", cfg.pivot_reinforce_radius));
+    }
+    if cfg.pivot_reinforce_height <= 0.0 || cfg.pivot_reinforce_height > cfg.pivot_post_height {
+        errors.push(format!(
+            "pivot_reinforce_height ({}mm) must be between 0 and pivot_post_height ({}mm)",
+            cfg.pivot_reinforce_height, cfg.pivot_post_height
+        ));
+    }
+
+    // The guide bracket's hole row must fit across its base.
+    if cfg.bracket_mount_hole_count == 0 {
+        errors.push("bracket_mount_hole_count must be at least 1".into());
+    } else {
+        if cfg.bracket_mount_hole_count > 1 && cfg.bracket_mount_hole_spacing < fastener.clearance + MIN_WALL {
+            errors.push(format!(
+                "bracket_mount_hole_spacing ({}mm) leaves less than {}mm between {} clearance holes",
+                cfg.bracket_mount_hole_spacing, MIN_WALL, fastener.designation
+            ));
+        }
+    }
+
+    // The arm outline blends the bar into each hub with a concave fillet,
+    // which needs the bar to be narrower than the hub.
+    for (key, r) in [("pivot", dancer_arm::pivot_hub_radius(cfg)), ("roller", dancer_arm::roller_hub_radius(cfg))] {
+        if cfg.dancer_arm_width >= 2.0 * r {
+            errors.push(format!(
+                "dancer_arm_width ({}mm) must be narrower than the {key} hub ({:.1}mm across)",
+                cfg.dancer_arm_width,
+                2.0 * r
+            ));
+        }
+    }
+
+    // The roller counterbore must widen the bearing bore, stay inside the
+    // hub, and stop short of the arm's inner face.
+    if cfg.dancer_roller_counterbore_diameter > 0.0 {
+        let max_diameter = 2.0 * (dancer_arm::roller_hub_radius(cfg) - MIN_WALL);
+        if cfg.dancer_roller_counterbore_diameter <= cfg.bearing_id || cfg.dancer_roller_counterbore_diameter > max_diameter {
+            errors.push(format!(
+                "dancer_roller_counterbore_diameter ({}mm) must be wider than bearing_id ({}mm) and at most {:.2}mm to fit the roller hub",
+                cfg.dancer_roller_counterbore_diameter, cfg.bearing_id, max_diameter
+            ));
+        }
+        if cfg.dancer_roller_counterbore_depth <= 0.0 || cfg.dancer_roller_counterbore_depth >= cfg.dancer_arm_thickness {
+            errors.push(format!(
+                "dancer_roller_counterbore_depth ({}mm) must be between 0 and dancer_arm_thickness ({}mm)",
+                cfg.dancer_roller_counterbore_depth, cfg.dancer_arm_thickness
+            ));
+        }
+    }
+
+    // A print-in-place pin needs a gap the printer can hold open that still
+    // leaves a post of printable thickness in the pivot bore.
+    if cfg.print_in_place && (cfg.print_in_place_gap < MIN_PRINT_GAP || cfg.print_in_place_gap > cfg.pivot_bore / 2.0 - MIN_WALL) {
+        errors.push(format!(
+            "print_in_place_gap ({}mm) must be at least {}mm and leave a {}mm pin in the {}mm pivot bore",
+            cfg.print_in_place_gap,
+            MIN_PRINT_GAP,
+            2.0 * MIN_WALL,
+            cfg.pivot_bore
+        ));
+    }
+
+    // A ribbed bar needs a printable web and rim and room for its pockets
+    // between the hubs.
+    if cfg.dancer_arm_profile == ArmProfile::Ribbed {
+        if cfg.dancer_arm_rib_web < MIN_WALL || cfg.dancer_arm_rib_web >= cfg.dancer_arm_thickness {
+            errors.push(format!(
+                "dancer_arm_rib_web ({}mm) must be at least {}mm and thinner than dancer_arm_thickness ({}mm)",
+                cfg.dancer_arm_rib_web, MIN_WALL, cfg.dancer_arm_thickness
+            ));
+        }
+        if cfg.dancer_arm_rib_rim < MIN_WALL || cfg.dancer_arm_width - 2.0 * cfg.dancer_arm_rib_rim <= 0.0 {
+            errors.push(format!(
+                "dancer_arm_rib_rim ({}mm) must be at least {}mm and leave room for a pocket in the {}mm wide bar",
+                cfg.dancer_arm_rib_rim, MIN_WALL, cfg.dancer_arm_width
+            ));
+        }
+        let (x0, x1) = dancer_arm::rib_pocket_span(cfg);
+        if x1 <= x0 {
+            errors.push(format!(
+                "dancer_arm_profile \"ribbed\" leaves no bar between the hubs to pocket; lengthen dancer_arm_length ({}mm) or use \"solid\"",
+                cfg.dancer_arm_length
+            ));
+        }
+    }
+
+    // Spring anchors must sit within the dancer arm bar; insert bosses may
+    // overhang its sides, since they stand on the bottom face.
+    let mut anchors = vec![("dancer_spring_hole_x", cfg.dancer_spring_hole_x)];
+    if let Some(x2) = cfg.dancer_spring_hole_2_x {
+        anchors.push(("dancer_spring_hole_2_x", x2));
+    }
+    let spring_r = dancer_arm::spring_anchor_radius(cfg);
+    let anchor = if cfg.dancer_spring_inserts { "insert boss" } else { "spring hole" };
+    // Nor may they break into the pivot or roller bore (or its counterbore).
+    let roller_bore_r = cfg.bearing_id.max(cfg.dancer_roller_counterbore_diameter) / 2.0;
+    let bores = [("pivot bore", 0.0, cfg.pivot_bore / 2.0), ("roller bore", cfg.dancer_arm_length, roller_bore_r)];
+    for (key, x) in anchors {
+        if x - spring_r < 0.0 || x + spring_r > cfg.dancer_arm_length {
+            errors.push(format!(
+                "{key} ({x}mm) puts the {anchor} outside the arm length (0..{}mm)",
+                cfg.dancer_arm_length
+            ));
+        }
+        for (bore, bore_x, bore_r) in bores {
+            let gap = (x - bore_x).hypot(cfg.dancer_spring_hole_offset) - spring_r - bore_r;
+            if gap < 0.0 {
+                errors.push(format!("{key} ({x}mm) puts the {anchor} {:.2}mm into the {bore}", -gap));
+            }
+        }
+    }
+    if cfg.insert_od <= 0.0 || cfg.insert_depth <= 0.0 {
+        errors.push(format!("insert_od ({}mm) and insert_depth ({}mm) must be positive", cfg.insert_od, cfg.insert_depth));
+    }
+    if cfg.dancer_spring_inserts && geometry::insert_boss_height(cfg) < cfg.dancer_arm_thickness {
+        errors.push(format!(
+            "insert_depth ({}mm) + {}mm floor is shorter than dancer_arm_thickness ({}mm), so the insert bore would open inside the arm",
+            cfg.insert_depth,
+            cfg.mm(geometry::INSERT_FLOOR),
+            cfg.dancer_arm_thickness
+        ));
+    }
+    if !cfg.dancer_spring_inserts && cfg.dancer_spring_hole_offset.abs() + spring_r > cfg.dancer_arm_width / 2.0 {
+        errors.push(format!(
+            "dancer_spring_hole_offset ({}mm) + hole radius ({spring_r}mm) exceeds half the arm width ({}mm)",
+            cfg.dancer_spring_hole_offset,
+            cfg.dancer_arm_width / 2.0
+        ));
+    }
+
+    // The V-block has to carry the whole label band, and the base has to
+    // reach past it far enough to hold the mounting holes.
+    if vial_cradle::cradle_length(cfg) < cfg.label_height {
+        errors.push(format!(
+            "cradle_end_clearance ({}mm) leaves a {:.2}mm V-block, shorter than label_height ({}mm)",
+            cfg.cradle_end_clearance,
+            vial_cradle::cradle_length(cfg),
+            cfg.label_height
+        ));
+    }
+
+    // The V-block must reach at least the vial's axis, or nothing holds the
+    // vial in the groove.
+    if cfg.cradle_vial_rise > cfg.vial_diameter / 2.0 {
+        errors.push(format!(
+            "cradle_vial_rise ({}mm) puts the vial axis above the V-block; keep it at most vial_diameter / 2 ({}mm)",
+            cfg.cradle_vial_rise,
+            cfg.vial_diameter / 2.0
+        ));
+    }
+    // Too low, and the vial would touch the cradle only below its base.
+    let seated = vial_cradle::contact_line_z(cfg) - vial_cradle::base_bottom_z(cfg);
+    if seated <= 0.0 {
+        errors.push(format!(
+            "cradle_vial_rise ({}mm) seats the vial below the underside of the cradle; keep it above {:.2}mm",
+            cfg.cradle_vial_rise,
+            cfg.cradle_vial_rise - seated
+        ));
+    }
+
+    // Clearance deepens the V-groove under the vial; the apex must stay
+    // above the underside of the cradle base. A large vial can run the
+    // groove through the base with no clearance, so only a clearance that
+    // is asked for is held to this.
+    let floor = vial_cradle::groove_apex_z(cfg) - vial_cradle::base_bottom_z(cfg);
+    if cfg.cradle_hole_edge_margin < MIN_EDGE_MARGIN {
+        errors.push(format!("cradle_hole_edge_margin ({}mm) must be at least {}mm", cfg.cradle_hole_edge_margin, MIN_EDGE_MARGIN));
+    }
+    if cfg.cradle_slot_travel < 0.0 {
+        errors.push(format!("cradle_slot_travel ({}mm) must not be negative", cfg.cradle_slot_travel));
+    }
+    if cfg.vial_cradle_clearance < 0.0 || (cfg.vial_cradle_clearance > 0.0 && floor <= 0.0) {
+        errors.push(format!(
+            "vial_cradle_clearance ({}mm) must be at least 0 and leave the groove apex above the cradle base ({:.2}mm of floor); raise cradle_base_height for more",
+            cfg.vial_cradle_clearance, floor
+        ));
+    }
+
+    // The saddle is always deeper than its seat, so it needs a floor even
+    // without clearance.
+    if cfg.cradle_style == CradleStyle::Saddle && floor < MIN_WALL {
+        errors.push(format!(
+            "cradle_style \"saddle\" leaves {:.2}mm of floor under the seat, under the {}mm minimum wall; raise cradle_vial_rise or cradle_base_height",
+            floor, MIN_WALL
+        ));
+    }
+
+    // The label window must stay above the vial contact lines and inside the
+    // V-block, clear of the base and its mounting holes.
+    if cfg.cradle_window {
+        let bottom_z = cfg.cradle_base_height / 2.0 + cfg.cradle_window_offset;
+        let min_z = vial_cradle::contact_line_z(cfg) + MIN_EDGE_MARGIN;
+        if bottom_z < min_z {
+            errors.push(format!(
+                "cradle_window_offset ({}mm) puts the window bottom at z={:.2}mm, below the vial contact lines + {}mm margin (z={:.2}mm)",
+                cfg.cradle_window_offset, bottom_z, MIN_EDGE_MARGIN, min_z
+            ));
+        }
+        let max_width = vial_cradle::cradle_length(cfg) - 2.0 * MIN_EDGE_MARGIN;
+        if cfg.cradle_window_width <= 0.0 || cfg.cradle_window_width > max_width {
+            errors.push(format!(
+                "cradle_window_width ({}mm) must be between 0 and {:.2}mm to leave the V-block ends intact",
+                cfg.cradle_window_width, max_width
+            ));
+        }
+        if cfg.cradle_window_height <= 0.0 {
+            errors.push(format!("cradle_window_height ({}mm) must be positive", cfg.cradle_window_height));
+        }
+    }
+
+    // Each peel plate side wall must stay printable beside the channel,
+    // which is wider than the label by the clearance.
+    let (left, right) = peel_plate::side_walls(cfg);
+    for (key, wall) in [("peel_wall_left", left), ("peel_wall_right", right)] {
+        if wall - cfg.peel_channel_width_clearance / 2.0 < MIN_WALL {
+            errors.push(format!(
+                "{key} ({wall}mm) leaves less than {}mm beside the label channel (half of peel_channel_width_clearance is {}mm)",
+                MIN_WALL,
+                cfg.peel_channel_width_clearance / 2.0
+            ));
+        }
+    }
+
+    // The wedge slopes down towards the label exit, and its front face
+    // still has to carry the label channel.
+    let front_height = peel_plate::front_height(cfg);
+    if front_height > cfg.peel_body_height_rear {
+        errors.push(format!(
+            "peel_body_height_front ({front_height}mm) must not exceed peel_body_height_rear ({}mm)",
+            cfg.peel_body_height_rear
+        ));
+    }
+    if front_height < cfg.mm(peel_plate::CHANNEL_DEPTH) + MIN_WALL {
+        errors.push(format!(
+            "peel_body_height_front ({front_height}mm) leaves less than {MIN_WALL}mm under the {}mm label channel",
+            cfg.mm(peel_plate::CHANNEL_DEPTH)
+        ));
+    }
+
+    // The leading-edge round has to fit inside the peel body's front.
+    let edge_limit = cfg.peel_body_depth.min(front_height) / 2.0;
+    if cfg.peel_edge_radius < 0.0 || cfg.peel_edge_radius > edge_limit {
+        errors.push(format!(
+            "peel_edge_radius ({}mm) must be between 0 and half the smaller of peel_body_depth and the front height ({}mm)",
+            cfg.peel_edge_radius, edge_limit
+        ));
+    }
+    if cfg.peel_edge_chamfer < 0.0 || cfg.peel_edge_chamfer > edge_limit {
+        errors.push(format!(
+            "peel_edge_chamfer ({}mm) must be between 0 and half the smaller of peel_body_depth and the front height ({}mm)",
+            cfg.peel_edge_chamfer, edge_limit
+        ));
+    }
+    // The 45° chamfer meets the sloped top chamfer / (1 - tan slope) back
+    // from the front, and never at 45° or steeper.
+    let rise = peel_plate::top_slope_deg(cfg).to_radians().tan();
+    if cfg.peel_edge_chamfer > 0.0 && (rise >= 1.0 || cfg.peel_edge_chamfer / (1.0 - rise) > cfg.peel_body_depth / 2.0) {
+        errors.push(format!(
+            "peel_edge_chamfer ({}mm) runs past the middle of the {:.1}° sloped top; lower it or raise peel_body_height_front",
+            cfg.peel_edge_chamfer,
+            peel_plate::top_slope_deg(cfg)
+        ));
+    }
+    if cfg.peel_edge_chamfer > 0.0 && cfg.peel_edge_radius > 0.0 {
+        errors.push("peel_edge_chamfer and peel_edge_radius both shape the leading edge; set one of them to 0".to_string());
+    }
+
+    // The exit chamfer must leave a straight bore and material around its
+    // rim, on the bracket pin hole and on the frame corner holes.
+    if cfg.hole_exit_chamfer < 0.0 {
+        errors.push(format!("hole_exit_chamfer ({}mm) must not be negative", cfg.hole_exit_chamfer));
+    } else if cfg.hole_exit_chamfer > 0.0 {
+        let pin_room = (cfg.bearing_od / 2.0 + 2.0).min(cfg.bracket_base_width / 2.0) - cfg.pivot_bore / 2.0 - MIN_EDGE_MARGIN;
+        let corner_room = cfg.mm(frame::CORNER_HOLE_INSET) - fastener.clearance / 2.0 - MIN_EDGE_MARGIN;
+        for (hole, thickness, room) in [("bracket pin hole", cfg.wall_thickness, pin_room), ("frame corner holes", cfg.base_thickness, corner_room)] {
+            let limit = room.min(thickness - MIN_WALL);
+            if cfg.hole_exit_chamfer > limit {
+                errors.push(format!(
+                    "hole_exit_chamfer ({}mm) is too large for the {hole}: at most {:.2}mm leaves {}mm around the rim and a {}mm bore",
+                    cfg.hole_exit_chamfer, limit, MIN_EDGE_MARGIN, MIN_WALL
+                ));
+            }
+        }
+    }
+
+    // A countersink must leave a straight bore under the screw head.
+    if cfg.countersink_mount_holes {
+        let sink = geometry::countersink_depth(fastener.clearance, fastener.countersunk_head, fasteners::COUNTERSINK_ANGLE);
+        let flange = if spool_holder::bolt_circle_count(cfg) > 0 { cfg.spool_flange_thickness } else { cfg.spool_flange_thickness + cfg.spool_height };
+        for (host, thickness) in [("guide_roller_bracket base", cfg.wall_thickness), ("spool_holder flange", flange)] {
+            if sink > thickness - MIN_WALL {
+                errors.push(format!(
+                    "countersink_mount_holes: the {sink:.2}mm countersink leaves less than {MIN_WALL}mm of bore through the {thickness}mm {host}"
+                ));
+            }
+        }
+    }
+
+    // Every mounting hole must sit inside its host face with an edge margin.
+    let (cradle_x, cradle_y) = (vial_cradle::base_length(cfg) / 2.0, vial_cradle::base_width(cfg) / 2.0);
+    let (bracket_x, bracket_y) = (cfg.bracket_base_width / 2.0, cfg.bracket_base_depth / 2.0);
+    let (peel_min_x, peel_max_x) = peel_plate::body_x_range(cfg);
+    let (peel_y, peel_z) = (cfg.peel_body_depth / 2.0, cfg.peel_body_height_rear / 2.0);
+    let (frame_x, frame_y) = (cfg.frame_length / 2.0, cfg.frame_width / 2.0);
+    // A slot is checked at both of its round ends.
+    let (reach_x, reach_y) = vial_cradle::slot_reach(cfg);
+    let slot_ends = |positions: [(f64, f64); 4]| positions.into_iter().flat_map(|(x, y)| [(x - reach_x, y - reach_y), (x + reach_x, y + reach_y)]).collect::<Vec<_>>();
+    let mut frame_holes = frame::corner_hole_positions(cfg).to_vec();
+    frame_holes.extend(slot_ends(frame::cradle_hole_positions(cfg)));
+    frame_holes.extend(frame::guide_hole_positions(cfg));
+    let groups = [
+        ("vial_cradle base", "cradle_mount_slot_spacing_x/_y", fastener.clearance, slot_ends(vial_cradle::mount_hole_positions(cfg)), (-cradle_x, -cradle_y), (cradle_x, cradle_y)),
+        ("guide_roller_bracket base", "bracket_mount_hole_count/_spacing", mount_d, guide_roller_bracket::mount_hole_positions(cfg), (-bracket_x, -bracket_y), (bracket_x, bracket_y)),
+        ("peel_plate rear face", "peel_mount_hole_spacing", fastener.clearance, peel_plate::rear_hole_positions(cfg), (peel_min_x, -peel_z), (peel_max_x, peel_z)),
+        ("peel_plate bottom face", "peel_bottom_hole_spacing", fastener.clearance, peel_plate::bottom_hole_positions(cfg), (peel_min_x, -peel_y), (peel_max_x, peel_y)),
+        ("main_frame base", "frame_length/_width", fastener.clearance, frame_holes, (-frame_x, -frame_y), (frame_x, frame_y)),
+    ];
+    for (host, fields, diameter, positions, min, max) in groups {
+        errors.extend(holes_off_face(host, fields, &positions, diameter, min, max));
+    }
+    // The rear holes' screw heads sit in the lower front face.
+    if cfg.countersink_mount_holes {
+        let front_top = peel_plate::top_z(cfg, peel_y);
+        errors.extend(holes_off_face("peel_plate front face", "peel_mount_hole_spacing", &peel_plate::rear_hole_positions(cfg), mount_d, (peel_min_x, -peel_z), (peel_max_x, front_top)));
+    }
+
+    // Peel plate mounting holes must stay out of the label channel and,
+    // from the bottom, out of the rear holes. The channel floor is lowest
+    // at the front, which the rear holes run through to.
+    let hole_r = fastener.clearance / 2.0;
+    let channel_half = (cfg.label_width + cfg.peel_channel_width_clearance) / 2.0;
+    for (x, z) in peel_plate::rear_hole_positions(cfg) {
+        if z + mount_d / 2.0 > peel_plate::channel_floor_z(cfg, peel_y) - MIN_WALL {
+            errors.push(format!("peel_plate rear hole at x={x:.1} leaves less than {MIN_WALL}mm under the label channel"));
+        }
+    }
+    if cfg.peel_bottom_holes {
+        // Blind under the sloped top at the hole's front edge.
+        let blind = peel_plate::top_z(cfg, hole_r) - peel_plate::base_bottom_z(cfg) - MIN_WALL;
+        if cfg.peel_bottom_hole_depth <= 0.0 || cfg.peel_bottom_hole_depth > blind {
+            errors.push(format!(
+                "peel_bottom_hole_depth ({}mm) must be between 0 and {:.2}mm so the holes stay blind",
+                cfg.peel_bottom_hole_depth, blind
+            ));
+        }
+        let hole_top = peel_plate::base_bottom_z(cfg) + cfg.peel_bottom_hole_depth;
+        for (x, y) in peel_plate::bottom_hole_positions(cfg) {
+            if x.abs() - hole_r < channel_half && hole_top > peel_plate::channel_floor_z(cfg, y + hole_r) - MIN_WALL {
+                errors.push(format!(
+                    "peel_plate bottom hole at x={x:.1} reaches within {MIN_WALL}mm of the label channel; reduce peel_bottom_hole_depth or move it outside the channel"
+                ));
+            }
+            let crosses = peel_plate::rear_hole_positions(cfg).iter().any(|&(rx, rz)| (rx - x).abs() < 2.0 * hole_r + MIN_WALL && hole_top > rz - hole_r - MIN_WALL);
+            if crosses {
+                errors.push(format!(
+                    "peel_plate bottom hole at x={x:.1} runs into a rear hole; change peel_bottom_hole_spacing or peel_bottom_hole_depth"
+                ));
+            }
+        }
+    }
+
+    // Peel plate ribs are printable, fit side by side in the body depth, and
+    // leave the bottom holes open. They span only the channel width below
+    // the body, so they can't reach the channel or the rear holes.
+    if cfg.peel_rib_count > 0 {
+        if cfg.peel_rib_thickness < MIN_WALL || cfg.peel_rib_height <= 0.0 {
+            errors.push(format!(
+                "peel_rib_thickness ({}mm) must be at least {MIN_WALL}mm and peel_rib_height ({}mm) above 0",
+                cfg.peel_rib_thickness, cfg.peel_rib_height
+            ));
+        }
+        if cfg.peel_rib_count as f64 * cfg.peel_rib_thickness > cfg.peel_body_depth / 2.0 {
+            errors.push(format!(
+                "peel_rib_count ({}) × peel_rib_thickness ({}mm) fills more than half the {}mm peel body depth",
+                cfg.peel_rib_count, cfg.peel_rib_thickness, cfg.peel_body_depth
+            ));
+        }
+        for y in peel_plate::rib_positions(cfg) {
+            let covered = peel_plate::bottom_hole_positions(cfg)
+                .iter()
+                .any(|&(hx, hy)| hx.abs() - hole_r < channel_half && (hy - y).abs() < cfg.peel_rib_thickness / 2.0 + hole_r);
+            if covered {
+                errors.push(format!("peel_plate rib at y={y:.1} covers a bottom hole; change peel_rib_count or peel_bottom_hole_spacing"));
+            }
+        }
+    }
+
+    // The roller bearing on the bracket pin must clear the base plate.
+    let min_height = guide_roller_bracket::min_height(cfg);
+    if !cfg.bracket_auto_height && cfg.bracket_height < min_height {
+        errors.push(format!(
+            "bracket_height ({}mm) must be at least {:.2}mm for a {}mm bearing to clear the base by bracket_bearing_clearance ({}mm); set bracket_auto_height = true to raise it automatically",
+            cfg.bracket_height, min_height, cfg.bearing_od, cfg.bracket_bearing_clearance
+        ));
+    }
+
+    // The pin hole offset is a fit adjustment, not a resize.
+    if cfg.bracket_pin_hole_offset.abs() > MAX_FIT_OFFSET {
+        errors.push(format!(
+            "bracket_pin_hole_offset ({}mm) must be within ±{}mm; change pivot_bore to resize the hole",
+            cfg.bracket_pin_hole_offset, MAX_FIT_OFFSET
+        ));
+    }
+
+    // A circle needs at least a triangle's worth of segments.
+    for (key, segments) in [("segments_hole", cfg.segments_hole), ("segments_cylinder", cfg.segments_cylinder), ("segments_flange", cfg.segments_flange)] {
+        if segments < 3 {
+            errors.push(format!("{key} ({segments}) must be at least 3"));
+        }
+    }
+
+    // 3MF display colors are #RRGGBB with an optional alpha byte.
+    for (name, color) in &cfg.colors {
+        let digits = color.strip_prefix('#').unwrap_or("");
+        if !matches!(digits.len(), 6 | 8) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            errors.push(format!("colors.{name}: '{color}' is not a #RRGGBB hex color"));
+        }
+    }
+
+    errors
+}
+
+/// One message per hole of diameter `diameter` at `positions` whose edge
+/// lands outside the rectangular face from `min` to `max`, or within
+/// `MIN_EDGE_MARGIN` of its boundary. `fields` names the config that places
+/// the holes.
+fn holes_off_face(host: &str, fields: &str, positions: &[(f64, f64)], diameter: f64, min: (f64, f64), max: (f64, f64)) -> Vec<String> {
+    let r = diameter / 2.0;
+    positions
+        .iter()
+        .filter_map(|&(x, y)| {
+            let margin = (x - r - min.0).min(max.0 - x - r).min(y - r - min.1).min(max.1 - y - r);
+            (margin < MIN_EDGE_MARGIN).then(|| {
+                format!(
+                    "{host}: mounting hole at ({x:.1}, {y:.1}) is {margin:.2}mm from the edge, under the {MIN_EDGE_MARGIN}mm margin (check {fields})"
+                )
+            })
+        })
+        .collect()
+}
+
+/// Geometry that builds and fits but may not work well in use. Returned
+/// messages are reported without stopping the build.
+pub fn warnings(cfg: &Config) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    // The label must not bend tighter around the peel edge than its stock allows.
+    if let Some(radius) = peel_plate::effective_bend_radius(cfg) {
+        if radius < cfg.min_bend_radius {
+            warnings.push(format!(
+                "peel_edge_radius ({}mm) bends the label at an effective {:.2}mm radius, tighter than min_bend_radius ({}mm)",
+                cfg.peel_edge_radius, radius, cfg.min_bend_radius
+            ));
+        }
+    }
+
+    let bracket_height = guide_roller_bracket::height(cfg);
+    if bracket_height > cfg.bracket_height {
+        warnings.push(format!(
+            "bracket_height raised from {}mm to {:.2}mm to fit the {}mm bearing (bracket_auto_height)",
+            cfg.bracket_height, bracket_height, cfg.bearing_od
+        ));
+    }
+
+    // The cradle base grows to keep its holes off the edge; say so, since
+    // it may no longer fit where it did.
+    let (grown_x, grown_y) = vial_cradle::base_growth(cfg);
+    if grown_x > 0.0 || grown_y > 0.0 {
+        warnings.push(format!(
+            "vial_cradle base grown by {:.2}mm in length and {:.2}mm in width to {:.2} × {:.2}mm so its mounting holes keep cradle_hole_edge_margin ({}mm)",
+            grown_x,
+            grown_y,
+            vial_cradle::base_length(cfg),
+            vial_cradle::base_width(cfg),
+            cfg.cradle_hole_edge_margin
+        ));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_config;
+
+    #[test]
+    fn flags_mounting_holes_that_spill_off_their_face() {
+        let mut cfg = load_config(None).unwrap();
+        assert!(validate(&cfg).is_empty(), "{:?}", validate(&cfg));
+        cfg.bracket_mount_hole_spacing = cfg.bracket_base_width - 1.0;
+        let errors = validate(&cfg);
+        assert_eq!(errors.iter().filter(|e| e.starts_with("guide_roller_bracket base:")).count(), 2, "{:?}", errors);
+
+        // The cradle base grows to fit its holes instead.
+        let mut cfg = load_config(None).unwrap();
+        cfg.cradle_mount_slot_spacing_y = vial_cradle::base_width(&cfg);
+        assert!(validate(&cfg).is_empty(), "{:?}", validate(&cfg));
+        assert!(warnings(&cfg).iter().any(|w| w.starts_with("vial_cradle base grown")));
+
+        let mut cfg = load_config(None).unwrap();
+        cfg.peel_mount_hole_spacing = cfg.label_width + 2.0 * cfg.wall_thickness;
+        assert_eq!(validate(&cfg).iter().filter(|e| e.starts_with("peel_plate rear face:")).count(), 2);
+    }
+
+    #[test]
+    fn saddle_needs_a_floor_under_the_seat() {
+        let mut cfg = Config { cradle_style: CradleStyle::Saddle, ..load_config(None).unwrap() };
+        assert!(validate(&cfg).is_empty(), "{:?}", validate(&cfg));
+        cfg.cradle_vial_rise = -7.5;
+        assert!(validate(&cfg).iter().any(|e| e.starts_with("cradle_style \"saddle\" leaves -0.")), "{:?}", validate(&cfg));
+    }
+
+    #[test]
+    fn cradle_vial_rise_keeps_the_vial_on_the_v_block() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.cradle_vial_rise = 9.0;
+        assert!(validate(&cfg).iter().any(|e| e.contains("puts the vial axis above the V-block")), "{:?}", validate(&cfg));
+        cfg.cradle_vial_rise = -9.0;
+        assert!(validate(&cfg).is_empty(), "{:?}", validate(&cfg));
+        cfg.cradle_vial_rise = -10.0;
+        assert!(validate(&cfg).iter().any(|e| e.contains("keep it above -9.34mm")), "{:?}", validate(&cfg));
+    }
+
+    #[test]
+    fn countersinks_need_bore_under_them_and_room_for_the_head() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.countersink_mount_holes = true;
+        cfg.peel_body_height_front = Some(cfg.peel_body_height_rear);
+        assert!(validate(&cfg).is_empty(), "{:?}", validate(&cfg));
+        cfg.wall_thickness = 2.0;
+        assert!(validate(&cfg).iter().any(|e| e.contains("of bore through the 2mm guide_roller_bracket base")), "{:?}", validate(&cfg));
+        cfg.wall_thickness = 2.5;
+        // The head sits in the lower front face, up against the channel.
+        cfg.peel_body_height_front = Some(cfg.peel_body_height_rear - 3.0);
+        assert!(validate(&cfg).iter().any(|e| e.contains("under the label channel")), "{:?}", validate(&cfg));
+    }
+
+    #[test]
+    fn peel_plate_holes_stay_on_their_faces_and_clear_of_each_other() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.peel_bottom_holes = true;
+        assert!(validate(&cfg).iter().any(|e| e.contains("runs into a rear hole")), "{:?}", validate(&cfg));
+        cfg.peel_bottom_hole_spacing = 20.0;
+        assert!(validate(&cfg).is_empty(), "{:?}", validate(&cfg));
+        cfg.peel_bottom_hole_depth = peel_plate::top_z(&cfg, 0.0) - peel_plate::base_bottom_z(&cfg) - 1.5;
+        assert!(validate(&cfg).iter().any(|e| e.contains("of the label channel")), "{:?}", validate(&cfg));
+        cfg.peel_bottom_hole_depth = 6.0;
+        cfg.peel_bottom_hole_spacing = cfg.label_width + 2.0 * cfg.wall_thickness;
+        assert_eq!(validate(&cfg).iter().filter(|e| e.starts_with("peel_plate bottom face:")).count(), 2);
+    }
+
+    #[test]
+    fn peel_ribs_fit_and_keep_the_bottom_holes_open() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.peel_bottom_holes = true;
+        cfg.peel_bottom_hole_spacing = 20.0;
+        cfg.peel_rib_count = 2;
+        assert!(validate(&cfg).is_empty(), "{:?}", validate(&cfg));
+        cfg.peel_rib_count = 3;
+        assert_eq!(validate(&cfg).iter().filter(|e| e.contains("covers a bottom hole")).count(), 1, "{:?}", validate(&cfg));
+        cfg.peel_rib_count = 8;
+        assert!(validate(&cfg).iter().any(|e| e.starts_with("peel_rib_count (8)")), "{:?}", validate(&cfg));
+    }
+
+    #[test]
+    fn spring_anchors_keep_clear_of_the_pivot_and_roller_bores() {
+        let mut cfg = load_config(None).unwrap();
+        assert!(cfg.dancer_spring_inserts);
+        // The boss reaches 7.5 - 4.5 = 3mm from the pivot axis, 1mm into the bore.
+        cfg.dancer_spring_hole_x = 6.0;
+        assert!(validate(&cfg).iter().any(|e| e == "dancer_spring_hole_x (6mm) puts the insert boss 1.00mm into the pivot bore"), "{:?}", validate(&cfg));
+        cfg.dancer_spring_hole_x = 10.0;
+        cfg.dancer_spring_hole_2_x = Some(50.0);
+        assert!(validate(&cfg).is_empty(), "{:?}", validate(&cfg));
+        cfg.dancer_roller_counterbore_diameter = 16.0;
+        assert!(validate(&cfg).iter().any(|e| e.starts_with("dancer_spring_hole_2_x (50mm) puts the insert boss") && e.ends_with("into the roller bore")), "{:?}", validate(&cfg));
+    }
+
+    #[test]
+    fn short_bracket_is_rejected_or_raised_to_fit_the_bearing() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.bracket_height = 15.0;
+        assert!(validate(&cfg).iter().any(|e| e.starts_with("bracket_height (15mm) must be at least 23.75mm")), "{:?}", validate(&cfg));
+        cfg.bracket_auto_height = true;
+        assert!(validate(&cfg).is_empty(), "{:?}", validate(&cfg));
+        assert_eq!(guide_roller_bracket::height(&cfg), guide_roller_bracket::min_height(&cfg));
+        assert!(warnings(&cfg).iter().any(|w| w.starts_with("bracket_height raised")));
+        // The bearing then clears the base top by exactly the clearance.
+        let gap = guide_roller_bracket::pin_hole_z(&cfg) - cfg.bearing_od / 2.0 - cfg.wall_thickness / 2.0;
+        assert!((gap - cfg.bracket_bearing_clearance).abs() < 1e-9);
+    }
+}
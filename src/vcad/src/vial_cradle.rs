@@ -1,40 +1,295 @@
 //! Vial cradle — simplified CSG version.
 //!
 //! The Build123d version uses a precise V-block with trigonometric calculations.
-//! This vcad version approximates the V-groove using two angled box cuts.
+//! This vcad version derives the same 90° V from `vial_diameter` and cuts
+//! each of its faces with its own slab tilted 45°. With `cradle_style =
+//! "saddle"` a half-cylinder is cut instead, seating the vial around its
+//! lower half.
 
 use vcad::*;
 
-use crate::config::Config;
+use crate::config::{Config, CradleStyle, SlotAxis};
+use crate::features::{feature, tagged, Role};
+use crate::geometry::{self, PartExt};
+
+/// Z of the bottom face of the base plate in build coordinates.
+pub fn base_bottom_z(cfg: &Config) -> f64 {
+    -cfg.cradle_base_height / 2.0
+}
+
+/// Angle of each V face from vertical (degrees); the groove is a 90° V.
+const V_HALF_ANGLE: f64 = 45.0;
+
+/// Radius of the cylinder the groove is cut to seat, and of the saddle: the
+/// vial plus its clearance.
+pub fn seat_radius(cfg: &Config) -> f64 {
+    (cfg.vial_diameter + cfg.vial_cradle_clearance) / 2.0
+}
+
+/// Z of the V-block's top face in build coordinates.
+pub fn block_top_z(cfg: &Config) -> f64 {
+    cfg.cradle_base_height / 2.0 + cfg.cradle_v_block_height
+}
+
+/// Z of the seated vial's axis in build coordinates, putting the vial's top
+/// `cradle_vial_rise` above the V-block. This is the fixed point: clearance
+/// deepens the groove under it rather than moving the vial off the label
+/// path.
+pub fn vial_axis_z(cfg: &Config) -> f64 {
+    block_top_z(cfg) + cfg.cradle_vial_rise - cfg.vial_diameter / 2.0
+}
+
+/// Z of the lowest point of the groove in build coordinates: the V apex, or
+/// the bottom of the saddle.
+pub fn groove_apex_z(cfg: &Config) -> f64 {
+    match cfg.cradle_style {
+        CradleStyle::V => vial_axis_z(cfg) - seat_radius(cfg) / V_HALF_ANGLE.to_radians().sin(),
+        CradleStyle::Saddle => vial_axis_z(cfg) - seat_radius(cfg),
+    }
+}
+
+/// Z of the lines where the seat cylinder touches the two V faces, or of
+/// the saddle's bottom line; the vial itself stands
+/// `vial_cradle_clearance / 2` off them.
+pub fn contact_line_z(cfg: &Config) -> f64 {
+    match cfg.cradle_style {
+        CradleStyle::V => vial_axis_z(cfg) - seat_radius(cfg) * V_HALF_ANGLE.to_radians().sin(),
+        CradleStyle::Saddle => vial_axis_z(cfg) - seat_radius(cfg),
+    }
+}
+
+/// Length of the V-block along the vial axis: the vial, less the end
+/// clearance.
+pub fn cradle_length(cfg: &Config) -> f64 {
+    cfg.vial_height - cfg.cradle_end_clearance
+}
+
+/// Length of the base plate: `cradle_base_margin` past both ends of the
+/// V-block, or longer if the mounting slots need it.
+pub fn base_length(cfg: &Config) -> f64 {
+    (cradle_length(cfg) + 2.0 * cfg.cradle_base_margin).max(hole_span(cfg, cfg.cradle_mount_slot_spacing_x, slot_reach(cfg).0))
+}
+
+/// Width (Y) of the base plate and V-block, widened like `base_length`.
+pub fn base_width(cfg: &Config) -> f64 {
+    (cfg.vial_diameter + cfg.mm(20.0)).max(hole_span(cfg, cfg.cradle_mount_slot_spacing_y, slot_reach(cfg).1))
+}
+
+/// Base size across a pair of slots `spacing` apart, each reaching `reach`
+/// past its center, that leaves `cradle_hole_edge_margin` outside each.
+fn hole_span(cfg: &Config, spacing: f64, reach: f64) -> f64 {
+    spacing + 2.0 * reach + cfg.fastener().clearance + 2.0 * cfg.cradle_hole_edge_margin
+}
+
+/// XY offset from a mounting slot's center to the centers of its round
+/// ends: half the `cradle_slot_travel` along `cradle_slot_axis`.
+pub fn slot_reach(cfg: &Config) -> (f64, f64) {
+    let half = cfg.cradle_slot_travel / 2.0;
+    match cfg.cradle_slot_axis {
+        SlotAxis::X => (half, 0.0),
+        SlotAxis::Y => (0.0, half),
+    }
+}
+
+/// One cradle mounting slot, centered on the origin and long enough to cut
+/// through a plate `thickness` thick: the fastener clearance wide, plus
+/// `cradle_slot_travel` along `cradle_slot_axis`. The frame cuts the same
+/// slots under the cradle's.
+pub fn mount_slot(cfg: &Config, name: &str, thickness: f64) -> Part {
+    let clearance = cfg.fastener().clearance;
+    let slot = geometry::obround(name, clearance + cfg.cradle_slot_travel, clearance, geometry::through_length(thickness), geometry::segments(cfg, cfg.segments_hole));
+    match cfg.cradle_slot_axis {
+        SlotAxis::X => slot,
+        SlotAxis::Y => slot.rotate(0.0, 0.0, 90.0),
+    }
+}
+
+/// How much the mounting holes grew the base past its nominal length and
+/// width; zero in both when the nominal base already fits them.
+pub fn base_growth(cfg: &Config) -> (f64, f64) {
+    (
+        base_length(cfg) - (cradle_length(cfg) + 2.0 * cfg.cradle_base_margin),
+        base_width(cfg) - (cfg.vial_diameter + cfg.mm(20.0)),
+    )
+}
+
+/// XY centers of the base mounting slots in the cradle's own coordinates.
+pub fn mount_hole_positions(cfg: &Config) -> [(f64, f64); 4] {
+    let sx = cfg.cradle_mount_slot_spacing_x / 2.0;
+    let sy = cfg.cradle_mount_slot_spacing_y / 2.0;
+    [(-sx, -sy), (sx, -sy), (-sx, sy), (sx, sy)]
+}
 
 pub fn build(cfg: &Config) -> Part {
-    let cradle_length = cfg.vial_height - 3.5; // match Python: vial_height - small clearance
-    let base_width = cfg.vial_diameter + 20.0;
-    let m3_hole = 3.4;
+    let cradle_length = cradle_length(cfg);
+    let base_width = base_width(cfg);
 
     // Base plate
-    let base = centered_cube("base", cradle_length + 18.0, base_width, cfg.cradle_base_height);
+    let base = feature(centered_cube("base", base_length(cfg), base_width, cfg.cradle_base_height));
 
     // V-block body — tall block that will be cut to form the V
-    let v_body = centered_cube("v_body", cradle_length, base_width, cfg.cradle_v_block_height)
-        .translate(0.0, 0.0, cfg.cradle_base_height / 2.0 + cfg.cradle_v_block_height / 2.0);
-
-    // V-groove cut — approximate with two angled boxes rotated 45 degrees.
-    let cut_size = cfg.vial_diameter * 1.5;
-    let cut_block = centered_cube("cut", cradle_length + 2.0, cut_size, cut_size)
-        .rotate(45.0, 0.0, 0.0)
-        .translate(0.0, 0.0, cfg.cradle_base_height + cfg.cradle_v_block_height - cut_size * 0.35);
-
-    // Mounting holes — 4 holes at corners of the base
-    let hole = centered_cylinder("hole", m3_hole / 2.0, cfg.cradle_base_height + 2.0, 32);
-    let holes = hole
-        .linear_pattern(cfg.cradle_mount_slot_spacing_x, 0.0, 0.0, 2)
-        .linear_pattern(0.0, cfg.cradle_mount_slot_spacing_y, 0.0, 2)
-        .translate(
-            -cfg.cradle_mount_slot_spacing_x / 2.0,
-            -cfg.cradle_mount_slot_spacing_y / 2.0,
-            0.0,
-        );
-
-    (base + v_body) - cut_block - holes
+    let v_body = feature(centered_cube("v_body", cradle_length, base_width, cfg.cradle_v_block_height)
+        .translate(0.0, 0.0, cfg.cradle_base_height / 2.0 + cfg.cradle_v_block_height / 2.0));
+
+    let groove_length = geometry::through_length(cradle_length);
+    let body = match cfg.cradle_style {
+        CradleStyle::V => {
+            // V-groove — keep only the part of the body below a 90° V. Each
+            // face of the V is the top of its own large slab rotated 45°
+            // about X, so the faces meet at exactly 90° at the apex whatever
+            // the slab size.
+            let apex_z = groove_apex_z(cfg);
+            let slab = 4.0 * (base_width + cfg.cradle_base_height + cfg.cradle_v_block_height);
+            let offset = slab / (2.0 * 2f64.sqrt());
+            let face = centered_cube("v_face", groove_length + 2.0 * slab, slab, slab).rotate(V_HALF_ANGLE, 0.0, 0.0);
+            let end = centered_cube("v_end", slab, slab, slab);
+            let prism = feature(face.translate(0.0, offset, apex_z - offset)
+                + face.translate(0.0, -offset, apex_z - offset)
+                + end.translate(groove_length / 2.0 + slab / 2.0, 0.0, 0.0)
+                + end.translate(-groove_length / 2.0 - slab / 2.0, 0.0, 0.0));
+            (base + v_body) & prism
+        }
+        CradleStyle::Saddle => {
+            // Saddle — a half-cylinder of the seat radius around the vial
+            // axis, with a slot of the same width above it to the block top
+            // so the vial drops in.
+            let axis_z = vial_axis_z(cfg);
+            let radius = seat_radius(cfg);
+            let above = cfg.cradle_base_height / 2.0 + cfg.cradle_v_block_height - axis_z + geometry::HOLE_OVERCUT;
+            let saddle = feature(centered_cylinder("saddle", radius, groove_length, geometry::segments(cfg, cfg.segments_cylinder))
+                .rotate(0.0, 90.0, 0.0)
+                .translate(0.0, 0.0, axis_z)
+                + centered_cube("saddle_slot", groove_length, 2.0 * radius, above).translate(0.0, 0.0, axis_z + above / 2.0));
+            (base + v_body) - saddle
+        }
+    };
+
+    // Mounting slots — 4 at the corners of the base, for adjusting the
+    // cradle's position on the frame
+    let slot = mount_slot(cfg, "hole", cfg.cradle_base_height);
+    let holes = tagged(Role::MountingHole, slot.grid_pattern(2, 2, cfg.cradle_mount_slot_spacing_x, cfg.cradle_mount_slot_spacing_y, true));
+
+    // Label window — optional slot through the +Y wall, above the contact lines.
+    let mut cradle = body - holes;
+    if cfg.cradle_window {
+        let depth = base_width / 2.0 + geometry::HOLE_OVERCUT;
+        let window_z = cfg.cradle_base_height / 2.0 + cfg.cradle_window_offset + cfg.cradle_window_height / 2.0;
+        cradle = cradle - feature(centered_cube("window", cfg.cradle_window_width, depth, cfg.cradle_window_height)
+            .translate(0.0, depth / 2.0, window_z));
+    }
+    cradle.with_name("vial_cradle")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::contains_point;
+    use crate::config::load_config;
+    use nalgebra::Point3;
+
+    #[test]
+    fn groove_is_hollow_and_base_is_solid() {
+        let cfg = load_config(None).unwrap();
+        let cradle = build(&cfg);
+        assert!(!contains_point(&cradle, Point3::new(0.0, 0.0, block_top_z(&cfg) - 1.0)), "groove should be open");
+        let base_end = cradle_length(&cfg) / 2.0 + cfg.cradle_base_margin / 2.0;
+        assert!(contains_point(&cradle, Point3::new(base_end, 0.0, 0.0)), "base should be solid");
+    }
+
+    #[test]
+    fn groove_seats_the_vial_plus_clearance_tangentially() {
+        let mut cfg = load_config(None).unwrap();
+        cfg.vial_cradle_clearance = 0.1;
+        let cradle = build(&cfg);
+        let rod = |d: f64| {
+            centered_cylinder("rod", d / 2.0, cradle_length(&cfg) - 2.0, 128)
+                .rotate(0.0, 90.0, 0.0)
+                .translate(0.0, 0.0, vial_axis_z(&cfg))
+        };
+        let seat = cfg.vial_diameter + cfg.vial_cradle_clearance;
+        assert!((&cradle & &rod(seat)).volume() < 1e-3, "seat cylinder should only touch the faces");
+        assert!((&cradle & &rod(seat + 0.2)).volume() > 1.0, "anything wider should bind");
+        let nominal = Config { vial_cradle_clearance: 0.0, ..load_config(None).unwrap() };
+        assert!((vial_axis_z(&nominal) - vial_axis_z(&cfg)).abs() < 1e-9, "the vial axis stays put");
+        assert!(groove_apex_z(&cfg) < groove_apex_z(&nominal), "clearance deepens the groove");
+    }
+
+    #[test]
+    fn groove_faces_are_at_45_degrees_and_seat_the_vial_top_at_its_rise() {
+        let cfg = load_config(None).unwrap();
+        let mesh = crate::mesh::TriMesh::from_part(&build(&cfg));
+        // Groove faces lean in from both sides, facing up and towards the
+        // axis; nothing else on the cradle faces up at a slant.
+        let mut sides = [false; 2];
+        for tri in 0..mesh.triangles.len() {
+            let n = mesh.normal(tri);
+            if n.z < 0.01 || n.y.abs() < 0.01 || n.x.abs() > 1e-6 {
+                continue;
+            }
+            let half_angle = n.z.atan2(n.y.abs()).to_degrees();
+            assert!((half_angle - V_HALF_ANGLE).abs() < 0.5, "groove face at {half_angle:.3}° from vertical");
+            sides[usize::from(n.y > 0.0)] = true;
+        }
+        assert_eq!(sides, [true, true], "both faces of the V");
+        let vial_top = vial_axis_z(&cfg) + cfg.vial_diameter / 2.0;
+        assert!((vial_top - block_top_z(&cfg) - cfg.cradle_vial_rise).abs() < 1e-9);
+    }
+
+    #[test]
+    fn saddle_seats_the_vial_plus_clearance_around_its_lower_half() {
+        let cfg = Config { cradle_style: CradleStyle::Saddle, vial_cradle_clearance: 0.1, ..load_config(None).unwrap() };
+        assert_eq!(seat_radius(&cfg), (cfg.vial_diameter + cfg.vial_cradle_clearance) / 2.0);
+        let cradle = build(&cfg);
+        let rod = |d: f64| {
+            centered_cylinder("rod", d / 2.0, cradle_length(&cfg) - 2.0, 128)
+                .rotate(0.0, 90.0, 0.0)
+                .translate(0.0, 0.0, vial_axis_z(&cfg))
+        };
+        let seat = 2.0 * seat_radius(&cfg);
+        // The kernel's polygonal saddle sits just inside the true circle.
+        assert!((&cradle & &rod(seat - 0.02)).volume() < 1e-3, "seat cylinder should fit");
+        assert!((&cradle & &rod(seat + 0.2)).volume() > 1.0, "anything wider should bind");
+        assert!(contains_point(&cradle, Point3::new(0.0, 0.0, groove_apex_z(&cfg) - 0.1)), "saddle bottom is solid below");
+        assert!(!contains_point(&cradle, Point3::new(0.0, 0.0, groove_apex_z(&cfg) + 0.1)));
+        assert_eq!(vial_axis_z(&cfg), vial_axis_z(&load_config(None).unwrap()), "the vial axis stays put");
+    }
+
+    #[test]
+    fn taller_vial_lengthens_the_cradle() {
+        let mut cfg = load_config(None).unwrap();
+        let short = build(&cfg).bounding_box();
+        cfg.vial_height += 10.0;
+        let long = build(&cfg).bounding_box();
+        let length = |(min, max): ([f64; 3], [f64; 3])| max[0] - min[0];
+        assert!((length(short) - base_length(&load_config(None).unwrap())).abs() < 1e-6);
+        assert!((length(long) - length(short) - 10.0).abs() < 1e-6, "base grows with the vial");
+    }
+
+    #[test]
+    fn mount_slots_run_along_the_chosen_axis() {
+        let mut cfg = load_config(None).unwrap();
+        let clearance = cfg.fastener().clearance;
+        let size = |cfg: &Config| {
+            let (min, max) = mount_slot(cfg, "slot", 5.0).bounding_box();
+            (max[0] - min[0], max[1] - min[1])
+        };
+        let (x, y) = size(&cfg);
+        assert!((x - clearance - cfg.cradle_slot_travel).abs() < 1e-6 && (y - clearance).abs() < 1e-6, "{x} x {y}");
+        cfg.cradle_slot_axis = SlotAxis::Y;
+        let (x, y) = size(&cfg);
+        assert!((x - clearance).abs() < 1e-6 && (y - clearance - cfg.cradle_slot_travel).abs() < 1e-6, "{x} x {y}");
+        assert_eq!(crate::features::count_tagged(Role::MountingHole, || build(&cfg)), 4);
+    }
+
+    #[test]
+    fn base_grows_to_keep_the_hole_edge_margin() {
+        let mut cfg = load_config(None).unwrap();
+        assert_eq!(base_growth(&cfg), (0.0, 0.0));
+        cfg.cradle_mount_slot_spacing_x = 60.0;
+        let (grown_x, grown_y) = base_growth(&cfg);
+        assert!(grown_x > 0.0 && grown_y == 0.0);
+        let (min, max) = build(&cfg).bounding_box();
+        let edge = (max[0] - min[0]) / 2.0 - cfg.cradle_mount_slot_spacing_x / 2.0 - cfg.cradle_slot_travel / 2.0 - cfg.fastener().clearance / 2.0;
+        assert!((edge - cfg.cradle_hole_edge_margin).abs() < 1e-5, "{edge}mm outside the holes");
+    }
 }
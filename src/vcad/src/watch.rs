@@ -0,0 +1,24 @@
+//! File watching for live rebuilds.
+//!
+//! Polls the modification time rather than pulling in a platform file
+//! notification crate; a half-second delay is fine for config edits.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Call `on_change` every time the file at `path` is modified. Never returns.
+pub fn on_change(path: &Path, mut on_change: impl FnMut()) -> ! {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut last: Option<SystemTime> = modified(path);
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let current = modified(path);
+        if current.is_some() && current != last {
+            last = current;
+            println!("\n{} changed, rebuilding...", path.display());
+            on_change();
+        }
+    }
+}